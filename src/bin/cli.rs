@@ -0,0 +1,468 @@
+//! # Workspace tools CLI
+//!
+//! A thin command line layer over the library, for shell scripts and CI images without node.
+//! Every command prints a table, or json with `--json`, and exits non-zero on errors.
+#![allow(clippy::all)]
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::exit;
+
+use workspace_node_tools::bumps::{apply_bumps, Bump, BumpOptions};
+use workspace_node_tools::changes::{
+    add_change, changes_file_exist, changes_status, get_change, init_changes, remove_change, Change,
+};
+use workspace_node_tools::conventional::{
+    get_conventional_for_package, ConventionalPackageOptions,
+};
+use workspace_node_tools::errors::WorkspaceError;
+use workspace_node_tools::git::git_current_branch;
+use workspace_node_tools::packages::{
+    get_changed_packages, get_package_info, get_packages, ChangedPackagesOptions,
+};
+use workspace_node_tools::paths::get_project_root_path;
+use workspace_node_tools::reports::{get_release_readiness, render_release_readiness};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "workspace-tools",
+    version,
+    about = "Node workspace version tools"
+)]
+struct Cli {
+    /// Directory inside the project, defaults to the current directory
+    #[arg(long, global = true)]
+    cwd: Option<String>,
+
+    /// Print json instead of a table
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Packages of the workspace
+    Packages {
+        #[command(subcommand)]
+        command: PackagesCommand,
+    },
+    /// List the packages changed since a git sha, branch or tag
+    Changed {
+        #[arg(long, default_value = "main")]
+        since: String,
+
+        /// Ignore changed files matched by the .gitignore rules
+        #[arg(long)]
+        apply_gitignore: bool,
+
+        /// Ignore changed files in the publishConfig.directory of the packages
+        #[arg(long)]
+        exclude_publish_directory: bool,
+
+        /// Ignore packages whose only changes are a changelog and a version bump
+        #[arg(long)]
+        ignore_release_artifacts: bool,
+    },
+    /// Pending changes of the branches
+    Changes {
+        #[command(subcommand)]
+        command: ChangesCommand,
+    },
+    /// Version bumps
+    Bump {
+        #[command(subcommand)]
+        command: BumpCommand,
+    },
+    /// Generate the changelog of the next release of a package, nothing is written
+    Changelog {
+        package: String,
+
+        /// Version of the release, defaults to the package version
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Release readiness report of the pending changes, markdown or json
+    Readiness,
+}
+
+#[derive(Debug, Subcommand)]
+enum PackagesCommand {
+    /// List the packages of the workspace
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+enum ChangesCommand {
+    /// Add a change of a package to the current branch
+    Add {
+        package: String,
+
+        #[arg(long, value_enum, default_value_t = ReleaseAs::Patch)]
+        release_as: ReleaseAs,
+
+        /// Deploy environments, comma separated
+        #[arg(long, value_delimiter = ',', default_value = "production")]
+        deploy: Vec<String>,
+    },
+    /// Remove the changes of a branch
+    Remove { branch: String },
+    /// Show the changes of a branch, defaults to the current branch
+    Show {
+        #[arg(long)]
+        branch: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum BumpCommand {
+    /// Show the current and next version of every pending change, nothing is written
+    Plan,
+    /// Apply the changes of a branch: write versions and changelogs, commit and tag
+    Apply {
+        /// Branch of the changes, defaults to the current branch
+        #[arg(long)]
+        branch: Option<String>,
+
+        #[arg(long, default_value = "main")]
+        since: String,
+
+        /// Also bump the dependents of the bumped packages
+        #[arg(long)]
+        sync_deps: bool,
+
+        #[arg(long)]
+        push: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ReleaseAs {
+    Major,
+    Minor,
+    Patch,
+    Snapshot,
+}
+
+impl From<ReleaseAs> for Bump {
+    fn from(release_as: ReleaseAs) -> Self {
+        match release_as {
+            ReleaseAs::Major => Bump::Major,
+            ReleaseAs::Minor => Bump::Minor,
+            ReleaseAs::Patch => Bump::Patch,
+            ReleaseAs::Snapshot => Bump::Snapshot,
+        }
+    }
+}
+
+/// Output of a command, json or a table with a header row
+struct Output<T: Serialize> {
+    value: T,
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl<T: Serialize> Output<T> {
+    fn print(&self, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if json {
+            println!("{}", serde_json::to_string_pretty(&self.value)?);
+            return Ok(());
+        }
+
+        let widths = self.header.iter().enumerate().map(|(index, title)| {
+            self.rows
+                .iter()
+                .map(|row| row[index].len())
+                .chain([title.len()])
+                .max()
+                .unwrap_or(0)
+        });
+        let widths = widths.collect::<Vec<usize>>();
+
+        let format_row = |cells: Vec<String>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        println!(
+            "{}",
+            format_row(
+                self.header
+                    .iter()
+                    .map(|title| title.to_uppercase())
+                    .collect()
+            )
+        );
+
+        for row in &self.rows {
+            println!("{}", format_row(row.to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
+fn release_as_name(bump: &Bump) -> String {
+    format!("{:?}", bump).to_lowercase()
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let root = get_project_root_path(cli.cwd.as_ref().map(PathBuf::from))
+        .ok_or_else(|| WorkspaceError::FileNotFound(cli.cwd.unwrap_or(String::from("."))))?;
+    let cwd = Some(root.to_string());
+    let current_branch = || git_current_branch(cwd.to_owned()).unwrap_or(String::from("main"));
+
+    match cli.command {
+        Commands::Packages {
+            command: PackagesCommand::List,
+        } => {
+            let packages = get_packages(cwd.to_owned())?;
+            let rows = packages
+                .iter()
+                .map(|package| {
+                    vec![
+                        package.name.to_string(),
+                        package.version.to_string(),
+                        package.package_relative_path.to_string(),
+                        package.private.to_string(),
+                    ]
+                })
+                .collect();
+
+            Output {
+                value: packages,
+                header: vec!["name", "version", "path", "private"],
+                rows,
+            }
+            .print(cli.json)
+        }
+        Commands::Changed {
+            since,
+            apply_gitignore,
+            exclude_publish_directory,
+            ignore_release_artifacts,
+        } => {
+            let packages = get_changed_packages(
+                Some(since),
+                cwd.to_owned(),
+                &ChangedPackagesOptions {
+                    apply_gitignore: Some(apply_gitignore),
+                    exclude_publish_directory: Some(exclude_publish_directory),
+                    ignore_release_artifacts: Some(ignore_release_artifacts),
+                },
+            )?;
+            let rows = packages
+                .iter()
+                .map(|package| {
+                    vec![
+                        package.name.to_string(),
+                        package.version.to_string(),
+                        package.changed_files.len().to_string(),
+                    ]
+                })
+                .collect();
+
+            Output {
+                value: packages,
+                header: vec!["name", "version", "changed files"],
+                rows,
+            }
+            .print(cli.json)
+        }
+        Commands::Changes { command } => match command {
+            ChangesCommand::Add {
+                package,
+                release_as,
+                deploy,
+            } => {
+                if get_package_info(package.to_string(), cwd.to_owned())?.is_none() {
+                    return Err(WorkspaceError::PackageNotFound(package).into());
+                }
+
+                if !changes_file_exist(cwd.to_owned(), None) {
+                    init_changes(cwd.to_owned(), &None);
+                }
+
+                let change = Change {
+                    package,
+                    release_as: release_as.into(),
+                    deploy,
+                    exact_version: None,
+                };
+
+                if !add_change(&change, cwd.to_owned(), None) {
+                    return Err(WorkspaceError::InvalidChanges(format!(
+                        "unable to add the change of {}",
+                        change.package
+                    ))
+                    .into());
+                }
+
+                Output {
+                    rows: vec![vec![
+                        change.package.to_string(),
+                        release_as_name(&change.release_as),
+                        change.deploy.join(","),
+                    ]],
+                    value: change,
+                    header: vec!["package", "release as", "deploy"],
+                }
+                .print(cli.json)
+            }
+            ChangesCommand::Remove { branch } => {
+                if !remove_change(branch.to_string(), cwd.to_owned(), None) {
+                    return Err(WorkspaceError::InvalidChanges(format!(
+                        "no changes for branch {}",
+                        branch
+                    ))
+                    .into());
+                }
+
+                Output {
+                    rows: vec![vec![branch.to_string()]],
+                    value: branch,
+                    header: vec!["removed branch"],
+                }
+                .print(cli.json)
+            }
+            ChangesCommand::Show { branch } => {
+                let branch = branch.unwrap_or_else(current_branch);
+                let changes = get_change(branch, cwd.to_owned(), None);
+                let rows = changes
+                    .iter()
+                    .map(|change| {
+                        vec![
+                            change.package.to_string(),
+                            release_as_name(&change.release_as),
+                            change.deploy.join(","),
+                        ]
+                    })
+                    .collect();
+
+                Output {
+                    value: changes,
+                    header: vec!["package", "release as", "deploy"],
+                    rows,
+                }
+                .print(cli.json)
+            }
+        },
+        Commands::Bump { command } => match command {
+            BumpCommand::Plan => {
+                let status = changes_status(cwd.to_owned(), None)?;
+                let rows = status
+                    .branches
+                    .iter()
+                    .flat_map(|branch| {
+                        branch.packages.iter().map(|package| {
+                            vec![
+                                branch.branch.to_string(),
+                                package.package.to_string(),
+                                release_as_name(&package.release_as),
+                                package.current_version.to_owned().unwrap_or_default(),
+                                package.next_version.to_owned().unwrap_or_default(),
+                            ]
+                        })
+                    })
+                    .collect();
+
+                Output {
+                    value: status,
+                    header: vec!["branch", "package", "release as", "current", "next"],
+                    rows,
+                }
+                .print(cli.json)
+            }
+            BumpCommand::Apply {
+                branch,
+                since,
+                sync_deps,
+                push,
+            } => {
+                let branch = branch.unwrap_or_else(current_branch);
+                let changes = get_change(branch.to_string(), cwd.to_owned(), None);
+
+                if changes.is_empty() {
+                    return Err(WorkspaceError::InvalidChanges(format!(
+                        "no changes for branch {}",
+                        branch
+                    ))
+                    .into());
+                }
+
+                let bumps = apply_bumps(&BumpOptions {
+                    changes,
+                    since: Some(since),
+                    sync_deps: Some(sync_deps),
+                    push: Some(push),
+                    cwd: cwd.to_owned(),
+                    ..Default::default()
+                })?;
+                let rows = bumps
+                    .iter()
+                    .map(|bump| {
+                        vec![
+                            bump.package_info.name.to_string(),
+                            bump.from.to_string(),
+                            bump.to.to_string(),
+                        ]
+                    })
+                    .collect();
+
+                Output {
+                    value: bumps,
+                    header: vec!["package", "from", "to"],
+                    rows,
+                }
+                .print(cli.json)
+            }
+        },
+        Commands::Changelog { package, version } => {
+            let package_info = get_package_info(package.to_string(), cwd.to_owned())?
+                .ok_or(WorkspaceError::PackageNotFound(package))?;
+
+            let conventional = get_conventional_for_package(
+                &package_info,
+                None,
+                cwd.to_owned(),
+                &Some(ConventionalPackageOptions {
+                    version: Some(version.unwrap_or(package_info.version.to_string())),
+                    ..Default::default()
+                }),
+            )?;
+
+            match cli.json {
+                true => println!("{}", serde_json::to_string_pretty(&conventional)?),
+                false => println!("{}", conventional.changelog_output),
+            }
+
+            Ok(())
+        }
+        Commands::Readiness => {
+            match cli.json {
+                true => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&get_release_readiness(cwd.to_owned(), &None)?)?
+                ),
+                false => print!("{}", render_release_readiness(cwd.to_owned(), &None)?),
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("error: {}", error);
+        exit(1);
+    }
+}
@@ -5,26 +5,47 @@
 //! # Bumps
 //!
 //! This module is responsible for managing the bumps in the monorepo.
+use chrono::Utc;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use regex::Regex;
 use semver::{BuildMetadata, Prerelease, Version as SemVersion};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use std::collections::HashMap;
-use std::fs::OpenOptions;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{canonicalize, remove_dir_all, OpenOptions};
 use std::io::{BufWriter, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
 
 use crate::conventional::ConventionalPackage;
 
-use super::changes::{get_package_change, init_changes, Change};
-use super::conventional::{get_conventional_for_package, ConventionalPackageOptions};
+use super::changes::{get_package_change, init_changes, Change, ChangesOptions, ChangesStorage};
+use super::config::{read_release_config, RELEASE_CONFIG_FILE};
+use super::conventional::{
+    get_breaking_commits_for_package, get_conventional_for_package, ConventionalPackageOptions,
+    CHANGELOG_FILE,
+};
+use super::dependency::{DependencyGraph, Node};
+use super::errors::WorkspaceError;
 use super::git::{
-    git_add_all, git_all_files_changed_since_sha, git_commit, git_config, git_current_branch,
-    git_current_sha, git_fetch_all, git_push, git_tag,
+    commit_paths, format_publish_tag, get_all_files_changed_since_branch, get_commits_between,
+    get_commits_since, get_last_known_publish_tag_info_for_package_with_policy,
+    get_latest_publish_tag_version_in, get_next_prerelease_number, get_publish_tags,
+    get_remote_or_local_tags, git_all_files_changed_since_sha, git_config, git_current_branch,
+    git_current_sha, git_fetch_all_with_timeout, git_last_commit_sha_for_path,
+    git_path_unchanged_since, git_push, git_push_head_to_branch, git_show_file, git_tag,
+    git_tags_pointing_at, git_worktree_add, git_worktree_remove, is_valid_tag_format,
+    normalize_publish_tags, split_publish_tag, CommandLimits, Commit, CommitsSinceOptions,
+    GitError, HistoryMode, PrereleasePolicy, RemoteTags,
 };
-use super::packages::PackageInfo;
-use super::packages::{get_package_info, get_packages};
-use super::paths::get_project_root_path;
+use super::manager::PackageManagerError;
+use super::packages::{get_package_info, get_packages, is_release_artifact, publish_target};
+use super::packages::{DependencyKind, PackageInfo, PublishTarget};
+use super::paths::{get_project_root_path, is_within};
+use super::versions::update_versions_manifest;
 
 #[cfg(feature = "napi")]
 #[napi(string_enum)]
@@ -46,9 +67,251 @@ pub enum Bump {
     Snapshot,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Enum representing how the prerelease of a snapshot version is built.
+/// `Sha` appends the current commit (`alpha.0.<sha>`), `Timestamp` the current UTC time,
+/// `Counter` increments the previous `alpha.<n>` prerelease and `Custom` uses the
+/// given identifier as is (e.g. `build-1234` yields `1.2.3-build-1234`).
+pub enum SnapshotStrategy {
+    Sha,
+    Timestamp,
+    Counter,
+    Custom(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An error that represents a failed bump operation
+pub enum BumpError {
+    InvalidPrerelease(String),
+    InvalidExactVersion(String),
+    ManifestBehind {
+        package: String,
+        manifest_version: String,
+        tag_version: String,
+    },
+    VersionRegression {
+        package: String,
+        from: String,
+        to: String,
+    },
+    PublishFailed {
+        package: String,
+        reason: String,
+    },
+    CommitFailed(String),
+    InvalidConfig(String),
+    InvalidTagFormat(String),
+    Git(String),
+    PackageManager(String),
+}
+
+impl std::fmt::Display for BumpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BumpError::InvalidPrerelease(identifier) => {
+                write!(f, "Invalid prerelease identifier: {}", identifier)
+            }
+            BumpError::InvalidExactVersion(version) => {
+                write!(f, "Invalid exact version: {}", version)
+            }
+            BumpError::ManifestBehind {
+                package,
+                manifest_version,
+                tag_version,
+            } => write!(
+                f,
+                "Package {} version {} is behind its latest tag {}, set a version source to release it",
+                package, manifest_version, tag_version
+            ),
+            BumpError::VersionRegression { package, from, to } => write!(
+                f,
+                "Package {} would be released as {}, which isn't greater than its version {}",
+                package, to, from
+            ),
+            BumpError::PublishFailed { package, reason } => {
+                write!(f, "Failed to publish package {}: {}", package, reason)
+            }
+            BumpError::CommitFailed(reason) => write!(f, "Failed to commit the release: {}", reason),
+            BumpError::InvalidConfig(message) => write!(f, "Invalid config: {}", message),
+            BumpError::InvalidTagFormat(tag_format) => write!(
+                f,
+                "Invalid tag format: {}, it needs a {{name}} then a {{version}} placeholder",
+                tag_format
+            ),
+            BumpError::Git(message) => write!(f, "{}", message),
+            BumpError::PackageManager(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for BumpError {}
+
+impl From<GitError> for BumpError {
+    fn from(error: GitError) -> Self {
+        BumpError::Git(error.to_string())
+    }
+}
+
+impl From<PackageManagerError> for BumpError {
+    fn from(error: PackageManagerError) -> Self {
+        BumpError::PackageManager(error.to_string())
+    }
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum SnapshotShaSource {
+    Head,
+    PackageLastCommit,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, Copy, PartialEq)]
+/// Enum representing which commit sha a snapshot version embeds. `PackageLastCommit` uses
+/// the last commit that touched the package, so unchanged packages keep a stable snapshot.
+pub enum SnapshotShaSource {
+    Head,
+    PackageLastCommit,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct BumpRule {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub bump: Bump,
+    pub breaking: Option<Bump>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Struct representing a rule mapping conventional commits to a bump. `commit_type` `*` matches
+/// any type and a missing `scope` any scope. Breaking commits bump major unless the rule
+/// has a `breaking` bump.
+pub struct BumpRule {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub bump: Bump,
+    pub breaking: Option<Bump>,
+}
+
+/// Conventional commit type to bump mapping, opted in with `default_bump_rules`. It isn't
+/// applied without bump rules, a package without change is then bumped patch as before.
+pub const DEFAULT_BUMP_RULES: &[(&str, Bump)] = &[
+    ("feat", Bump::Minor),
+    ("fix", Bump::Patch),
+    ("*", Bump::Patch),
+];
+
+/// Commit types that are worth a release on their own, the default rules of `needs_release`.
+/// Chores, docs, tests and the like only ship with a release triggered by other commits.
+pub const RELEASE_BUMP_RULES: &[(&str, Bump)] = &[
+    ("feat", Bump::Minor),
+    ("fix", Bump::Patch),
+    ("perf", Bump::Patch),
+];
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct NeedsReleaseOptions {
+    pub bump_rules: Option<Vec<BumpRule>>,
+    pub history_mode: Option<HistoryMode>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub tag_format: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+/// Struct representing the options of `needs_release`. `bump_rules` default to
+/// `RELEASE_BUMP_RULES` and `history_mode` to `AllCommits`. The last release is the latest
+/// publish tag of the `tag_format` the `prerelease_policy` allows.
+pub struct NeedsReleaseOptions {
+    pub bump_rules: Option<Vec<BumpRule>>,
+    pub history_mode: Option<HistoryMode>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub tag_format: Option<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum ZeroMajorPolicy {
+    Standard,
+    BreakingBumpsMinor,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, Copy, PartialEq)]
+/// Enum representing how bumps are applied to pre 1.0 (0.x) versions.
+/// With `BreakingBumpsMinor` a major bump produces a minor and a minor bump produces a patch.
+pub enum ZeroMajorPolicy {
+    Standard,
+    BreakingBumpsMinor,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum VersionAlignment {
+    Aligned,
+    ManifestAhead,
+    ManifestBehind,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, Copy, PartialEq)]
+/// Enum representing how the package.json version compares with the latest publish tag.
+/// `ManifestBehind` usually comes from a bad merge and blocks the release by default.
+pub enum VersionAlignment {
+    Aligned,
+    ManifestAhead,
+    ManifestBehind,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum VersionSource {
+    Manifest,
+    Tags,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, Copy, PartialEq)]
+/// Enum representing which version to bump from when the package.json and the latest
+/// publish tag disagree.
+pub enum VersionSource {
+    Manifest,
+    Tags,
+}
+
 #[cfg(feature = "napi")]
 #[napi(object)]
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct VersionTagAlignment {
+    pub package: String,
+    pub manifest_version: String,
+    pub tag_version: Option<String>,
+    pub alignment: VersionAlignment,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Struct representing the alignment of a package version with its latest publish tag.
+/// Packages never released have no tag version and are aligned.
+pub struct VersionTagAlignment {
+    pub package: String,
+    pub manifest_version: String,
+    pub tag_version: Option<String>,
+    pub alignment: VersionAlignment,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub struct BumpOptions {
     pub changes: Vec<Change>,
     pub since: Option<String>,
@@ -58,10 +321,32 @@ pub struct BumpOptions {
     pub sync_deps: Option<bool>,
     pub push: Option<bool>,
     pub cwd: Option<String>,
+    pub include_packages: Option<Vec<String>>,
+    pub exclude_packages: Option<Vec<String>>,
+    pub changes_path: Option<String>,
+    pub collect_metrics: Option<bool>,
+    pub zero_major_policy: Option<ZeroMajorPolicy>,
+    pub use_temp_worktree: Option<bool>,
+    pub version_source: Option<VersionSource>,
+    pub train_tag: Option<TrainTagOptions>,
+    pub protect_breaking_commits: Option<bool>,
+    pub versions_manifest: Option<String>,
+    pub snapshot_sha_source: Option<SnapshotShaSource>,
+    pub single_commit: Option<bool>,
+    pub write_changelog: Option<bool>,
+    pub bump_rules: Option<Vec<BumpRule>>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub ignore_release_artifacts: Option<bool>,
+    pub sync_dep_kinds: Option<Vec<DependencyKind>>,
+    pub channels: Option<Vec<ChannelRule>>,
+    pub skip_empty: Option<bool>,
+    pub history_mode: Option<HistoryMode>,
+    pub changelog_file: Option<String>,
+    pub tag_format: Option<String>,
 }
 
 #[cfg(not(feature = "napi"))]
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 /// Struct representing the options for the bump operation.
 pub struct BumpOptions {
     pub changes: Vec<Change>,
@@ -72,16 +357,43 @@ pub struct BumpOptions {
     pub sync_deps: Option<bool>,
     pub push: Option<bool>,
     pub cwd: Option<String>,
+    pub include_packages: Option<Vec<String>>,
+    pub exclude_packages: Option<Vec<String>>,
+    pub changes_path: Option<String>,
+    pub collect_metrics: Option<bool>,
+    pub zero_major_policy: Option<ZeroMajorPolicy>,
+    pub use_temp_worktree: Option<bool>,
+    pub version_source: Option<VersionSource>,
+    pub train_tag: Option<TrainTagOptions>,
+    pub protect_breaking_commits: Option<bool>,
+    pub versions_manifest: Option<String>,
+    pub snapshot_sha_source: Option<SnapshotShaSource>,
+    pub single_commit: Option<bool>,
+    pub write_changelog: Option<bool>,
+    pub bump_rules: Option<Vec<BumpRule>>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub ignore_release_artifacts: Option<bool>,
+    pub sync_dep_kinds: Option<Vec<DependencyKind>>,
+    pub channels: Option<Vec<ChannelRule>>,
+    pub skip_empty: Option<bool>,
+    pub history_mode: Option<HistoryMode>,
+    pub changelog_file: Option<String>,
+    pub tag_format: Option<String>,
 }
 
 #[cfg(not(feature = "napi"))]
 #[derive(Debug, Clone, Deserialize, Serialize)]
-/// Struct representing the bump package.
+/// Struct representing the bump package. `changelog` is the generated changelog of the
+/// release, only set by `apply_bumps`. `dist_tag` is the npm dist-tag of the release channel.
+/// `warnings` are the issues that didn't block the bump (e.g. a manifest ahead of its tags).
 pub struct BumpPackage {
     pub from: String,
     pub to: String,
     pub package_info: PackageInfo,
     pub conventional_commits: Value,
+    pub changelog: Option<String>,
+    pub dist_tag: Option<String>,
+    pub warnings: Vec<String>,
 }
 
 #[cfg(feature = "napi")]
@@ -92,6 +404,9 @@ pub struct BumpPackage {
     pub to: String,
     pub package_info: PackageInfo,
     pub conventional_commits: Value,
+    pub changelog: Option<String>,
+    pub dist_tag: Option<String>,
+    pub warnings: Vec<String>,
 }
 
 #[cfg(not(feature = "napi"))]
@@ -119,6 +434,170 @@ pub struct RecommendBumpPackage {
     pub deploy_to: Vec<String>,
 }
 
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PackageReleaseMetrics {
+    pub package: String,
+    pub duration_ms: u32,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Struct representing the time spent releasing a package.
+pub struct PackageReleaseMetrics {
+    pub package: String,
+    pub duration_ms: u32,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct ReleaseMetrics {
+    pub total_duration_ms: u32,
+    pub packages: Vec<PackageReleaseMetrics>,
+    pub commits: u32,
+    pub tags: u32,
+    pub pushes: u32,
+    pub warnings: u32,
+    pub changelog_bytes: u32,
+    pub git_commands: HashMap<String, u32>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+/// Struct representing the metrics of a release run. `git_commands` maps each
+/// git subcommand to the number of times it was invoked.
+pub struct ReleaseMetrics {
+    pub total_duration_ms: u32,
+    pub packages: Vec<PackageReleaseMetrics>,
+    pub commits: u32,
+    pub tags: u32,
+    pub pushes: u32,
+    pub warnings: u32,
+    pub changelog_bytes: u32,
+    pub git_commands: HashMap<String, u32>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ChannelRule {
+    pub branch: String,
+    pub prerelease_id: Option<String>,
+    pub dist_tag: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Struct representing the release channel of a branch. Releases from a channel with a
+/// `prerelease_id` are prereleases (e.g. `2.0.0-next.3`), the others are stable. `dist_tag`
+/// is the npm dist-tag the releases are published under.
+pub struct ChannelRule {
+    pub branch: String,
+    pub prerelease_id: Option<String>,
+    pub dist_tag: Option<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct TrainTagOptions {
+    pub format: String,
+    pub message_template: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Struct representing the options of the release train tag, a single tag marking the
+/// whole release (e.g. `release/{date}.{sequence}`).
+pub struct TrainTagOptions {
+    pub format: String,
+    pub message_template: Option<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct TrainTag {
+    pub name: String,
+    pub packages: Vec<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Struct representing a release train tag and the packages (`name@version`) it includes.
+pub struct TrainTag {
+    pub name: String,
+    pub packages: Vec<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum ReleaseStatus {
+    Released,
+    AlreadyReleased,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, Copy, PartialEq)]
+/// Enum representing the outcome of a package in a release run. `AlreadyReleased` packages
+/// were released by a previous run (e.g. a retried CI job) and weren't touched.
+pub enum ReleaseStatus {
+    Released,
+    AlreadyReleased,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PackageReleaseStatus {
+    pub package: String,
+    pub version: String,
+    pub status: ReleaseStatus,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Struct representing the release outcome of a package.
+pub struct PackageReleaseStatus {
+    pub package: String,
+    pub version: String,
+    pub status: ReleaseStatus,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReleaseResult {
+    pub bumps: Vec<BumpPackage>,
+    pub metrics: Option<ReleaseMetrics>,
+    pub train_tag: Option<TrainTag>,
+    pub packages: Vec<PackageReleaseStatus>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Struct representing the result of applying bumps. Metrics are only
+/// available when `collect_metrics` is enabled.
+pub struct ReleaseResult {
+    pub bumps: Vec<BumpPackage>,
+    pub metrics: Option<ReleaseMetrics>,
+    pub train_tag: Option<TrainTag>,
+    pub packages: Vec<PackageReleaseStatus>,
+}
+
+impl ReleaseMetrics {
+    fn record_git_command(&mut self, subcommand: &str, success: bool) {
+        *self.git_commands.entry(subcommand.to_string()).or_insert(0) += 1;
+
+        if !success {
+            self.warnings += 1;
+        }
+    }
+}
+
 impl Bump {
     /// Bumps the version of the package to major.
     fn bump_major(version: String) -> SemVersion {
@@ -151,67 +630,313 @@ impl Bump {
     }
 
     /// Bumps the version of the package to snapshot appending the sha to the version.
-    fn bump_snapshot(version: String) -> SemVersion {
-        let sha = git_current_sha(None);
-        let alpha = format!("alpha.{}.{}", 0, sha);
+    /// Without a sha the current commit of the process working directory is used.
+    fn bump_snapshot(version: String, sha: Option<String>) -> SemVersion {
+        let strategy = match sha {
+            Some(sha) => SnapshotStrategy::Custom(format!("alpha.0.{}", sha)),
+            None => SnapshotStrategy::Sha,
+        };
+
+        Bump::bump_snapshot_with_strategy(version.to_string(), &strategy, None).unwrap_or_else(
+            |_| {
+                let mut sem_version = SemVersion::parse(&version).unwrap();
+                sem_version.pre = Prerelease::EMPTY;
+                sem_version.build = BuildMetadata::EMPTY;
+                sem_version
+            },
+        )
+    }
 
+    /// Bumps the version of the package to snapshot using the given strategy for the prerelease.
+    pub fn bump_snapshot_with_strategy(
+        version: String,
+        strategy: &SnapshotStrategy,
+        cwd: Option<String>,
+    ) -> Result<SemVersion, BumpError> {
         let mut sem_version = SemVersion::parse(&version).unwrap();
-        sem_version.pre = Prerelease::new(alpha.as_str()).unwrap_or(Prerelease::EMPTY);
+
+        let prerelease = match strategy {
+            SnapshotStrategy::Sha => format!("alpha.{}.{}", 0, git_current_sha(cwd)),
+            SnapshotStrategy::Timestamp => {
+                format!("alpha.{}", chrono::Utc::now().format("%Y%m%d%H%M%S"))
+            }
+            SnapshotStrategy::Counter => {
+                let counter = sem_version
+                    .pre
+                    .as_str()
+                    .strip_prefix("alpha.")
+                    .and_then(|counter| counter.parse::<u64>().ok())
+                    .map(|counter| counter + 1)
+                    .unwrap_or(0);
+
+                format!("alpha.{}", counter)
+            }
+            SnapshotStrategy::Custom(identifier) => identifier.to_string(),
+        };
+
+        sem_version.pre = match prerelease.is_empty() {
+            true => return Err(BumpError::InvalidPrerelease(prerelease)),
+            false => Prerelease::new(prerelease.as_str())
+                .map_err(|_| BumpError::InvalidPrerelease(prerelease.to_string()))?,
+        };
         sem_version.build = BuildMetadata::EMPTY;
-        sem_version
+
+        Ok(sem_version)
+    }
+}
+
+/// Bumps the version according to the release type and the zero major policy.
+/// Under `ZeroMajorPolicy::BreakingBumpsMinor` a 0.x version bumps one level lower.
+/// Snapshots embed `snapshot_sha`, resolved by the caller for the right repository.
+pub(crate) fn bump_version(
+    version: String,
+    release_as: Bump,
+    zero_major_policy: Option<ZeroMajorPolicy>,
+    snapshot_sha: Option<String>,
+) -> SemVersion {
+    let is_zero_major = SemVersion::parse(&version).unwrap().major == 0;
+
+    let release_as = match (zero_major_policy, release_as) {
+        (Some(ZeroMajorPolicy::BreakingBumpsMinor), Bump::Major) if is_zero_major => Bump::Minor,
+        (Some(ZeroMajorPolicy::BreakingBumpsMinor), Bump::Minor) if is_zero_major => Bump::Patch,
+        _ => release_as,
+    };
+
+    match release_as {
+        Bump::Major => Bump::bump_major(version),
+        Bump::Minor => Bump::bump_minor(version),
+        Bump::Patch => Bump::bump_patch(version),
+        Bump::Snapshot => Bump::bump_snapshot(version, snapshot_sha),
+    }
+}
+
+/// Finds the release channel of a branch.
+pub fn get_channel_rule(
+    channels: &Option<Vec<ChannelRule>>,
+    branch: &String,
+) -> Option<ChannelRule> {
+    channels
+        .as_ref()
+        .and_then(|channels| channels.iter().find(|channel| &channel.branch == branch))
+        .map(|channel| channel.to_owned())
+}
+
+/// Refuses the release channels whose prerelease id can't be used in a version, before any
+/// package is bumped.
+pub fn validate_channel_rules(channels: &Option<Vec<ChannelRule>>) -> Result<(), BumpError> {
+    channels
+        .iter()
+        .flatten()
+        .filter_map(|channel| channel.prerelease_id.as_ref())
+        .try_for_each(|prerelease_id| {
+            match prerelease_id.is_empty()
+                || Prerelease::new(&format!("{}.1", prerelease_id)).is_err()
+            {
+                true => Err(BumpError::InvalidPrerelease(prerelease_id.to_string())),
+                false => Ok(()),
+            }
+        })
+}
+
+/// Computes the version of a release on a channel. The bump is applied to the stable base of the
+/// version, a prerelease only moves its base when the bump goes beyond it (e.g. a minor bump of
+/// `1.1.0-next.1` stays on `1.1.0`). Prerelease channels append `-<prerelease_id>.<n>`, with `n`
+/// following the publish tags of that base, stable channels release the base. Fails with
+/// `InvalidPrerelease` when the prerelease id of the channel can't be used in a version.
+pub fn get_channel_version(
+    package_name: &String,
+    version: &String,
+    release_as: Bump,
+    zero_major_policy: Option<ZeroMajorPolicy>,
+    channel: &ChannelRule,
+    cwd: Option<String>,
+    tag_format: &Option<String>,
+) -> Result<SemVersion, BumpError> {
+    let current = SemVersion::parse(version).unwrap();
+    let stable = SemVersion::new(current.major, current.minor, current.patch);
+
+    let mut base = match (current.pre.is_empty(), release_as) {
+        (true, _) => bump_version(version.to_string(), release_as, zero_major_policy, None),
+        (false, Bump::Major) if current.minor != 0 || current.patch != 0 => {
+            Bump::bump_major(stable.to_string())
+        }
+        (false, Bump::Minor) if current.patch != 0 => Bump::bump_minor(stable.to_string()),
+        (false, _) => stable,
+    };
+    base.pre = Prerelease::EMPTY;
+    base.build = BuildMetadata::EMPTY;
+
+    if let Some(ref prerelease_id) = channel.prerelease_id {
+        let number = get_next_prerelease_number(
+            package_name,
+            &base.to_string(),
+            prerelease_id,
+            cwd,
+            tag_format,
+        )?;
+
+        base.pre = Prerelease::new(&format!("{}.{}", prerelease_id, number))
+            .map_err(|_| BumpError::InvalidPrerelease(prerelease_id.to_string()))?;
     }
+
+    Ok(base)
 }
 
+/// Recommends the bump of a package from its change, or without one from its commits with the
+/// `bump_rules` (patch without rules). Protected breaking commits take the `breaking` bump of
+/// their rule, major by default. Fails with
+/// `InvalidExactVersion` when the exact version of the change isn't a valid semver, and with
+/// `VersionRegression` when the recommended version isn't greater than the current one.
 pub fn get_package_recommend_bump(
     package_info: &PackageInfo,
     root: &String,
     options: Option<BumpOptions>,
-) -> RecommendBumpPackage {
+) -> Result<RecommendBumpPackage, BumpError> {
     let ref current_branch =
         git_current_branch(Some(root.to_string())).unwrap_or(String::from("origin/main"));
 
+    get_package_recommend_bump_on_branch(package_info, root, options, current_branch)
+}
+
+/// Recommends the bump of a package the same way as `get_package_recommend_bump`, for the given
+/// branch instead of the checked out one.
+fn get_package_recommend_bump_on_branch(
+    package_info: &PackageInfo,
+    root: &String,
+    options: Option<BumpOptions>,
+    current_branch: &String,
+) -> Result<RecommendBumpPackage, BumpError> {
     let package_version = &package_info.version.to_string();
     let package_name = &package_info.name.to_string();
+
+    let settings = options.unwrap_or_default();
+
     let package_change = get_package_change(
         package_name.to_string(),
         current_branch.to_string(),
         Some(root.to_string()),
+        settings.changes_path.to_owned(),
     );
 
-    let settings = options.unwrap_or_else(|| BumpOptions {
-        changes: vec![],
-        since: None,
-        release_as: None,
-        fetch_all: None,
-        fetch_tags: None,
-        sync_deps: None,
-        push: None,
-        cwd: None,
-    });
-
     let ref since = settings.since.unwrap_or(String::from("origin/main"));
 
-    let release_as = settings
-        .release_as
-        .unwrap_or_else(|| match package_change.to_owned() {
-            Some(change) => change.release_as,
+    // Without a change, bump rules resolve the bump from the package commits
+    let release_as = match (settings.release_as, package_change.to_owned()) {
+        (Some(release_as), _) => release_as,
+        (None, Some(change)) => change.release_as,
+        (None, None) => match settings.bump_rules {
+            Some(ref rules) => get_commits_bump(
+                &get_package_commits(
+                    package_info,
+                    root,
+                    settings.history_mode,
+                    settings.prerelease_policy,
+                    &settings.tag_format,
+                )?,
+                rules,
+            )
+            .unwrap_or(Bump::Patch),
             None => Bump::Patch,
-        });
+        },
+    };
+
+    // Breaking commits are never skipped silently, they lead to a major bump unless a bump rule
+    // overrides it
+    let protect_breaking_commits = settings.protect_breaking_commits.unwrap_or(false);
+    let breaking_commits = match protect_breaking_commits {
+        true => get_breaking_commits_for_package(
+            package_info,
+            Some(root.to_string()),
+            protect_breaking_commits,
+            settings.prerelease_policy,
+            &settings.tag_format,
+        )?,
+        false => vec![],
+    };
+    let release_as = match breaking_commits.len() {
+        0 => release_as,
+        _ => {
+            let breaking_bump = match settings.bump_rules {
+                Some(ref rules) => {
+                    get_commits_bump(&breaking_commits, rules).unwrap_or(Bump::Major)
+                }
+                None => Bump::Major,
+            };
+
+            match bump_rank(&breaking_bump) > bump_rank(&release_as) {
+                true => breaking_bump,
+                false => release_as,
+            }
+        }
+    };
 
+    // Without a change, `Deploy:` trailers of the package commits route the deploy
     let deploy_to = match package_change.to_owned() {
         Some(change) => change.deploy,
-        None => vec![String::from("production")],
+        None => match get_deploy_trailers(
+            package_info,
+            root,
+            settings.prerelease_policy,
+            &settings.tag_format,
+        )? {
+            deploy_trailers if deploy_trailers.len() != 0 => deploy_trailers,
+            _ => vec![String::from("production")],
+        },
     };
 
     let fetch_all = settings.fetch_all.unwrap_or(false);
 
-    let semversion = match release_as {
-        Bump::Major => Bump::bump_major(package_version.to_string()),
-        Bump::Minor => Bump::bump_minor(package_version.to_string()),
-        Bump::Patch => Bump::bump_patch(package_version.to_string()),
-        Bump::Snapshot => Bump::bump_snapshot(package_version.to_string()),
+    // An exact version on the change short-circuits the computed bump, snapshots of a branch
+    // that isn't a release one stay snapshots
+    let exact_version = match release_as {
+        Bump::Snapshot => None,
+        _ => settings
+            .changes
+            .iter()
+            .find(|change| &change.package == package_name)
+            .or(package_change.as_ref())
+            .and_then(|change| change.exact_version.to_owned()),
+    };
+
+    let semversion = match exact_version {
+        Some(exact_version) => SemVersion::parse(&exact_version)
+            .map_err(|_| BumpError::InvalidExactVersion(exact_version.to_string()))?,
+        None => match get_channel_rule(&settings.channels, current_branch) {
+            // Snapshots stay snapshots, whatever the channel of the branch
+            Some(ref channel) if release_as != Bump::Snapshot => get_channel_version(
+                package_name,
+                package_version,
+                release_as,
+                settings.zero_major_policy,
+                channel,
+                Some(root.to_string()),
+                &settings.tag_format,
+            )?,
+            _ => {
+                let snapshot_sha = match release_as {
+                    Bump::Snapshot => match settings.snapshot_sha_source {
+                        Some(SnapshotShaSource::PackageLastCommit) => git_last_commit_sha_for_path(
+                            package_info.package_relative_path.to_string(),
+                            Some(root.to_string()),
+                        )
+                        .or_else(|| Some(git_current_sha(Some(root.to_string())))),
+                        _ => Some(git_current_sha(Some(root.to_string()))),
+                    },
+                    _ => None,
+                };
+
+                bump_version(
+                    package_version.to_string(),
+                    release_as,
+                    settings.zero_major_policy,
+                    snapshot_sha,
+                )
+            }
+        },
     };
 
+    validate_version_progression(package_name, package_version, &semversion, release_as)?;
+
     let changed_files = git_all_files_changed_since_sha(since.to_string(), Some(root.to_string()));
     let ref version = semversion.to_string();
 
@@ -222,68 +947,459 @@ pub fn get_package_recommend_bump(
         &Some(ConventionalPackageOptions {
             version: Some(version.to_string()),
             title: Some("# What changed?".to_string()),
+            protect_breaking_commits: settings.protect_breaking_commits.to_owned(),
+            prerelease_policy: settings.prerelease_policy.to_owned(),
+            history_mode: settings.history_mode.to_owned(),
+            changelog_file: settings.changelog_file.to_owned(),
+            tag_format: settings.tag_format.to_owned(),
+            ..Default::default()
         }),
-    );
+    )?;
 
-    RecommendBumpPackage {
+    Ok(RecommendBumpPackage {
         from: package_version.to_string(),
         to: version.to_string(),
         package_info: package_info.to_owned(),
         conventional: conventional.to_owned(),
         changed_files: changed_files.to_owned(),
         deploy_to: deploy_to.to_owned(),
-    }
+    })
 }
 
-/// Get bumps version of the package. If sync_deps is true, it will also sync the dependencies and dev-dependencies.
-pub fn get_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
-    let ref root = match options.cwd {
-        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
-        None => get_project_root_path(None).unwrap(),
-    };
-
-    if options.fetch_tags.is_some() {
-        git_fetch_all(Some(root.to_string()), options.fetch_tags)
-            .expect("No possible to fetch tags");
+/// Refuses a bump whose version isn't greater than the current one (e.g. a wrong exact version).
+/// Snapshots are exempt, they're prereleases of the current version by design.
+pub fn validate_version_progression(
+    package: &String,
+    from: &String,
+    to: &SemVersion,
+    release_as: Bump,
+) -> Result<(), BumpError> {
+    if release_as == Bump::Snapshot {
+        return Ok(());
     }
 
-    let since = match options.since {
-        Some(ref since) => since.to_string(),
-        None => String::from("origin/main"),
-    };
+    match SemVersion::parse(from) {
+        Ok(from_version) if to <= &from_version => Err(BumpError::VersionRegression {
+            package: package.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
 
-    let current_branch = git_current_branch(Some(root.to_string())).unwrap_or(String::from("main"));
+/// Builds the bump rules of `DEFAULT_BUMP_RULES`, to give as the `bump_rules` option.
+pub fn default_bump_rules() -> Vec<BumpRule> {
+    to_bump_rules(DEFAULT_BUMP_RULES)
+}
 
-    let ref packages = get_packages(Some(root.to_string()));
-    let changed_packages = packages
+/// Builds bump rules from commit type to bump pairs, any scope.
+fn to_bump_rules(rules: &[(&str, Bump)]) -> Vec<BumpRule> {
+    rules
         .iter()
-        .filter(|package| {
-            options
-                .changes
-                .iter()
-                .any(|change| change.package == package.name)
+        .map(|(commit_type, bump)| BumpRule {
+            commit_type: commit_type.to_string(),
+            scope: None,
+            bump: bump.to_owned(),
+            breaking: None,
         })
-        .map(|package| package.to_owned())
-        .collect::<Vec<PackageInfo>>();
+        .collect::<Vec<BumpRule>>()
+}
 
-    if changed_packages.len() == 0 {
-        return vec![];
+/// Order of the bumps, the highest bump of several commits wins
+fn bump_rank(bump: &Bump) -> u8 {
+    match bump {
+        Bump::Major => 3,
+        Bump::Minor => 2,
+        Bump::Patch => 1,
+        Bump::Snapshot => 0,
     }
+}
 
-    let mut bump_changes = HashMap::new();
-    let mut bump_dependencies = HashMap::new();
+/// Resolves the bump of a list of commits with the given rules. Each commit takes the bump of
+/// the first matching rule and the highest one is returned. Commits that aren't conventional
+/// or don't match any rule are ignored, None when no commit matched.
+pub fn get_commits_bump(commits: &Vec<Commit>, rules: &Vec<BumpRule>) -> Option<Bump> {
+    let subject_regex =
+        Regex::new(r"^(?P<type>[a-zA-Z]+)(?:\((?P<scope>[^)]*)\))?(?P<breaking>!)?:").unwrap();
 
-    for changed_package in changed_packages.iter() {
-        let change = options
-            .changes
-            .iter()
-            .find(|change| change.package == changed_package.name);
+    commits
+        .iter()
+        .filter_map(|commit| {
+            let captures = subject_regex.captures(&commit.subject)?;
+            let commit_type = &captures["type"];
+            let scope = captures.name("scope").map(|scope| scope.as_str());
+            let breaking = captures.name("breaking").is_some()
+                || commit.body.contains("BREAKING CHANGE:")
+                || commit.body.contains("BREAKING-CHANGE:");
+
+            let rule = rules.iter().find(|rule| {
+                (rule.commit_type == "*" || rule.commit_type.eq_ignore_ascii_case(commit_type))
+                    && match rule.scope {
+                        Some(ref rule_scope) => scope == Some(rule_scope.as_str()),
+                        None => true,
+                    }
+            })?;
 
-        if change.is_some() {
-            let release_as = match Some(current_branch.contains("main")) {
-                Some(true) => change.unwrap().release_as,
-                Some(false) | None => Bump::Snapshot,
-            };
+            match (breaking, rule.breaking) {
+                (true, Some(breaking_bump)) => Some(breaking_bump),
+                (true, None) => Some(Bump::Major),
+                (false, _) => Some(rule.bump),
+            }
+        })
+        .max_by_key(|bump| bump_rank(bump))
+}
+
+/// Get the commits of a package since its last publish tag (of the tag format, allowed by the
+/// prerelease policy), of the history mode (`AllCommits` by default).
+fn get_package_commits(
+    package_info: &PackageInfo,
+    root: &String,
+    history_mode: Option<HistoryMode>,
+    prerelease_policy: Option<PrereleasePolicy>,
+    tag_format: &Option<String>,
+) -> Result<Vec<Commit>, GitError> {
+    let hash = get_last_known_publish_tag_info_for_package_with_policy(
+        package_info,
+        Some(root.to_string()),
+        prerelease_policy,
+        tag_format,
+    )?
+    .map(|tag_info| tag_info.hash);
+
+    Ok(get_commits_since(
+        Some(root.to_string()),
+        hash,
+        Some(package_info.package_relative_path.to_string()),
+        &CommitsSinceOptions {
+            history_mode,
+            ..Default::default()
+        },
+    ))
+}
+
+/// Detects whether a package needs a release from its conventional commits alone, without a
+/// change. Returns the bump of the commits since its last publish tag, of the history mode,
+/// with the bump rules (`RELEASE_BUMP_RULES` by default). Breaking commits no rule matches bump
+/// major. None when no commit is worth a release. Fails when the publish tags can't be listed.
+pub fn needs_release(
+    package_info: &PackageInfo,
+    cwd: Option<String>,
+    options: Option<NeedsReleaseOptions>,
+) -> Result<Option<Bump>, GitError> {
+    let ref root = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+    let options = options.unwrap_or_default();
+
+    let commits = get_package_commits(
+        package_info,
+        root,
+        options.history_mode,
+        options.prerelease_policy,
+        &options.tag_format,
+    )?;
+
+    let mut rules = options
+        .bump_rules
+        .unwrap_or_else(|| to_bump_rules(RELEASE_BUMP_RULES));
+
+    // A catch-all rule without bump of its own only keeps the breaking commits
+    rules.push(BumpRule {
+        commit_type: String::from("*"),
+        scope: None,
+        bump: Bump::Snapshot,
+        breaking: Some(Bump::Major),
+    });
+
+    match get_commits_bump(&commits, &rules) {
+        Some(Bump::Snapshot) | None => Ok(None),
+        bump => Ok(bump),
+    }
+}
+
+/// Collects the environments of the `Deploy:` trailers (comma separated) in the package
+/// commits since its last publish tag.
+fn get_deploy_trailers(
+    package_info: &PackageInfo,
+    root: &String,
+    prerelease_policy: Option<PrereleasePolicy>,
+    tag_format: &Option<String>,
+) -> Result<Vec<String>, GitError> {
+    let mut environments =
+        get_package_commits(package_info, root, None, prerelease_policy, tag_format)?
+            .iter()
+            .flat_map(|commit| commit.trailers.to_owned())
+            .filter(|trailer| trailer.key.eq_ignore_ascii_case("deploy"))
+            .flat_map(|trailer| {
+                trailer
+                    .value
+                    .split(',')
+                    .map(|environment| environment.trim().to_string())
+                    .filter(|environment| !environment.is_empty())
+                    .collect::<Vec<String>>()
+            })
+            .collect::<Vec<String>>();
+
+    environments.sort();
+    environments.dedup();
+
+    Ok(environments)
+}
+
+/// Compares the package.json version of a package with its latest version among the tags.
+fn get_version_tag_alignment(package: &PackageInfo, tags: &[RemoteTags]) -> VersionTagAlignment {
+    let tag_version = get_latest_publish_tag_version_in(tags, &package.name);
+
+    let manifest = SemVersion::parse(&package.version).ok();
+    let tag = tag_version
+        .as_ref()
+        .and_then(|version| SemVersion::parse(version).ok());
+
+    let alignment = match (manifest, tag) {
+        (Some(manifest), Some(tag)) if manifest > tag => VersionAlignment::ManifestAhead,
+        (Some(manifest), Some(tag)) if manifest < tag => VersionAlignment::ManifestBehind,
+        _ => VersionAlignment::Aligned,
+    };
+
+    VersionTagAlignment {
+        package: package.name.to_string(),
+        manifest_version: package.version.to_string(),
+        tag_version,
+        alignment,
+    }
+}
+
+/// Compares the package.json version of every package with its latest publish tag version.
+pub fn check_version_tag_alignment(
+    cwd: Option<String>,
+) -> Result<Vec<VersionTagAlignment>, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref tags = get_publish_tags(Some(root.to_string()))?;
+
+    let alignments = get_packages(Some(root.to_string()))?
+        .iter()
+        .map(|package| get_version_tag_alignment(package, tags))
+        .collect::<Vec<VersionTagAlignment>>();
+
+    Ok(alignments)
+}
+
+/// Refuses the release of a package whose manifest is behind its latest tag,
+/// unless a version source was explicitly chosen.
+pub fn validate_version_tag_alignment(
+    alignments: &[VersionTagAlignment],
+    version_source: &Option<VersionSource>,
+) -> Result<(), BumpError> {
+    if version_source.is_some() {
+        return Ok(());
+    }
+
+    match alignments
+        .iter()
+        .find(|alignment| alignment.alignment == VersionAlignment::ManifestBehind)
+    {
+        Some(alignment) => Err(BumpError::ManifestBehind {
+            package: alignment.package.to_string(),
+            manifest_version: alignment.manifest_version.to_string(),
+            tag_version: alignment.tag_version.to_owned().unwrap_or_default(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Get bumps version of the package. If sync_deps is true, it will also sync the dependencies and dev-dependencies.
+/// Packages can be restricted with include_packages (allowlist) and exclude_packages (denylist).
+/// Fails with `ManifestBehind` when a package version is behind its latest publish tag and no
+/// version_source is set, a version ahead of its tag is only reported in the bump warnings.
+/// Packages whose only changes since `since` are release artifacts (changelog and version) are
+/// skipped, unless ignore_release_artifacts is false.
+/// The dependency kinds triggering a sync bump are set with sync_dep_kinds (default `[Prod]`).
+/// On a branch of one of the release channels, versions follow the channel (see
+/// `get_channel_version`) and carry its dist-tag, other branches than main get snapshots.
+/// Channels are validated first, an invalid prerelease id fails with `InvalidPrerelease`.
+/// With skip_empty, packages without conventional commits since their last release are skipped.
+/// Options not given are read from the release config of the project (see `ReleaseConfig`).
+/// Fails with the error of the first package whose bump can't be computed.
+pub fn get_bumps(options: &BumpOptions) -> Result<Vec<BumpPackage>, BumpError> {
+    let ref root = match options.cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let current_branch = git_current_branch(Some(root.to_string())).unwrap_or(String::from("main"));
+
+    get_bumps_on_branch(&with_release_config(options, root)?, &current_branch)
+}
+
+/// Fills the options not given with the release config of the project, explicit options win.
+/// Fails with `InvalidConfig` when the release config can't be read.
+fn with_release_config(options: &BumpOptions, root: &String) -> Result<BumpOptions, BumpError> {
+    let config = match read_release_config(Some(root.to_string())) {
+        Ok(config) => config,
+        Err(WorkspaceError::FileNotFound(_)) => return Ok(options.to_owned()),
+        Err(WorkspaceError::InvalidConfig(message)) => {
+            return Err(BumpError::InvalidConfig(message))
+        }
+        Err(error) => {
+            return Err(BumpError::InvalidConfig(format!(
+                "{}: {}",
+                RELEASE_CONFIG_FILE, error
+            )))
+        }
+    };
+
+    Ok(BumpOptions {
+        since: options.since.to_owned().or(config.default_branch),
+        sync_deps: options.sync_deps.or(config.sync_deps),
+        history_mode: options.history_mode.or(config.history_mode),
+        changelog_file: options.changelog_file.to_owned().or(config.changelog_file),
+        tag_format: options.tag_format.to_owned().or(config.tag_format),
+        bump_rules: options.bump_rules.to_owned().or(config.bump_rules),
+        exclude_packages: options
+            .exclude_packages
+            .to_owned()
+            .or(config.ignored_packages),
+        ..options.to_owned()
+    })
+}
+
+/// Get bumps version of the packages as if the given branch was checked out. The branch decides
+/// between a release and a snapshot, which matters when the checkout is a detached worktree.
+fn get_bumps_on_branch(
+    options: &BumpOptions,
+    current_branch: &String,
+) -> Result<Vec<BumpPackage>, BumpError> {
+    let BumpPlan {
+        mut bumps,
+        alignments,
+    } = plan_bumps_on_branch(options, current_branch)?;
+
+    validate_version_tag_alignment(&alignments, &options.version_source)?;
+    write_bumps(&mut bumps, options.sync_deps.unwrap_or(false));
+
+    Ok(bumps)
+}
+
+/// The bumps of a release and the version tag alignment of their packages, nothing written yet.
+struct BumpPlan {
+    bumps: Vec<BumpPackage>,
+    alignments: Vec<VersionTagAlignment>,
+}
+
+/// Computes the bumps of the packages on the given branch without writing them, nor validating
+/// the version tag alignment of the packages.
+fn plan_bumps_on_branch(
+    options: &BumpOptions,
+    current_branch: &String,
+) -> Result<BumpPlan, BumpError> {
+    let ref root = match options.cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    validate_channel_rules(&options.channels)?;
+
+    if let Some(ref tag_format) = options.tag_format {
+        if !is_valid_tag_format(tag_format) {
+            return Err(BumpError::InvalidTagFormat(tag_format.to_string()));
+        }
+    }
+
+    if options.fetch_tags.is_some() {
+        let timeout = CommandLimits::network(&Some(root.to_string())).timeout;
+        git_fetch_all_with_timeout(Some(root.to_string()), options.fetch_tags, timeout)?;
+    }
+
+    let since = match options.since {
+        Some(ref since) => since.to_string(),
+        None => String::from("origin/main"),
+    };
+
+    let is_package_allowed = |package_name: &String| {
+        let included = match options.include_packages {
+            Some(ref include_packages) => include_packages.contains(package_name),
+            None => true,
+        };
+        let excluded = match options.exclude_packages {
+            Some(ref exclude_packages) => exclude_packages.contains(package_name),
+            None => false,
+        };
+
+        included && !excluded
+    };
+
+    let ref packages = get_packages(Some(root.to_string()))?;
+
+    // A branch with a release channel releases like main, as prereleases when the channel has
+    // a prerelease id, instead of snapshots
+    let channel = get_channel_rule(&options.channels, current_branch);
+    let is_release_branch = channel.is_some() || current_branch.contains("main");
+
+    // Packages only changed by the changelog and version of a previous release (e.g. a
+    // cherry picked release commit) are not bumped again
+    let release_artifacts_only = match options.ignore_release_artifacts.unwrap_or(true) {
+        true => {
+            let changed_files =
+                get_all_files_changed_since_branch(packages, &since, Some(root.to_string()));
+
+            packages
+                .iter()
+                .filter(|package| {
+                    let package_files = changed_files
+                        .iter()
+                        .filter(|file| is_within(Path::new(&package.package_path), Path::new(file)))
+                        .collect::<Vec<&String>>();
+
+                    package_files.len() > 0
+                        && package_files.iter().all(|file| {
+                            is_release_artifact(file, &since, root, &options.changelog_file)
+                        })
+                })
+                .map(|package| package.name.to_string())
+                .collect::<Vec<String>>()
+        }
+        false => vec![],
+    };
+
+    let changed_packages = packages
+        .iter()
+        .filter(|package| {
+            options
+                .changes
+                .iter()
+                .any(|change| change.package == package.name)
+        })
+        .filter(|package| !release_artifacts_only.contains(&package.name))
+        .filter(|package| is_package_allowed(&package.name))
+        .map(|package| package.to_owned())
+        .collect::<Vec<PackageInfo>>();
+
+    if changed_packages.len() == 0 {
+        return Ok(BumpPlan {
+            bumps: vec![],
+            alignments: vec![],
+        });
+    }
+
+    let mut bump_changes = HashMap::new();
+
+    for changed_package in changed_packages.iter() {
+        let change = options
+            .changes
+            .iter()
+            .find(|change| change.package == changed_package.name);
+
+        if change.is_some() {
+            let release_as = match Some(is_release_branch) {
+                Some(true) => change.unwrap().release_as,
+                Some(false) | None => Bump::Snapshot,
+            };
 
             let change = Change {
                 release_as,
@@ -294,21 +1410,35 @@ pub fn get_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
         }
 
         if options.sync_deps.unwrap_or(false) {
+            let sync_dep_kinds = options
+                .sync_dep_kinds
+                .to_owned()
+                .unwrap_or(vec![DependencyKind::Prod]);
+
             packages.iter().for_each(|package| {
                 package.dependencies.iter().for_each(|dependency| {
-                    let release_as = match Some(current_branch.contains("main")) {
+                    let release_as = match Some(is_release_branch) {
                         Some(true) => Bump::Patch,
                         Some(false) | None => Bump::Snapshot,
                     };
 
-                    if dependency.name == changed_package.name {
-                        if change.is_some() && !bump_changes.contains_key(&package.name) {
+                    let synced_kind = package
+                        .get_dependency_kinds(&dependency.name)
+                        .iter()
+                        .any(|kind| sync_dep_kinds.contains(kind));
+
+                    if dependency.name == changed_package.name && synced_kind {
+                        let allowed = is_package_allowed(&package.name);
+
+                        if change.is_some() && allowed && !bump_changes.contains_key(&package.name)
+                        {
                             bump_changes.insert(
                                 package.name.to_string(),
                                 Change {
                                     package: package.name.to_string(),
                                     release_as,
                                     deploy: change.unwrap().deploy.to_owned(),
+                                    exact_version: None,
                                 },
                             );
                         }
@@ -318,17 +1448,42 @@ pub fn get_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
         }
     }
 
+    let ref tags = normalize_publish_tags(
+        get_publish_tags(Some(root.to_string()))?,
+        &options.tag_format,
+    );
+    let alignments = packages
+        .iter()
+        .filter(|package| bump_changes.contains_key(&package.name))
+        .map(|package| get_version_tag_alignment(package, tags))
+        .collect::<Vec<VersionTagAlignment>>();
+
     let mut bumps = bump_changes
         .iter()
         .map(|(package_name, change)| {
-            let package = get_package_info(package_name.to_string(), Some(root.to_string()));
+            let mut package = packages
+                .iter()
+                .find(|package| &package.name == package_name)
+                .cloned();
+
+            // Bump from the latest tag instead of the manifest when tags are trusted
+            if let Some(VersionSource::Tags) = options.version_source {
+                let tag_version = alignments
+                    .iter()
+                    .find(|alignment| &alignment.package == package_name)
+                    .and_then(|alignment| alignment.tag_version.to_owned());
+
+                if let (Some(package), Some(tag_version)) = (package.as_mut(), tag_version) {
+                    package.update_version(tag_version);
+                }
+            }
 
-            let release_as = match Some(current_branch.contains("main")) {
+            let release_as = match Some(is_release_branch) {
                 Some(true) => change.release_as.to_owned(),
                 Some(false) | None => Bump::Snapshot,
             };
 
-            let recommended_bump = get_package_recommend_bump(
+            let recommended_bump = get_package_recommend_bump_on_branch(
                 &package.unwrap(),
                 root,
                 Some(BumpOptions {
@@ -340,8 +1495,48 @@ pub fn get_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
                     sync_deps: options.sync_deps.to_owned(),
                     push: options.push.to_owned(),
                     cwd: Some(root.to_string()),
+                    include_packages: options.include_packages.to_owned(),
+                    exclude_packages: options.exclude_packages.to_owned(),
+                    changes_path: options.changes_path.to_owned(),
+                    collect_metrics: options.collect_metrics.to_owned(),
+                    zero_major_policy: options.zero_major_policy.to_owned(),
+                    use_temp_worktree: options.use_temp_worktree.to_owned(),
+                    version_source: options.version_source.to_owned(),
+                    train_tag: options.train_tag.to_owned(),
+                    protect_breaking_commits: options.protect_breaking_commits.to_owned(),
+                    versions_manifest: options.versions_manifest.to_owned(),
+                    snapshot_sha_source: options.snapshot_sha_source.to_owned(),
+                    single_commit: options.single_commit.to_owned(),
+                    write_changelog: options.write_changelog.to_owned(),
+                    bump_rules: options.bump_rules.to_owned(),
+                    prerelease_policy: options.prerelease_policy.to_owned(),
+                    ignore_release_artifacts: options.ignore_release_artifacts.to_owned(),
+                    sync_dep_kinds: options.sync_dep_kinds.to_owned(),
+                    channels: options.channels.to_owned(),
+                    skip_empty: options.skip_empty.to_owned(),
+                    history_mode: options.history_mode.to_owned(),
+                    changelog_file: options.changelog_file.to_owned(),
+                    tag_format: options.tag_format.to_owned(),
                 }),
-            );
+                current_branch,
+            )?;
+
+            // A manifest ahead of its tags may hide skipped releases, released as is unless a
+            // version source was chosen
+            let warnings = alignments
+                .iter()
+                .filter(|alignment| &alignment.package == package_name)
+                .filter(|alignment| alignment.alignment == VersionAlignment::ManifestAhead)
+                .filter(|_| options.version_source.is_none())
+                .map(|alignment| {
+                    format!(
+                        "Package {} version {} is ahead of its latest tag version {}",
+                        alignment.package,
+                        alignment.manifest_version,
+                        alignment.tag_version.to_owned().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<String>>();
 
             let bump = BumpPackage {
                 from: recommended_bump.from.to_string(),
@@ -351,19 +1546,32 @@ pub fn get_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
                     .conventional_commits
                     .to_owned(),
                 package_info: recommended_bump.package_info.to_owned(),
+                changelog: None,
+                dist_tag: channel
+                    .as_ref()
+                    .and_then(|channel| channel.dist_tag.to_owned()),
+                warnings,
             };
 
-            if bump.package_info.dependencies.len() > 0 {
-                bump_dependencies.insert(
-                    package_name.to_string(),
-                    bump.package_info.dependencies.to_owned(),
-                );
-            }
-
-            return bump;
+            Ok(bump)
         })
-        .collect::<Vec<BumpPackage>>();
+        .collect::<Result<Vec<BumpPackage>, BumpError>>()?;
+
+    // Packages without conventional commits would only get a placeholder changelog
+    if options.skip_empty.unwrap_or(false) {
+        bumps.retain(|bump| {
+            bump.conventional_commits
+                .as_array()
+                .map_or(false, |commits| commits.len() > 0)
+        });
+    }
+
+    Ok(BumpPlan { bumps, alignments })
+}
 
+/// Writes the versions of the bumps to their package.json, and with sync_deps the bumped
+/// versions of their dependencies.
+fn write_bumps(bumps: &mut Vec<BumpPackage>, sync_deps: bool) {
     bumps.iter_mut().for_each(|bump| {
         let version = bump.to.to_string();
         bump.package_info.update_version(version.to_string());
@@ -372,15 +1580,11 @@ pub fn get_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
         bump.package_info.write_package_json();
     });
 
-    if options.sync_deps.unwrap_or(false) {
-        bump_dependencies.iter().for_each(|(package_name, deps)| {
-            let temp_bumps = bumps.clone();
-            let bump = bumps
-                .iter_mut()
-                .find(|b| b.package_info.name == package_name.to_string())
-                .unwrap();
+    if sync_deps {
+        let temp_bumps = bumps.clone();
 
-            for dep in deps {
+        bumps.iter_mut().for_each(|bump| {
+            for dep in bump.package_info.dependencies.to_owned() {
                 let bump_dep = temp_bumps.iter().find(|b| b.package_info.name == dep.name);
 
                 if bump_dep.is_some() {
@@ -397,137 +1601,886 @@ pub fn get_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
             }
         });
     }
-
-    bumps
 }
 
 /// Apply version bumps, commit and push changes. Returns a list of packages that have been updated.
 /// Also generate changelog file and update dependencies and devDependencies in package.json.
-pub fn apply_bumps(options: &BumpOptions) -> Vec<BumpPackage> {
+/// With `write_changelog` disabled the CHANGELOG.md files are left untouched, the changelog is
+/// still generated and returned in each bump.
+/// With `use_temp_worktree`, everything is written, committed and tagged in a temporary worktree
+/// of HEAD (removed afterwards), leaving the current checkout untouched. Tags are shared with it.
+/// With `single_commit`, all packages are released in one commit carrying every package tag.
+/// The dist-tag of the release channel is written to the `publishConfig.tag` of the package.json.
+/// `changelog_file` replaces the CHANGELOG.md file name and `tag_format` the `{name}@{version}`
+/// format of the publish tags. Options not given are read from the release config of the
+/// project (see `ReleaseConfig`), a malformed config fails with `InvalidConfig`.
+/// A failed release commit stops the release with `CommitFailed`, its package isn't tagged or
+/// pushed.
+pub fn apply_bumps(options: &BumpOptions) -> Result<Vec<BumpPackage>, BumpError> {
+    Ok(apply_bumps_with_metrics(options)?.bumps)
+}
+
+/// Apply version bumps the same way as `apply_bumps`, also returning the release metrics
+/// (durations, git invocations, changelog bytes) when `collect_metrics` is enabled.
+pub fn apply_bumps_with_metrics(options: &BumpOptions) -> Result<ReleaseResult, BumpError> {
+    run_release(options, &None)
+}
+
+/// Apply version bumps the same way as `apply_bumps`, releasing the packages in dependency order
+/// (dependencies before their dependents) and publishing each one right after its release
+/// commit and tag (and push), running `publish_command` through `sh -c` in its package directory.
+/// With `single_commit` packages are published in that order after the release commit.
+/// Private packages aren't published.
+/// The command gets `PKG_NAME`, `PKG_VERSION`, `PKG_PREVIOUS_VERSION`, `PKG_PATH` and
+/// `PKG_DIST_TAG` (when released on a channel) env vars. The first failing publish stops the
+/// release, its package is returned in the error and the remaining packages aren't released.
+pub fn apply_and_publish(
+    options: BumpOptions,
+    publish_command: String,
+) -> Result<Vec<BumpPackage>, BumpError> {
+    Ok(run_release(&options, &Some(publish_command))?.bumps)
+}
+
+/// A released package in the publish graph. Its dependencies are matched by name only, as the
+/// bump may have moved the package away from the version its dependents still require.
+struct PublishNode<'a> {
+    bump: &'a BumpPackage,
+    dependencies: Vec<String>,
+}
+
+impl<'a> Node for PublishNode<'a> {
+    type DependencyType = String;
+
+    fn dependencies(&self) -> &[Self::DependencyType] {
+        &self.dependencies[..]
+    }
+
+    fn matches(&self, dependency: &Self::DependencyType) -> bool {
+        &self.bump.package_info.name == dependency
+    }
+}
+
+/// Sorts the bumps so the dependencies of a package are released and published before it.
+fn get_publish_order(bumps: Vec<BumpPackage>) -> Vec<BumpPackage> {
+    let nodes = bumps
+        .iter()
+        .map(|bump| PublishNode {
+            bump,
+            dependencies: bump
+                .package_info
+                .dependencies
+                .iter()
+                .map(|dependency| dependency.name.to_string())
+                .collect::<Vec<String>>(),
+        })
+        .collect::<Vec<PublishNode>>();
+
+    DependencyGraph::from(&nodes[..])
+        .filter_map(|step| step.as_resolved().map(|node| node.bump.to_owned()))
+        .collect::<Vec<BumpPackage>>()
+}
+
+/// Runs the publish command in the package directory of the bump, private packages are skipped.
+fn publish_package(bump: &BumpPackage, publish_command: &String) -> Result<(), BumpError> {
+    if publish_target(&bump.package_info) == PublishTarget::Private {
+        return Ok(());
+    }
+
+    let ref package_path = bump.package_info.package_path;
+
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(publish_command)
+        .current_dir(package_path)
+        .env("PKG_NAME", &bump.package_info.name)
+        .env("PKG_VERSION", &bump.to)
+        .env("PKG_PREVIOUS_VERSION", &bump.from)
+        .env("PKG_PATH", package_path);
+
+    if let Some(ref dist_tag) = bump.dist_tag {
+        command.env("PKG_DIST_TAG", dist_tag);
+    }
+
+    let output = command.output().map_err(|error| BumpError::PublishFailed {
+        package: bump.package_info.name.to_string(),
+        reason: error.to_string(),
+    })?;
+
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(BumpError::PublishFailed {
+            package: bump.package_info.name.to_string(),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }),
+    }
+}
+
+/// Runs the release, in the temporary worktree with `use_temp_worktree`, publishing the
+/// released packages with the publish command when given (see `apply_and_publish`).
+fn run_release(
+    options: &BumpOptions,
+    publish_command: &Option<String>,
+) -> Result<ReleaseResult, BumpError> {
     let ref root = match options.cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let ref changes_data = init_changes(Some(root.to_string()), &None);
-    let git_user_name = changes_data.git_user_name.to_owned();
-    let git_user_email = changes_data.git_user_email.to_owned();
-
-    git_config(
-        &git_user_name.unwrap_or(String::from("")),
-        &git_user_email.unwrap_or(String::from("")),
-        &root.to_string(),
-    )
-    .expect("Failed to set git user name and email");
+    let current_branch = git_current_branch(Some(root.to_string())).unwrap_or(String::from("main"));
+    let ref options = with_release_config(options, root)?;
 
-    let bumps = get_bumps(options);
+    if !options.use_temp_worktree.unwrap_or(false) {
+        return release_bumps(options, &current_branch, false, publish_command);
+    }
 
-    if bumps.len() != 0 {
-        for bump in &bumps {
-            let git_message = changes_data.message.to_owned();
+    let worktree = TempWorktree::create(root, &options.changes_path);
+    let worktree_options = BumpOptions {
+        cwd: Some(worktree.path.to_string()),
+        changes_path: Some(worktree.changes_path.to_string()),
+        ..options.to_owned()
+    };
 
-            let ref bump_pkg_json_file_path =
-                PathBuf::from(bump.package_info.package_json_path.to_string());
-            let ref bump_changelog_file_path =
-                PathBuf::from(bump.package_info.package_path.to_string())
-                    .join(String::from("CHANGELOG.md"));
+    let mut result = release_bumps(&worktree_options, &current_branch, true, publish_command)?;
 
-            // Write bump_pkg_json_file_path
-            let bump_pkg_json_file = OpenOptions::new()
-                .write(true)
-                .append(false)
-                .open(bump_pkg_json_file_path)
-                .unwrap();
-            let pkg_json_writer = BufWriter::new(bump_pkg_json_file);
-            serde_json::to_writer_pretty(pkg_json_writer, &bump.package_info.pkg_json).unwrap();
+    // Package paths point to the worktree, which is removed when the guard is dropped
+    result.bumps.iter_mut().for_each(|bump| {
+        let package_info = &mut bump.package_info;
 
-            let conventional = get_conventional_for_package(
-                &bump.package_info,
-                options.fetch_all.to_owned(),
-                Some(root.to_string()),
-                &Some(ConventionalPackageOptions {
-                    version: Some(bump.to.to_string()),
-                    title: Some("# What changed?".to_string()),
-                }),
-            );
+        package_info.package_json_path =
+            package_info
+                .package_json_path
+                .replacen(&worktree.path, root, 1);
+        package_info.package_path = package_info.package_path.replacen(&worktree.path, root, 1);
+    });
 
-            // Write bump_changelog_file_path
-            let mut bump_changelog_file = OpenOptions::new()
-                .write(true)
-                .create(true)
-                .append(false)
-                .open(bump_changelog_file_path)
-                .unwrap();
+    Ok(result)
+}
 
-            bump_changelog_file
-                .write_all(conventional.changelog_output.as_bytes())
-                .unwrap();
+/// Maps each deploy environment of the changes to the released packages (`name@version`)
+/// destined for it. Bumps without a change (e.g. synced dependents) aren't deployed.
+pub fn get_deployment_targets(
+    bumps: &[BumpPackage],
+    changes: &[Change],
+) -> HashMap<String, Vec<String>> {
+    let mut targets: HashMap<String, Vec<String>> = HashMap::new();
 
-            let ref package_tag = format!("{}@{}", bump.package_info.name, bump.to);
+    for bump in bumps {
+        let change = changes
+            .iter()
+            .find(|change| change.package == bump.package_info.name);
 
-            git_add_all(&root.to_string()).expect("Failed to add all files to git");
-            git_commit(
-                git_message.unwrap_or(String::from("chore: release version")),
-                None,
-                None,
-                Some(root.to_string()),
-            )
-            .unwrap();
-            git_tag(
-                package_tag.to_string(),
-                Some(format!(
-                    "chore: release {} to version {}",
-                    bump.package_info.name, bump.to
-                )),
-                Some(root.to_string()),
-            )
-            .unwrap();
+        if let Some(change) = change {
+            for environment in &change.deploy {
+                let packages = targets.entry(environment.to_string()).or_default();
+                let package = format!("{}@{}", bump.package_info.name, bump.to);
 
-            if options.push.unwrap_or(false) {
-                git_push(Some(root.to_string()), Some(true)).unwrap();
+                if !packages.contains(&package) {
+                    packages.push(package);
+                }
             }
         }
     }
 
-    bumps
+    targets.values_mut().for_each(|packages| packages.sort());
+    targets
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::changes::{add_change, get_change, init_changes};
-    use crate::manager::PackageManager;
-    use crate::packages::get_changed_packages;
-    use crate::paths::get_project_root_path;
-    use crate::utils::create_test_monorepo;
-    use std::fs::remove_dir_all;
-    use std::fs::File;
-    use std::io::Write;
-    use std::process::Command;
-    use std::process::Stdio;
+/// A temporary `git worktree` of the current HEAD. It's removed when dropped, so the
+/// worktree is cleaned up even if the release fails midway.
+struct TempWorktree {
+    root: String,
+    path: String,
+    changes_path: String,
+}
 
-    fn create_single_changes(root: &String) -> Result<(), Box<dyn std::error::Error>> {
-        let change_package_a = Change {
-            package: String::from("@scope/package-a"),
-            release_as: Bump::Major,
-            deploy: vec![String::from("production")],
-        };
+impl TempWorktree {
+    /// Creates the worktree in the temp directory and copies the changes (usually not committed)
+    /// from the current checkout, so the release reads the same data.
+    fn create(root: &String, changes_path: &Option<String>) -> TempWorktree {
+        let rand_string: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(30)
+            .map(char::from)
+            .collect();
 
-        init_changes(Some(root.to_string()), &None);
+        let ref worktree_dir = std::env::temp_dir().join(format!("worktree-{}", rand_string));
+        let path = worktree_dir.display().to_string();
 
-        add_change(&change_package_a, Some(root.to_string()));
+        let created = git_worktree_add(&path, Some(root.to_string())).unwrap();
 
-        Ok(())
-    }
+        if !created {
+            panic!("Failed to create temporary worktree at {}", path);
+        }
 
-    fn create_single_package(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        let js_path = monorepo_dir.join("packages/package-a/index.js");
+        let path = canonicalize(worktree_dir).unwrap().display().to_string();
 
-        let branch = Command::new("git")
-            .current_dir(&monorepo_dir)
-            .arg("checkout")
-            .arg("-b")
-            .arg("feat/message")
+        let storage = ChangesStorage::resolve(root, changes_path);
+        let storage_path = match storage {
+            ChangesStorage::SingleFile(ref file) => file,
+            ChangesStorage::PerBranchDirectory(ref dir) => dir,
+        };
+        let changes_path = storage_path
+            .strip_prefix(root)
+            .unwrap_or(storage_path)
+            .display()
+            .to_string();
+
+        let worktree = TempWorktree {
+            root: root.to_string(),
+            path,
+            changes_path,
+        };
+
+        if let Some(ref changes) = storage.read() {
+            ChangesStorage::resolve(&worktree.path, &Some(worktree.changes_path.to_string()))
+                .write(changes);
+        }
+
+        worktree
+    }
+}
+
+impl Drop for TempWorktree {
+    fn drop(&mut self) {
+        let removed = git_worktree_remove(&self.path, Some(self.root.to_string())).unwrap_or(false);
+
+        if !removed {
+            let _ = remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// Runs the release pipeline (write versions and changelogs, commit, tag and push) in the
+/// `cwd` checkout. A detached checkout pushes HEAD to the given branch.
+fn release_bumps(
+    options: &BumpOptions,
+    current_branch: &String,
+    detached: bool,
+    publish_command: &Option<String>,
+) -> Result<ReleaseResult, BumpError> {
+    let started_at = match options.collect_metrics {
+        Some(true) => Some(Instant::now()),
+        _ => None,
+    };
+    let mut metrics = started_at.map(|_| ReleaseMetrics::default());
+
+    let ref root = match options.cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref changes_data = init_changes(
+        Some(root.to_string()),
+        &Some(ChangesOptions {
+            message: None,
+            git_user_name: None,
+            git_user_email: None,
+            changes_path: options.changes_path.to_owned(),
+        }),
+    );
+    let git_user_name = changes_data.git_user_name.to_owned();
+    let git_user_email = changes_data.git_user_email.to_owned();
+
+    // Packages released by a previous run of the same release (e.g. a retried CI job) are skipped
+    let BumpPlan {
+        bumps: planned_bumps,
+        alignments,
+    } = plan_bumps_on_branch(options, current_branch)?;
+    let mut already_released = vec![];
+    let mut bumps = vec![];
+
+    for bump in planned_bumps {
+        match get_already_released_version(&bump, root, &options.tag_format) {
+            Some(version) => already_released.push(PackageReleaseStatus {
+                package: bump.package_info.name.to_string(),
+                version,
+                status: ReleaseStatus::AlreadyReleased,
+            }),
+            None => bumps.push(bump),
+        }
+    }
+
+    if already_released.len() != 0 && bumps.len() == 0 {
+        if let Some(ref mut metrics) = metrics {
+            metrics.total_duration_ms = started_at
+                .map(|started| started.elapsed().as_millis() as u32)
+                .unwrap_or(0);
+        }
+
+        return Ok(ReleaseResult {
+            bumps: vec![],
+            metrics,
+            train_tag: None,
+            packages: already_released,
+        });
+    }
+
+    // An already released package may be behind its tags, as the release commit isn't checked out
+    let pending_alignments = alignments
+        .into_iter()
+        .filter(|alignment| {
+            bumps
+                .iter()
+                .any(|bump| bump.package_info.name == alignment.package)
+        })
+        .collect::<Vec<VersionTagAlignment>>();
+
+    validate_version_tag_alignment(&pending_alignments, &options.version_source)?;
+
+    let configured = git_config(
+        &git_user_name.unwrap_or(String::from("")),
+        &git_user_email.unwrap_or(String::from("")),
+        &root.to_string(),
+    )
+    .map_err(|error| BumpError::Git(error.to_string()))?;
+
+    if let Some(ref mut metrics) = metrics {
+        // git_config sets user.name and user.email in two invocations
+        metrics.record_git_command("config", configured);
+        metrics.record_git_command("config", configured);
+    }
+
+    write_bumps(&mut bumps, options.sync_deps.unwrap_or(false));
+
+    // Dependencies are released, and published, before their dependents
+    if publish_command.is_some() {
+        bumps = get_publish_order(bumps);
+    }
+
+    let push_release = || match (options.push.unwrap_or(false), detached) {
+        (true, false) => Some(git_push(Some(root.to_string()), Some(true)).unwrap()),
+        (true, true) => Some(
+            git_push_head_to_branch(
+                current_branch.to_string(),
+                None,
+                Some(root.to_string()),
+                Some(true),
+            )
+            .unwrap(),
+        ),
+        (false, _) => None,
+    };
+    let tag_release = |bump: &BumpPackage| {
+        git_tag(
+            format_publish_tag(&options.tag_format, &bump.package_info.name, &bump.to),
+            Some(format!(
+                "chore: release {} to version {}",
+                bump.package_info.name, bump.to
+            )),
+            None,
+            None,
+            Some(root.to_string()),
+        )
+        .unwrap()
+    };
+    let mut released = true;
+    let single_commit = options.single_commit.unwrap_or(false);
+    let write_changelog = options.write_changelog.unwrap_or(true);
+    let changelog_file = options
+        .changelog_file
+        .to_owned()
+        .unwrap_or(String::from(CHANGELOG_FILE));
+    let mut single_commit_paths: Vec<String> = vec![];
+
+    // The history is fetched once, the changelogs of every package are generated from it
+    if bumps.len() != 0 && options.fetch_all.is_some() {
+        let timeout = CommandLimits::network(&Some(root.to_string())).timeout;
+        let fetched =
+            git_fetch_all_with_timeout(Some(root.to_string()), options.fetch_all, timeout)?;
+
+        if let Some(ref mut metrics) = metrics {
+            metrics.record_git_command("fetch", fetched);
+        }
+    }
+
+    if bumps.len() != 0 {
+        for bump in &mut bumps {
+            let package_started_at = started_at.map(|_| Instant::now());
+            let git_message = changes_data.message.to_owned();
+
+            let ref bump_pkg_json_file_path =
+                PathBuf::from(bump.package_info.package_json_path.to_string());
+            let ref bump_changelog_file_path =
+                PathBuf::from(bump.package_info.package_path.to_string()).join(&changelog_file);
+
+            // npm publishes under the dist-tag of the release channel
+            if let Some(ref dist_tag) = bump.dist_tag {
+                bump.package_info.update_publish_tag(dist_tag.to_string());
+            }
+
+            // Write bump_pkg_json_file_path
+            let bump_pkg_json_file = OpenOptions::new()
+                .write(true)
+                .append(false)
+                .open(bump_pkg_json_file_path)
+                .unwrap();
+            let pkg_json_writer = BufWriter::new(bump_pkg_json_file);
+            serde_json::to_writer_pretty(pkg_json_writer, &bump.package_info.pkg_json).unwrap();
+
+            let conventional = get_conventional_for_package(
+                &bump.package_info,
+                None,
+                Some(root.to_string()),
+                &Some(ConventionalPackageOptions {
+                    version: Some(bump.to.to_string()),
+                    title: Some("# What changed?".to_string()),
+                    protect_breaking_commits: options.protect_breaking_commits.to_owned(),
+                    prerelease_policy: options.prerelease_policy.to_owned(),
+                    history_mode: options.history_mode.to_owned(),
+                    changelog_file: options.changelog_file.to_owned(),
+                    tag_format: options.tag_format.to_owned(),
+                    ..Default::default()
+                }),
+            )?;
+
+            let mut release_paths = vec![bump_pkg_json_file_path.display().to_string()];
+
+            // Without write_changelog the changelog is only returned in the bump
+            if write_changelog {
+                let mut bump_changelog_file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .append(false)
+                    .open(bump_changelog_file_path)
+                    .unwrap();
+
+                bump_changelog_file
+                    .write_all(conventional.changelog_output.as_bytes())
+                    .unwrap();
+
+                release_paths.push(bump_changelog_file_path.display().to_string());
+            }
+
+            if options.versions_manifest.is_some() {
+                let manifest_path = update_versions_manifest(
+                    &BTreeMap::from([(bump.package_info.name.to_string(), bump.to.to_string())]),
+                    Some(root.to_string()),
+                    options.versions_manifest.to_owned(),
+                )
+                .expect("Failed to update versions manifest");
+
+                release_paths.push(manifest_path.display().to_string());
+            }
+
+            if let Some(ref mut metrics) = metrics {
+                metrics.changelog_bytes += conventional.changelog_output.len() as u32;
+            }
+
+            bump.changelog = Some(conventional.changelog_output);
+
+            if single_commit {
+                single_commit_paths.extend(release_paths);
+            } else {
+                // Nothing is tagged or pushed without its release commit
+                commit_paths(
+                    release_paths,
+                    git_message.unwrap_or(String::from("chore: release version")),
+                    Some(root.to_string()),
+                )
+                .map_err(|error| BumpError::CommitFailed(error.to_string()))?;
+                let tagged = tag_release(bump);
+
+                let pushed = push_release();
+
+                released &= tagged;
+
+                if let Some(ref mut metrics) = metrics {
+                    // Staging and committing are a single step, both succeeded
+                    metrics.record_git_command("add", true);
+                    metrics.record_git_command("commit", true);
+                    metrics.record_git_command("tag", tagged);
+
+                    metrics.commits += 1;
+                    metrics.tags += tagged as u32;
+
+                    if let Some(pushed) = pushed {
+                        metrics.record_git_command("push", pushed);
+                        metrics.pushes += pushed as u32;
+                    }
+                }
+
+                if let Some(ref publish_command) = publish_command {
+                    publish_package(bump, publish_command)?;
+                }
+            }
+
+            if let Some(ref mut metrics) = metrics {
+                metrics.packages.push(PackageReleaseMetrics {
+                    package: bump.package_info.name.to_string(),
+                    duration_ms: package_started_at
+                        .map(|started| started.elapsed().as_millis() as u32)
+                        .unwrap_or(0),
+                });
+            }
+        }
+
+        // One release commit for all packages, every package tag points at it
+        if single_commit {
+            single_commit_paths.sort();
+            single_commit_paths.dedup();
+
+            commit_paths(
+                single_commit_paths,
+                changes_data
+                    .message
+                    .to_owned()
+                    .unwrap_or(String::from("chore: release version")),
+                Some(root.to_string()),
+            )
+            .map_err(|error| BumpError::CommitFailed(error.to_string()))?;
+
+            if let Some(ref mut metrics) = metrics {
+                metrics.record_git_command("add", true);
+                metrics.record_git_command("commit", true);
+                metrics.commits += 1;
+            }
+
+            for bump in &bumps {
+                let tagged = tag_release(bump);
+
+                released &= tagged;
+
+                if let Some(ref mut metrics) = metrics {
+                    metrics.record_git_command("tag", tagged);
+                    metrics.tags += tagged as u32;
+                }
+            }
+
+            if let Some(pushed) = push_release() {
+                if let Some(ref mut metrics) = metrics {
+                    metrics.record_git_command("push", pushed);
+                    metrics.pushes += pushed as u32;
+                }
+            }
+
+            if let Some(ref publish_command) = publish_command {
+                for bump in &bumps {
+                    publish_package(bump, publish_command)?;
+                }
+            }
+        }
+    }
+
+    let train_tag = match options.train_tag {
+        Some(ref train_tag_options) if bumps.len() != 0 && released => {
+            let train_tag = create_train_tag(train_tag_options, &bumps, root)?;
+
+            if let Some(ref mut metrics) = metrics {
+                metrics.record_git_command("tag", train_tag.is_some());
+                metrics.tags += train_tag.is_some() as u32;
+            }
+
+            if train_tag.is_some() {
+                if let Some(pushed) = push_release() {
+                    if let Some(ref mut metrics) = metrics {
+                        metrics.record_git_command("push", pushed);
+                        metrics.pushes += pushed as u32;
+                    }
+                }
+            }
+
+            train_tag
+        }
+        _ => None,
+    };
+
+    if let Some(ref mut metrics) = metrics {
+        metrics.total_duration_ms = started_at
+            .map(|started| started.elapsed().as_millis() as u32)
+            .unwrap_or(0);
+    }
+
+    let packages = already_released
+        .into_iter()
+        .chain(bumps.iter().map(|bump| PackageReleaseStatus {
+            package: bump.package_info.name.to_string(),
+            version: bump.to.to_string(),
+            status: ReleaseStatus::Released,
+        }))
+        .collect::<Vec<PackageReleaseStatus>>();
+
+    Ok(ReleaseResult {
+        bumps,
+        metrics,
+        train_tag,
+        packages,
+    })
+}
+
+/// Finds the version of a planned bump released by a previous run of the same release (e.g. a
+/// retried CI job). Either its target tag exists on a commit whose package.json has the target
+/// version, or, rerunning on top of that release, the version it starts from was tagged on the
+/// commit that set it in the package.json and every commit since is a release commit (it
+/// carries a publish tag), so there is nothing new to release.
+fn get_already_released_version(
+    bump: &BumpPackage,
+    root: &String,
+    tag_format: &Option<String>,
+) -> Option<String> {
+    let ref package = bump.package_info;
+    let ref package_json_path = PathBuf::from(&package.package_relative_path)
+        .join("package.json")
+        .display()
+        .to_string();
+
+    let version_at = |rev: String| {
+        git_show_file(rev, package_json_path.to_string(), Some(root.to_string()))
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|pkg_json| pkg_json["version"].as_str().map(String::from))
+    };
+
+    let target_tag = format_publish_tag(tag_format, &package.name, &bump.to);
+
+    if version_at(target_tag) == Some(bump.to.to_string()) {
+        return Some(bump.to.to_string());
+    }
+
+    let ref from_tag = format_publish_tag(tag_format, &package.name, &bump.from);
+    let tagged = version_at(from_tag.to_string()) == Some(bump.from.to_string());
+    let bumped = match version_at(format!("{}^", from_tag)) {
+        Some(previous_version) => previous_version != bump.from,
+        None => false,
+    };
+    let release_commits_only = || {
+        get_commits_between(
+            Some(root.to_string()),
+            Some(from_tag.to_string()),
+            String::from("HEAD"),
+            None,
+        )
+        .iter()
+        .all(|commit| {
+            let tags = git_tags_pointing_at(commit.hash.to_string(), Some(root.to_string()))
+                .into_iter()
+                .map(|tag| RemoteTags {
+                    hash: commit.hash.to_string(),
+                    tag,
+                })
+                .collect::<Vec<RemoteTags>>();
+
+            normalize_publish_tags(tags, tag_format)
+                .iter()
+                .any(|item| split_publish_tag(&item.tag).is_some())
+        })
+    };
+    let released = tagged
+        && bumped
+        && release_commits_only()
+        && git_path_unchanged_since(
+            from_tag.to_string(),
+            package.package_relative_path.to_string(),
+            Some(root.to_string()),
+        );
+
+    match released {
+        true => Some(bump.from.to_string()),
+        false => None,
+    }
+}
+
+/// Creates the release train tag at the current commit. The name is built from the format
+/// replacing `{date}` (UTC, `YYYY-MM-DD`), `{count}` (number of packages) and `{sequence}`,
+/// which starts at 1 and is incremented until the tag doesn't exist yet. The message template
+/// supports `{name}`, `{date}`, `{count}` and `{packages}` (one `name@version` per line).
+fn create_train_tag(
+    options: &TrainTagOptions,
+    bumps: &Vec<BumpPackage>,
+    root: &String,
+) -> Result<Option<TrainTag>, GitError> {
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    let count = bumps.len().to_string();
+
+    let packages = bumps
+        .iter()
+        .map(|bump| format!("{}@{}", bump.package_info.name, bump.to))
+        .collect::<Vec<String>>();
+
+    let existing_tags = get_remote_or_local_tags(Some(root.to_string()), Some(true))?
+        .iter()
+        .map(|item| item.tag.replace("refs/tags/", ""))
+        .collect::<Vec<String>>();
+
+    let format_name = |sequence: u32| {
+        options
+            .format
+            .replace("{date}", &date)
+            .replace("{count}", &count)
+            .replace("{sequence}", &sequence.to_string())
+    };
+
+    let mut sequence = 1;
+    let mut name = format_name(sequence);
+
+    while options.format.contains("{sequence}") && existing_tags.contains(&name) {
+        sequence += 1;
+        name = format_name(sequence);
+    }
+
+    let message = options
+        .message_template
+        .to_owned()
+        .unwrap_or(String::from("chore: release train {name}\n\n{packages}"))
+        .replace("{name}", &name)
+        .replace("{date}", &date)
+        .replace("{count}", &count)
+        .replace("{packages}", &packages.join("\n"));
+
+    let tagged = git_tag(
+        name.to_string(),
+        Some(message),
+        None,
+        None,
+        Some(root.to_string()),
+    )
+    .unwrap();
+
+    match tagged {
+        true => Ok(Some(TrainTag { name, packages })),
+        false => Ok(None),
+    }
+}
+
+/// Renders a list of conventional commits (as serialized by git-cliff) as markdown list items.
+fn render_conventional_commits(conventional_commits: &Value) -> Vec<String> {
+    let group_regex = Regex::new(r"<!--\s*\d+\s*-->").unwrap();
+
+    match conventional_commits.as_array() {
+        Some(commits) => commits
+            .iter()
+            .map(|commit| {
+                let message = commit["message"].as_str().unwrap_or_default().trim();
+                let id = commit["id"].as_str().unwrap_or_default();
+                let short_id = id.get(0..7).unwrap_or(id);
+                let group = commit["group"]
+                    .as_str()
+                    .map(|group| group_regex.replace_all(group, "").trim().to_string());
+                let scope = commit["scope"]
+                    .as_str()
+                    .map(|scope| format!("*({})* ", scope))
+                    .unwrap_or_default();
+
+                match group {
+                    Some(group) if !group.is_empty() => {
+                        format!("- **{}**: {}{} ({})", group, scope, message, short_id)
+                    }
+                    _ => format!("- {}{} ({})", scope, message, short_id),
+                }
+            })
+            .collect::<Vec<String>>(),
+        None => vec![],
+    }
+}
+
+/// Generates a release pull request body for the given bumps. It contains a summary header,
+/// the version transition of every package and a collapsible changelog section per package.
+/// The changelog reuses the bump conventional commits, falling back to the commits
+/// since the last publish tag of the package when none were recorded.
+pub fn generate_release_pr_body(
+    bumps: &[BumpPackage],
+    cwd: Option<String>,
+) -> Result<String, GitError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut body = vec![
+        String::from("## Release"),
+        String::new(),
+        format!("This release bumps {} package(s).", bumps.len()),
+        String::new(),
+        String::from("| Package | From | To |"),
+        String::from("| --- | --- | --- |"),
+    ];
+
+    for bump in bumps {
+        body.push(format!(
+            "| `{}` | {} | {} |",
+            bump.package_info.name, bump.from, bump.to
+        ));
+    }
+
+    for bump in bumps {
+        let mut changelog = render_conventional_commits(&bump.conventional_commits);
+
+        if changelog.is_empty() {
+            let conventional = get_conventional_for_package(
+                &bump.package_info,
+                None,
+                Some(root.to_string()),
+                &Some(ConventionalPackageOptions {
+                    version: Some(bump.to.to_string()),
+                    ..Default::default()
+                }),
+            )?;
+
+            changelog = render_conventional_commits(&conventional.conventional_commits);
+        }
+
+        if changelog.is_empty() {
+            changelog.push(String::from("- No conventional commits found."));
+        }
+
+        body.push(String::new());
+        body.push(format!(
+            "### {} {} → {}",
+            bump.package_info.name, bump.from, bump.to
+        ));
+        body.push(String::new());
+        body.push(String::from("<details>"));
+        body.push(String::from("<summary>Changelog</summary>"));
+        body.push(String::new());
+        body.append(&mut changelog);
+        body.push(String::new());
+        body.push(String::from("</details>"));
+    }
+
+    body.push(String::new());
+
+    Ok(body.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::changes::{add_change, get_change, init_changes};
+    use crate::conventional::EMPTY_CHANGELOG_PLACEHOLDER;
+    use crate::git::{get_commit_count_since, git_add_all, git_commit, package_workdir_unclean};
+    use crate::manager::PackageManager;
+    use crate::packages::{get_changed_packages, update_packages_field, ChangedPackagesOptions};
+    use crate::paths::get_project_root_path;
+    use crate::utils::{create_test_monorepo, run_git};
+    use crate::versions::{read_versions_manifest, verify_versions_manifest};
+    use std::fs::remove_dir_all;
+    use std::fs::File;
+    use std::io::Write;
+    use std::process::Command;
+    use std::process::Stdio;
+
+    fn create_single_changes(root: &String) -> Result<(), Box<dyn std::error::Error>> {
+        let change_package_a = Change {
+            package: String::from("@scope/package-a"),
+            release_as: Bump::Major,
+            deploy: vec![String::from("production")],
+            exact_version: None,
+        };
+
+        init_changes(Some(root.to_string()), &None);
+
+        add_change(&change_package_a, Some(root.to_string()), None);
+
+        Ok(())
+    }
+
+    fn create_single_package(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        let js_path = monorepo_dir.join("packages/package-a/index.js");
+
+        let branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("-b")
+            .arg("feat/message")
             .stdout(Stdio::piped())
             .spawn()
             .expect("Git branch problem");
@@ -568,18 +2521,20 @@ mod tests {
             package: String::from("@scope/package-a"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         let change_package_c = Change {
             package: String::from("@scope/package-c"),
             release_as: Bump::Minor,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
-        add_change(&change_package_a, Some(root.to_string()));
-        add_change(&change_package_c, Some(root.to_string()));
+        add_change(&change_package_a, Some(root.to_string()), None);
+        add_change(&change_package_c, Some(root.to_string()), None);
 
         Ok(())
     }
@@ -638,11 +2593,12 @@ mod tests {
             package: String::from("@scope/package-b"),
             release_as: Bump::Snapshot,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
-        add_change(&change_package_a, Some(root.to_string()));
+        add_change(&change_package_a, Some(root.to_string()), None);
 
         Ok(())
     }
@@ -697,18 +2653,20 @@ mod tests {
             package: String::from("@scope/package-a"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         let change_package_b = Change {
             package: String::from("@scope/package-b"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
-        add_change(&change_package_a, Some(root.to_string()));
-        add_change(&change_package_b, Some(root.to_string()));
+        add_change(&change_package_a, Some(root.to_string()), None);
+        add_change(&change_package_b, Some(root.to_string()), None);
 
         Ok(())
     }
@@ -774,18 +2732,17 @@ mod tests {
         create_single_package(monorepo_dir)?;
         create_single_changes(&root)?;
 
-        let changes = get_change(String::from("feat/message"), Some(root.to_string()));
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
 
         let bumps = get_bumps(&BumpOptions {
             changes,
             since: Some(String::from("main")),
             release_as: Some(Bump::Major),
-            fetch_all: None,
-            fetch_tags: None,
             sync_deps: Some(false),
             push: Some(false),
             cwd: Some(root.to_string()),
-        });
+            ..Default::default()
+        })?;
 
         dbg!(&bumps);
 
@@ -809,18 +2766,16 @@ mod tests {
         create_multiple_packages(monorepo_dir)?;
         create_multiple_changes(&root)?;
 
-        let changes = get_change(String::from("feat/message"), Some(root.to_string()));
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
 
         let bumps = get_bumps(&BumpOptions {
             changes,
             since: Some(String::from("main")),
-            release_as: None,
-            fetch_all: None,
-            fetch_tags: None,
             sync_deps: Some(false),
             push: Some(false),
             cwd: Some(root.to_string()),
-        });
+            ..Default::default()
+        })?;
 
         assert_eq!(bumps.len(), 2);
 
@@ -835,140 +2790,2500 @@ mod tests {
     }
 
     #[test]
-    fn test_single_dependency_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
+    fn test_include_packages_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
 
         let ref root = project_root.to_string();
 
-        create_single_dependency_package(monorepo_dir)?;
-        create_single_dependency_changes(&root)?;
+        create_multiple_packages(monorepo_dir)?;
+        create_multiple_changes(&root)?;
 
-        let changes = get_change(String::from("feat/message"), Some(root.to_string()));
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
 
         let bumps = get_bumps(&BumpOptions {
             changes,
             since: Some(String::from("main")),
-            release_as: None,
-            fetch_all: None,
-            fetch_tags: None,
-            sync_deps: Some(true),
+            sync_deps: Some(false),
             push: Some(false),
             cwd: Some(root.to_string()),
-        });
-
-        assert_eq!(bumps.len(), 2);
-
-        let first_bump = bumps.get(0);
-        let second_bump = bumps.get(1);
+            include_packages: Some(vec![String::from("@scope/package-c")]),
+            ..Default::default()
+        })?;
 
-        assert_eq!(first_bump.is_some(), true);
-        assert_eq!(second_bump.is_some(), true);
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].package_info.name, String::from("@scope/package-c"));
 
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_multiple_dependency_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
+    fn test_exclude_packages_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
 
         let ref root = project_root.to_string();
 
-        create_multiple_dependency_packages(monorepo_dir)?;
-        create_multiple_dependency_changes(&root)?;
+        create_multiple_packages(monorepo_dir)?;
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let bumps = get_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            exclude_packages: Some(vec![String::from("@scope/package-a")]),
+            ..Default::default()
+        })?;
+
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].package_info.name, String::from("@scope/package-c"));
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
 
-        let changes = get_change(String::from("feat/message"), Some(root.to_string()));
+    #[test]
+    fn test_single_dependency_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        create_single_dependency_package(monorepo_dir)?;
+        create_single_dependency_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
 
         let bumps = get_bumps(&BumpOptions {
             changes,
             since: Some(String::from("main")),
-            release_as: None,
-            fetch_all: None,
-            fetch_tags: None,
             sync_deps: Some(true),
             push: Some(false),
             cwd: Some(root.to_string()),
-        });
+            ..Default::default()
+        })?;
 
-        assert_eq!(bumps.len(), 3);
+        assert_eq!(bumps.len(), 2);
 
         let first_bump = bumps.get(0);
         let second_bump = bumps.get(1);
-        let third_bump = bumps.get(2);
 
         assert_eq!(first_bump.is_some(), true);
         assert_eq!(second_bump.is_some(), true);
-        assert_eq!(third_bump.is_some(), true);
 
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_apply_bumps() -> Result<(), Box<dyn std::error::Error>> {
-        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
-        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+    fn test_get_bumps_release_config() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
 
-        create_multiple_dependency_packages(monorepo_dir)?;
+        let ref root = project_root.to_string();
 
-        let ref root = project_root.unwrap().to_string();
+        create_single_dependency_package(monorepo_dir)?;
+        create_single_dependency_changes(&root)?;
 
-        let packages = get_changed_packages(Some(String::from("main")), Some(root.to_string()))
-            .iter()
-            .map(|package| package.name.to_string())
-            .collect::<Vec<String>>();
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
 
-        init_changes(Some(root.to_string()), &None);
+        // get_bumps writes the versions, they're restored before bumping again
+        let bumps_count = |sync_deps: Option<bool>| -> Result<usize, Box<dyn std::error::Error>> {
+            let bumps = get_bumps(&BumpOptions {
+                changes: changes.to_owned(),
+                since: Some(String::from("main")),
+                sync_deps,
+                push: Some(false),
+                cwd: Some(root.to_string()),
+                ..Default::default()
+            })?;
 
-        for package in packages {
-            let change_package = Change {
-                package: package.to_string(),
-                release_as: Bump::Major,
-                deploy: vec![String::from("production")],
-            };
+            run_git(&monorepo_dir, &["checkout", "--", "packages"])?;
 
-            add_change(&change_package, Some(root.to_string()));
-        }
+            Ok(bumps.len())
+        };
 
-        let changes = get_change(String::from("feat/message"), Some(root.to_string()));
+        assert_eq!(bumps_count(None)?, 1);
 
-        let main_branch = Command::new("git")
+        std::fs::write(
+            monorepo_dir.join(RELEASE_CONFIG_FILE),
+            r#"{ "sync_deps": true }"#,
+        )?;
+
+        assert_eq!(bumps_count(None)?, 2);
+        assert_eq!(bumps_count(Some(false))?, 1);
+
+        std::fs::write(
+            monorepo_dir.join(RELEASE_CONFIG_FILE),
+            r#"{ "sync_deps": "yes" }"#,
+        )?;
+
+        let malformed = get_bumps(&BumpOptions {
+            changes: changes.to_owned(),
+            since: Some(String::from("main")),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(matches!(malformed, Err(BumpError::InvalidConfig(_))), true);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_bumps_sync_dep_kinds() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        // package-c only depends on package-b as a devDependency
+        let package_c_json_path = monorepo_dir.join("packages/package-c/package.json");
+        let mut package_c_json = serde_json::from_str::<serde_json::Value>(
+            std::fs::read_to_string(&package_c_json_path)?.as_str(),
+        )?;
+        package_c_json["devDependencies"] = serde_json::json!({ "@scope/package-b": "1.0.0" });
+        std::fs::write(
+            &package_c_json_path,
+            serde_json::to_string_pretty(&package_c_json)?,
+        )?;
+
+        git_add_all(&root.to_string())?;
+        git_commit(
+            String::from("chore: package-c dev dependency"),
+            None,
+            None,
+            None,
+            None,
+            Some(root.to_string()),
+        )?;
+
+        create_single_dependency_package(monorepo_dir)?;
+        create_single_dependency_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let bump_options = BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            sync_deps: Some(true),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        };
+
+        let bumps = get_bumps(&bump_options)?;
+        let bumped = |bumps: &Vec<BumpPackage>, name: &str| {
+            bumps.iter().any(|bump| bump.package_info.name == name)
+        };
+
+        assert_eq!(bumps.len(), 2);
+        assert_eq!(bumped(&bumps, "@scope/package-a"), true);
+        assert_eq!(bumped(&bumps, "@scope/package-c"), false);
+
+        // get_bumps writes the bumped versions, restore the manifests before bumping again
+        Command::new("git")
             .current_dir(&monorepo_dir)
             .arg("checkout")
-            .arg("main")
+            .arg("--")
+            .arg("packages")
             .stdout(Stdio::piped())
             .spawn()
-            .expect("Git checkout main problem");
+            .expect("Git checkout problem")
+            .wait_with_output()?;
 
-        main_branch.wait_with_output()?;
+        let bumps = get_bumps(&BumpOptions {
+            sync_dep_kinds: Some(vec![DependencyKind::Prod, DependencyKind::Dev]),
+            ..bump_options
+        })?;
 
-        let merge_branch = Command::new("git")
-            .current_dir(&monorepo_dir)
-            .arg("merge")
-            .arg("feat/message")
-            .stdout(Stdio::piped())
-            .spawn()
-            .expect("Git merge problem");
+        assert_eq!(bumps.len(), 3);
+        assert_eq!(bumped(&bumps, "@scope/package-c"), true);
 
-        merge_branch.wait_with_output()?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_needs_release() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        let commit = |content: &str, message: &str| -> Result<(), std::io::Error> {
+            std::fs::write(monorepo_dir.join("packages/package-b/index.js"), content)?;
+            git_add_all(&root.to_string())?;
+            git_commit(
+                message.to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some(root.to_string()),
+            )?;
+            Ok(())
+        };
+        let package_b = || {
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(
+            needs_release(&package_b(), Some(root.to_string()), None)?,
+            None
+        );
+
+        commit(r#"export const message = "hello";"#, "chore: add index")?;
+        commit(
+            r#"export const message = "hello!";"#,
+            "docs: document message",
+        )?;
+
+        assert_eq!(
+            needs_release(&package_b(), Some(root.to_string()), None)?,
+            None
+        );
+
+        commit(
+            r#"export const message = "hello world";"#,
+            "feat: message to the world",
+        )?;
+
+        assert_eq!(
+            needs_release(&package_b(), Some(root.to_string()), None)?,
+            Some(Bump::Minor)
+        );
+
+        commit(
+            r#"export const greeting = "hello";"#,
+            "refactor!: rename message",
+        )?;
+
+        assert_eq!(
+            needs_release(&package_b(), Some(root.to_string()), None)?,
+            Some(Bump::Major)
+        );
+
+        let ref merges_only = Some(NeedsReleaseOptions {
+            history_mode: Some(HistoryMode::MergesOnly),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            needs_release(&package_b(), Some(root.to_string()), merges_only.to_owned())?,
+            None
+        );
+
+        let ref docs_release = Some(NeedsReleaseOptions {
+            bump_rules: Some(vec![BumpRule {
+                commit_type: String::from("docs"),
+                scope: None,
+                bump: Bump::Patch,
+                breaking: None,
+            }]),
+            ..Default::default()
+        });
+
+        run_git(&monorepo_dir, &["reset", "--hard", "HEAD~2"])?;
+
+        assert_eq!(
+            needs_release(&package_b(), Some(root.to_string()), None)?,
+            None
+        );
+        assert_eq!(
+            needs_release(
+                &package_b(),
+                Some(root.to_string()),
+                docs_release.to_owned()
+            )?,
+            Some(Bump::Patch)
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_needs_release_with_tag_format() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        // The package is released with the slash format only
+        run_git(&monorepo_dir, &["tag", "-d", "@scope/package-b@1.0.0"])?;
+
+        std::fs::write(
+            monorepo_dir.join("packages/package-b/index.js"),
+            r#"export const message = "hello";"#,
+        )?;
+        git_add_all(&root.to_string())?;
+        git_commit(
+            String::from("feat: add message"),
+            None,
+            None,
+            None,
+            None,
+            Some(root.to_string()),
+        )?;
+        run_git(&monorepo_dir, &["tag", "@scope/package-b/v1.1.0"])?;
+
+        let package_b =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+        let slash_format = Some(NeedsReleaseOptions {
+            tag_format: Some(String::from("{name}/v{version}")),
+            ..Default::default()
+        });
+
+        // The commits range starts at the last tag of the format, the feature is released.
+        // The default format knows no release, every commit counts
+        assert_eq!(
+            needs_release(&package_b, Some(root.to_string()), slash_format)?,
+            None
+        );
+        assert_eq!(
+            needs_release(&package_b, Some(root.to_string()), None)?,
+            Some(Bump::Minor)
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
 
+    #[test]
+    fn test_get_bumps_skip_empty() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        // package-c has no commits since its release
+        init_changes(Some(root.to_string()), &None);
+        add_change(
+            &Change {
+                package: String::from("@scope/package-c"),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            },
+            Some(root.to_string()),
+            None,
+        );
+
+        let changes = get_change(String::from("main"), Some(root.to_string()), None);
         let bump_options = BumpOptions {
             changes,
             since: Some(String::from("main")),
-            release_as: Some(Bump::Minor),
-            fetch_all: None,
-            fetch_tags: None,
-            sync_deps: Some(true),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            skip_empty: Some(true),
+            ..Default::default()
+        };
+
+        let bumps = get_bumps(&bump_options)?;
+
+        assert_eq!(bumps.len(), 0);
+
+        let bumps = get_bumps(&BumpOptions {
+            skip_empty: None,
+            ..bump_options
+        })?;
+
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].to, String::from("1.0.1"));
+        assert_eq!(bumps[0].conventional_commits, Value::Array(vec![]));
+
+        let conventional = get_conventional_for_package(
+            &bumps[0].package_info,
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(bumps[0].to.to_string()),
+                ..Default::default()
+            }),
+        )?;
+
+        assert_eq!(conventional.changelog_output.contains("## [1.0.1]"), true);
+        assert_eq!(
+            conventional
+                .changelog_output
+                .contains(EMPTY_CHANGELOG_PLACEHOLDER),
+            true
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_bumps_release_channels() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        let change = Change {
+            package: String::from("@scope/package-b"),
+            release_as: Bump::Minor,
+            deploy: vec![String::from("production")],
+            exact_version: None,
+        };
+        let bump_options = |changes: Vec<Change>| BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            sync_deps: Some(false),
             push: Some(false),
             cwd: Some(root.to_string()),
+            channels: Some(vec![
+                ChannelRule {
+                    branch: String::from("main"),
+                    prerelease_id: None,
+                    dist_tag: Some(String::from("latest")),
+                },
+                ChannelRule {
+                    branch: String::from("next"),
+                    prerelease_id: Some(String::from("next")),
+                    dist_tag: Some(String::from("next")),
+                },
+            ]),
+            ..Default::default()
         };
 
-        let bumps = apply_bumps(&bump_options);
+        std::fs::write(
+            monorepo_dir.join("packages/package-b/index.js"),
+            r#"export const message = "hello";"#,
+        )?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(
+            &monorepo_dir,
+            &["commit", "-m", "feat: message to the world"],
+        )?;
+
+        init_changes(Some(root.to_string()), &None);
+        add_change(&change, Some(root.to_string()), None);
+
+        let changes = get_change(String::from("main"), Some(root.to_string()), None);
+        let bumps = get_bumps(&bump_options(changes))?;
+
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].to, String::from("1.1.0"));
+        assert_eq!(bumps[0].dist_tag, Some(String::from("latest")));
+
+        // get_bumps writes the bumped versions, restore the manifests before bumping again
+        run_git(&monorepo_dir, &["checkout", "--", "packages"])?;
+
+        // The next channel already released 1.1.0-next.1
+        run_git(&monorepo_dir, &["checkout", "-b", "next"])?;
+        let mut package_b =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+        package_b.update_version(String::from("1.1.0-next.1"));
+        package_b.write_package_json();
+        run_git(&monorepo_dir, &["add", "packages"])?;
+        run_git(
+            &monorepo_dir,
+            &[
+                "commit",
+                "-m",
+                "chore: release @scope/package-b to version 1.1.0-next.1",
+            ],
+        )?;
+        run_git(&monorepo_dir, &["tag", "@scope/package-b@1.1.0-next.1"])?;
+
+        std::fs::write(
+            monorepo_dir.join("packages/package-b/index.js"),
+            r#"export const message = "hello next";"#,
+        )?;
+        run_git(&monorepo_dir, &["add", "packages"])?;
+        run_git(
+            &monorepo_dir,
+            &["commit", "-m", "feat: message to the next world"],
+        )?;
+
+        add_change(&change, Some(root.to_string()), None);
+
+        let changes = get_change(String::from("next"), Some(root.to_string()), None);
+        let bumps = get_bumps(&bump_options(changes))?;
+
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].from, String::from("1.1.0-next.1"));
+        assert_eq!(bumps[0].to, String::from("1.1.0-next.2"));
+        assert_eq!(bumps[0].dist_tag, Some(String::from("next")));
+
+        let invalid_channel = get_bumps(&BumpOptions {
+            channels: Some(vec![ChannelRule {
+                branch: String::from("beta"),
+                prerelease_id: Some(String::from("beta!")),
+                dist_tag: None,
+            }]),
+            ..bump_options(get_change(
+                String::from("next"),
+                Some(root.to_string()),
+                None,
+            ))
+        });
+
+        assert_eq!(
+            invalid_channel.err(),
+            Some(BumpError::InvalidPrerelease(String::from("beta!")))
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_dependency_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        create_multiple_dependency_packages(monorepo_dir)?;
+        create_multiple_dependency_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let bumps = get_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            sync_deps: Some(true),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        })?;
 
         assert_eq!(bumps.len(), 3);
+
+        let first_bump = bumps.get(0);
+        let second_bump = bumps.get(1);
+        let third_bump = bumps.get(2);
+
+        assert_eq!(first_bump.is_some(), true);
+        assert_eq!(second_bump.is_some(), true);
+        assert_eq!(third_bump.is_some(), true);
+
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
+
+    #[test]
+    fn test_exact_version_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let invalid = get_bumps(&BumpOptions {
+            changes: vec![Change {
+                package: String::from("@scope/package-b"),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: Some(String::from("two")),
+            }],
+            since: Some(String::from("main")),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            invalid.err(),
+            Some(BumpError::InvalidExactVersion(String::from("two")))
+        );
+
+        let bumps = get_bumps(&BumpOptions {
+            changes: vec![Change {
+                package: String::from("@scope/package-b"),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: Some(String::from("2.0.0-beta.1")),
+            }],
+            since: Some(String::from("main")),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        })?;
+
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].from, "1.0.0");
+        assert_eq!(bumps[0].to, "2.0.0-beta.1");
+
+        run_git(&monorepo_dir, &["checkout", "--", "."])?;
+        run_git(&monorepo_dir, &["checkout", "-b", "feat/exact-version"])?;
+
+        // The exact version of a change added to the changes file is kept, a branch that isn't
+        // a release one still bumps a snapshot
+        init_changes(Some(root.to_string()), &None);
+        add_change(
+            &Change {
+                package: String::from("@scope/package-b"),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: Some(String::from("2.0.0-beta.1")),
+            },
+            Some(root.to_string()),
+            None,
+        );
+
+        let changes = get_change(
+            String::from("feat/exact-version"),
+            Some(root.to_string()),
+            None,
+        );
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].exact_version, Some(String::from("2.0.0-beta.1")));
+
+        let bumps = get_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        })?;
+
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].to.starts_with("1.0.0"), true);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_sha_source_get_bumps() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let commit_file = |file: &str, message: &str| -> Result<(), Box<dyn std::error::Error>> {
+            std::fs::write(monorepo_dir.join(file), message)?;
+            git_add_all(&root.to_string())?;
+            git_commit(
+                message.to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some(root.to_string()),
+            )?;
+            Ok(())
+        };
+
+        let snapshot_bump = |source: SnapshotShaSource| -> String {
+            let bumps = get_bumps(&BumpOptions {
+                changes: vec![Change {
+                    package: String::from("@scope/package-c"),
+                    release_as: Bump::Snapshot,
+                    deploy: vec![String::from("production")],
+                    exact_version: None,
+                }],
+                since: Some(String::from("main")),
+                sync_deps: Some(false),
+                push: Some(false),
+                cwd: Some(root.to_string()),
+                snapshot_sha_source: Some(source),
+                ..Default::default()
+            })
+            .unwrap();
+
+            // Restores the bumped package.json files so each run starts from the same versions
+            Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("checkout")
+                .arg("--")
+                .arg(".")
+                .output()
+                .expect("Git checkout problem");
+
+            bumps[0].to.to_string()
+        };
+
+        commit_file("packages/package-c/index.js", "feat: package c change")?;
+
+        let package_sha = git_current_sha(Some(root.to_string()));
+        let first = snapshot_bump(SnapshotShaSource::PackageLastCommit);
+
+        assert_eq!(first, format!("1.0.0-alpha.0.{}", package_sha));
+
+        commit_file("packages/package-b/index.js", "feat: package b change")?;
+
+        let second = snapshot_bump(SnapshotShaSource::PackageLastCommit);
+
+        assert_eq!(second, first);
+
+        // The test process runs in another repository, so the sha must come from cwd
+        let head = snapshot_bump(SnapshotShaSource::Head);
+
+        assert_eq!(
+            head,
+            format!("1.0.0-alpha.0.{}", git_current_sha(Some(root.to_string())))
+        );
+        assert_ne!(head, first);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_dependency_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        let packages = get_changed_packages(
+            Some(String::from("main")),
+            Some(root.to_string()),
+            &ChangedPackagesOptions::default(),
+        )?
+        .iter()
+        .map(|package| package.name.to_string())
+        .collect::<Vec<String>>();
+
+        init_changes(Some(root.to_string()), &None);
+
+        for package in packages {
+            let change_package = Change {
+                package: package.to_string(),
+                release_as: Bump::Major,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            };
+
+            add_change(&change_package, Some(root.to_string()), None);
+        }
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let main_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout main problem");
+
+        main_branch.wait_with_output()?;
+
+        let merge_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("merge")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git merge problem");
+
+        merge_branch.wait_with_output()?;
+
+        let bump_options = BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(true),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        };
+
+        let bumps = apply_bumps(&bump_options)?;
+
+        assert_eq!(bumps.len(), 3);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_release_pr_body() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm).unwrap();
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+
+        let ref root = project_root.to_string();
+
+        create_multiple_packages(monorepo_dir)?;
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let bumps = get_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Major),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        })?;
+
+        let body = generate_release_pr_body(&bumps, Some(root.to_string()))?;
+
+        assert_eq!(bumps.len(), 2);
+
+        for bump in &bumps {
+            let transition = format!("### {} {} → {}", bump.package_info.name, bump.from, bump.to);
+
+            assert_eq!(body.contains(&transition), true);
+        }
+
+        assert_eq!(body.matches("<summary>Changelog</summary>").count(), 2);
+        assert_eq!(body.contains("message to the world"), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_with_metrics() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let main_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout main problem");
+
+        main_branch.wait_with_output()?;
+
+        let merge_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("merge")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git merge problem");
+
+        merge_branch.wait_with_output()?;
+
+        let ref remote_dir = std::env::temp_dir().join(format!(
+            "{}-remote.git",
+            monorepo_dir.file_name().unwrap().to_str().unwrap()
+        ));
+
+        run_git(
+            &monorepo_dir,
+            &["init", "--bare", remote_dir.to_str().unwrap()],
+        )?;
+        run_git(
+            &monorepo_dir,
+            &["remote", "add", "origin", remote_dir.to_str().unwrap()],
+        )?;
+
+        let result = apply_bumps_with_metrics(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            fetch_all: Some(true),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            collect_metrics: Some(true),
+            ..Default::default()
+        })?;
+
+        let metrics = result.metrics.unwrap();
+
+        assert_eq!(result.bumps.len(), 2);
+        assert_eq!(metrics.packages.len(), result.bumps.len());
+        assert_eq!(metrics.tags, result.bumps.len() as u32);
+        assert_eq!(metrics.git_commands.get("tag"), Some(&2));
+        assert_eq!(metrics.git_commands.get("fetch"), Some(&1));
+        assert_eq!(metrics.pushes, 0);
+        assert_eq!(metrics.changelog_bytes > 0, true);
+
+        for bump in &result.bumps {
+            let package_metrics = metrics
+                .packages
+                .iter()
+                .find(|package| package.package == bump.package_info.name);
+
+            assert_eq!(package_metrics.is_some(), true);
+        }
+
+        remove_dir_all(&remote_dir)?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_commit_failure() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        run_git(&monorepo_dir, &["checkout", "main"])?;
+        run_git(&monorepo_dir, &["merge", "feat/message"])?;
+
+        // A staged file outside the release makes the release commit refuse to go through
+        std::fs::write(monorepo_dir.join("notes.md"), "work in progress")?;
+        run_git(&monorepo_dir, &["add", "notes.md"])?;
+
+        let result = apply_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(matches!(result, Err(BumpError::CommitFailed(_))), true);
+        assert_eq!(
+            run_git(&monorepo_dir, &["tag", "--list", "@scope/*@1.1.0"])?,
+            ""
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_single_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let main_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout main problem");
+
+        main_branch.wait_with_output()?;
+
+        let merge_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("merge")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git merge problem");
+
+        merge_branch.wait_with_output()?;
+
+        let head = git_current_sha(Some(root.to_string()));
+
+        let result = apply_bumps_with_metrics(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            collect_metrics: Some(true),
+            single_commit: Some(true),
+            ..Default::default()
+        })?;
+
+        let metrics = result.metrics.unwrap();
+
+        assert_eq!(result.bumps.len(), 2);
+        assert_eq!(metrics.commits, 1);
+        assert_eq!(metrics.tags, 2);
+        assert_eq!(
+            get_commit_count_since(Some(root.to_string()), Some(head), None),
+            1
+        );
+
+        let release_commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("rev-parse")
+            .arg("HEAD")
+            .output()?;
+        let release_commit = String::from_utf8(release_commit.stdout)?;
+
+        for bump in &result.bumps {
+            let tag_commit = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("rev-parse")
+                .arg(format!("{}@{}^{{commit}}", bump.package_info.name, bump.to))
+                .output()?;
+
+            assert_eq!(String::from_utf8(tag_commit.stdout)?, release_commit);
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    /// Releases package-a, package-b (its dependency) and a private package-c from feat/message
+    /// merged into main, running the publish command and returning the monorepo and its log.
+    fn apply_and_publish_monorepo(
+        publish_command: &str,
+    ) -> Result<(PathBuf, Result<Vec<BumpPackage>, BumpError>, String), Box<dyn std::error::Error>>
+    {
+        let monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref root = get_project_root_path(Some(monorepo_dir.to_path_buf())).unwrap();
+        let ref publish_log = monorepo_dir.join("publish.log");
+
+        create_multiple_dependency_packages(&monorepo_dir)?;
+
+        let ref package_c_json_path = monorepo_dir.join("packages/package-c/package.json");
+        let mut package_c_json =
+            serde_json::from_str::<Value>(&std::fs::read_to_string(package_c_json_path)?)?;
+        package_c_json["private"] = Value::Bool(true);
+        std::fs::write(
+            package_c_json_path,
+            serde_json::to_string_pretty(&package_c_json)?,
+        )?;
+        std::fs::write(
+            monorepo_dir.join("packages/package-c/index.js"),
+            r#"export const message = "hello package-c";"#,
+        )?;
+
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "feat: private package-c"])?;
+
+        create_multiple_dependency_changes(root)?;
+        add_change(
+            &Change {
+                package: String::from("@scope/package-c"),
+                release_as: Bump::Major,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            },
+            Some(root.to_string()),
+            None,
+        );
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        run_git(&monorepo_dir, &["checkout", "main"])?;
+        run_git(&monorepo_dir, &["merge", "feat/message"])?;
+
+        let result = apply_and_publish(
+            BumpOptions {
+                changes,
+                since: Some(String::from("main")),
+                release_as: Some(Bump::Major),
+                sync_deps: Some(false),
+                push: Some(false),
+                cwd: Some(root.to_string()),
+                ..Default::default()
+            },
+            format!("{{ {}; }} >> {}", publish_command, publish_log.display()),
+        );
+
+        let log = std::fs::read_to_string(publish_log).unwrap_or_default();
+
+        Ok((monorepo_dir, result, log))
+    }
+
+    #[test]
+    fn test_apply_and_publish() -> Result<(), Box<dyn std::error::Error>> {
+        let (ref monorepo_dir, result, log) = apply_and_publish_monorepo(
+            r#"echo "$PKG_NAME $PKG_PREVIOUS_VERSION $PKG_VERSION $(basename "$PWD") $(git tag --list "@scope/package-[ab]@2.0.0" | tr '\n' ' ')""#,
+        )?;
+
+        let bumps = result?;
+
+        // Each package is published once released, before its dependents are
+        assert_eq!(bumps.len(), 3);
+        assert_eq!(
+            log,
+            "@scope/package-b 1.0.0 2.0.0 package-b @scope/package-b@2.0.0 \n\
+             @scope/package-a 1.0.0 2.0.0 package-a @scope/package-a@2.0.0 @scope/package-b@2.0.0 \n"
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_and_publish_aborts_on_failure() -> Result<(), Box<dyn std::error::Error>> {
+        let (ref monorepo_dir, result, log) =
+            apply_and_publish_monorepo(r#"echo "$PKG_NAME"; echo "registry down" >&2; exit 1"#)?;
+
+        assert_eq!(
+            result.err(),
+            Some(BumpError::PublishFailed {
+                package: String::from("@scope/package-b"),
+                reason: String::from("registry down"),
+            })
+        );
+        assert_eq!(log, "@scope/package-b\n");
+
+        // The release stops, the dependents of the failed package aren't released
+        let tags = run_git(&monorepo_dir, &["tag", "--list", "@scope/package-a@2.0.0"])?;
+
+        assert_eq!(tags, String::new());
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_without_writing_changelog() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let main_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout main problem");
+
+        main_branch.wait_with_output()?;
+
+        let merge_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("merge")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git merge problem");
+
+        merge_branch.wait_with_output()?;
+
+        let bumps = apply_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            write_changelog: Some(false),
+            ..Default::default()
+        })?;
+
+        assert_eq!(bumps.len(), 2);
+
+        for bump in &bumps {
+            let changelog_path =
+                PathBuf::from(bump.package_info.package_path.to_string()).join(CHANGELOG_FILE);
+
+            assert_eq!(changelog_path.exists(), false);
+            assert_eq!(
+                bump.changelog
+                    .as_ref()
+                    .map(|changelog| changelog.contains(&format!("[{}]", bump.to)))
+                    .unwrap_or(false),
+                true
+            );
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_deployment_targets() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let bump_package = |package: &str, to: &str| BumpPackage {
+            from: String::from("1.0.0"),
+            to: to.to_string(),
+            package_info: get_package_info(package.to_string(), Some(root.to_string()))
+                .unwrap()
+                .unwrap(),
+            conventional_commits: Value::Null,
+            changelog: None,
+            dist_tag: None,
+            warnings: vec![],
+        };
+
+        let bumps = vec![
+            bump_package("@scope/package-a", "1.1.0"),
+            bump_package("@scope/package-b", "2.0.0"),
+            bump_package("@scope/package-d", "1.0.1"),
+        ];
+        let changes = vec![
+            Change {
+                package: String::from("@scope/package-a"),
+                release_as: Bump::Minor,
+                deploy: vec![String::from("int"), String::from("production")],
+                exact_version: None,
+            },
+            Change {
+                package: String::from("@scope/package-b"),
+                release_as: Bump::Major,
+                deploy: vec![String::from("production"), String::from("staging")],
+                exact_version: None,
+            },
+        ];
+
+        let targets = get_deployment_targets(&bumps, &changes);
+
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets["int"], vec!["@scope/package-a@1.1.0"]);
+        assert_eq!(
+            targets["production"],
+            vec!["@scope/package-a@1.1.0", "@scope/package-b@2.0.0"]
+        );
+        assert_eq!(targets["staging"], vec!["@scope/package-b@2.0.0"]);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_rerun_already_released() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let main_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout main problem");
+
+        main_branch.wait_with_output()?;
+
+        let merge_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("merge")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git merge problem");
+
+        merge_branch.wait_with_output()?;
+
+        let options = BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            collect_metrics: Some(true),
+            ..Default::default()
+        };
+
+        let first = apply_bumps_with_metrics(&options)?;
+
+        assert_eq!(first.bumps.len(), 2);
+        assert_eq!(
+            first
+                .packages
+                .iter()
+                .all(|package| package.status == ReleaseStatus::Released),
+            true
+        );
+
+        let head = git_current_sha(Some(root.to_string()));
+        let tags = get_remote_or_local_tags(Some(root.to_string()), Some(true))?.len();
+
+        let second = apply_bumps_with_metrics(&options)?;
+        let metrics = second.metrics.unwrap();
+
+        assert_eq!(second.bumps.len(), 0);
+        assert_eq!(second.packages.len(), 2);
+        assert_eq!(
+            second
+                .packages
+                .iter()
+                .all(|package| package.status == ReleaseStatus::AlreadyReleased),
+            true
+        );
+
+        for bump in &first.bumps {
+            let package = second
+                .packages
+                .iter()
+                .find(|package| package.package == bump.package_info.name)
+                .unwrap();
+
+            assert_eq!(package.version, bump.to);
+        }
+
+        assert_eq!(metrics.git_commands.len(), 0);
+        assert_eq!(metrics.commits + metrics.tags + metrics.pushes, 0);
+        assert_eq!(git_current_sha(Some(root.to_string())), head);
+        assert_eq!(
+            get_remote_or_local_tags(Some(root.to_string()), Some(true))?.len(),
+            tags
+        );
+
+        for bump in &first.bumps {
+            assert_eq!(
+                package_workdir_unclean(&bump.package_info, Some(root.to_string())),
+                false
+            );
+        }
+
+        // A retried job checks out the merge commit again, before the release commits
+        run_git(&monorepo_dir, &["checkout", "-B", "main", "HEAD~2"])?;
+
+        let retry = apply_bumps_with_metrics(&options)?;
+
+        assert_eq!(retry.bumps.len(), 0);
+        assert_eq!(retry.metrics.unwrap().git_commands.len(), 0);
+
+        for bump in &first.bumps {
+            let package = retry
+                .packages
+                .iter()
+                .find(|package| package.package == bump.package_info.name)
+                .unwrap();
+
+            assert_eq!(package.version, bump.to);
+            assert_eq!(package.status, ReleaseStatus::AlreadyReleased);
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_dependent_after_release() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_dependency_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_dependency_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        run_git(&monorepo_dir, &["checkout", "main"])?;
+        run_git(&monorepo_dir, &["merge", "feat/message"])?;
+
+        let first = apply_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            sync_deps: Some(true),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        })?;
+
+        assert_eq!(
+            first
+                .iter()
+                .any(|bump| bump.package_info.name == "@scope/package-b" && bump.to == "2.0.0"),
+            true
+        );
+
+        // The next release only changes package-b, its dependent package-a is bumped with it
+        run_git(&monorepo_dir, &["checkout", "-b", "feat/next"])?;
+        std::fs::write(
+            monorepo_dir.join("packages/package-b/index.js"),
+            r#"export const message = "hello again package-b";"#,
+        )?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(
+            &monorepo_dir,
+            &["commit", "-m", "feat: message to package-b"],
+        )?;
+
+        add_change(
+            &Change {
+                package: String::from("@scope/package-b"),
+                release_as: Bump::Minor,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            },
+            Some(root.to_string()),
+            None,
+        );
+
+        let changes = get_change(String::from("feat/next"), Some(root.to_string()), None);
+
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "chore: add change"])?;
+        run_git(&monorepo_dir, &["checkout", "main"])?;
+        run_git(&monorepo_dir, &["merge", "feat/next"])?;
+
+        let second = apply_bumps_with_metrics(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            sync_deps: Some(true),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        })?;
+
+        let package_a = second
+            .packages
+            .iter()
+            .find(|package| package.package == "@scope/package-a")
+            .unwrap();
+        let package_b = second
+            .packages
+            .iter()
+            .find(|package| package.package == "@scope/package-b")
+            .unwrap();
+
+        assert_eq!(package_a.status, ReleaseStatus::Released);
+        assert_eq!(package_b.status, ReleaseStatus::Released);
+        assert_eq!(package_b.version, String::from("2.1.0"));
+
+        let package_a_json =
+            get_package_info(String::from("@scope/package-a"), Some(root.to_string()))?
+                .unwrap()
+                .pkg_json;
+
+        assert_eq!(
+            package_a_json["dependencies"]["@scope/package-b"],
+            serde_json::json!("2.1.0")
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_tag_format() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        run_git(&monorepo_dir, &["checkout", "main"])?;
+        run_git(&monorepo_dir, &["merge", "feat/message"])?;
+
+        let invalid = apply_bumps(&BumpOptions {
+            changes: changes.to_owned(),
+            since: Some(String::from("main")),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            tag_format: Some(String::from("v{version}")),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            invalid.err(),
+            Some(BumpError::InvalidTagFormat(String::from("v{version}")))
+        );
+
+        std::fs::write(
+            monorepo_dir.join(RELEASE_CONFIG_FILE),
+            r#"{ "tag_format": "{name}/v{version}" }"#,
+        )?;
+        git_add_all(&root.to_string())?;
+        git_commit(
+            String::from("chore: release config"),
+            None,
+            None,
+            None,
+            None,
+            Some(root.to_string()),
+        )?;
+
+        let options = BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            collect_metrics: Some(true),
+            ..Default::default()
+        };
+
+        let first = apply_bumps_with_metrics(&options)?;
+
+        assert_eq!(first.bumps.len(), 2);
+
+        let tags = get_remote_or_local_tags(Some(root.to_string()), Some(true))?;
+
+        for bump in &first.bumps {
+            let tag = format!("refs/tags/{}/v{}", bump.package_info.name, bump.to);
+
+            assert_eq!(tags.iter().any(|remote| remote.tag == tag), true);
+        }
+
+        let second = apply_bumps_with_metrics(&options)?;
+
+        assert_eq!(second.bumps.len(), 0);
+        assert_eq!(
+            second
+                .packages
+                .iter()
+                .all(|package| package.status == ReleaseStatus::AlreadyReleased),
+            true
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_with_temp_worktree() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let main_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout main problem");
+
+        main_branch.wait_with_output()?;
+
+        let merge_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("merge")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git merge problem");
+
+        merge_branch.wait_with_output()?;
+
+        let git_status = || -> Result<String, std::io::Error> {
+            let status = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("status")
+                .arg("--porcelain")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git status problem");
+
+            Ok(String::from_utf8(status.wait_with_output()?.stdout).unwrap())
+        };
+
+        let status_before = git_status()?;
+        let sha_before = git_current_sha(Some(root.to_string()));
+
+        let bumps = apply_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            use_temp_worktree: Some(true),
+            ..Default::default()
+        })?;
+
+        let tags = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("tag")
+            .arg("--list")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git tag problem");
+        let tags = String::from_utf8(tags.wait_with_output()?.stdout).unwrap();
+
+        let worktrees = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("worktree")
+            .arg("list")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git worktree problem");
+        let worktrees = String::from_utf8(worktrees.wait_with_output()?.stdout).unwrap();
+
+        assert_eq!(bumps.len(), 2);
+        assert_eq!(git_status()?, status_before);
+        assert_eq!(git_current_sha(Some(root.to_string())), sha_before);
+        assert_eq!(worktrees.lines().count(), 1);
+
+        for bump in &bumps {
+            let ref package_tag = format!("{}@{}", bump.package_info.name, bump.to);
+
+            assert_eq!(tags.lines().any(|tag| tag == package_tag), true);
+            assert_eq!(bump.package_info.package_path.starts_with(root), true);
+
+            let package_info =
+                get_package_info(bump.package_info.name.to_string(), Some(root.to_string()))?
+                    .unwrap();
+
+            assert_eq!(package_info.version, bump.from);
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_with_train_tag() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_multiple_packages(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_multiple_changes(&root)?;
+
+        let changes = get_change(String::from("feat/message"), Some(root.to_string()), None);
+
+        let main_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout main problem");
+
+        main_branch.wait_with_output()?;
+
+        let merge_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("merge")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git merge problem");
+
+        merge_branch.wait_with_output()?;
+
+        let options = BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            release_as: Some(Bump::Minor),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            use_temp_worktree: Some(true),
+            version_source: Some(VersionSource::Tags),
+            train_tag: Some(TrainTagOptions {
+                format: String::from("release/{date}.{sequence}"),
+                message_template: None,
+            }),
+            ..Default::default()
+        };
+
+        let first_release = apply_bumps_with_metrics(&options)?;
+        let second_release = apply_bumps_with_metrics(&options)?;
+
+        let date = Utc::now().format("%Y-%m-%d").to_string();
+
+        let first_train_tag = first_release.train_tag.unwrap();
+        let second_train_tag = second_release.train_tag.unwrap();
+
+        assert_eq!(first_train_tag.name, format!("release/{}.1", date));
+        assert_eq!(second_train_tag.name, format!("release/{}.2", date));
+        assert_eq!(second_train_tag.packages.len(), second_release.bumps.len());
+
+        let annotation = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("tag")
+            .arg("--list")
+            .arg("--format=%(contents)")
+            .arg(&second_train_tag.name)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git tag problem");
+        let annotation = String::from_utf8(annotation.wait_with_output()?.stdout).unwrap();
+
+        for bump in &second_release.bumps {
+            let ref package = format!("{}@{}", bump.package_info.name, bump.to);
+
+            assert_eq!(second_train_tag.packages.contains(package), true);
+            assert_eq!(annotation.lines().any(|line| line == package), true);
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_bumps_with_versions_manifest() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let release = |package: &str, release_as: Bump| {
+            apply_bumps(&BumpOptions {
+                changes: vec![Change {
+                    package: String::from(package),
+                    release_as,
+                    deploy: vec![String::from("production")],
+                    exact_version: None,
+                }],
+                since: Some(String::from("main")),
+                release_as: Some(release_as),
+                sync_deps: Some(false),
+                push: Some(false),
+                cwd: Some(root.to_string()),
+                versions_manifest: Some(String::from("versions.json")),
+                ..Default::default()
+            })
+        };
+        let committed_files = || -> Result<String, std::io::Error> {
+            let show = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("show")
+                .arg("--name-only")
+                .arg("--format=")
+                .arg("HEAD")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git show problem");
+
+            Ok(String::from_utf8(show.wait_with_output()?.stdout).unwrap())
+        };
+
+        assert_eq!(release("@scope/package-a", Bump::Minor)?.len(), 1);
+
+        let first_manifest = read_versions_manifest(Some(root.to_string()), None)?;
+
+        assert_eq!(first_manifest.len(), 4);
+        assert_eq!(first_manifest["@scope/package-a"], "1.1.0");
+        assert_eq!(first_manifest["@scope/package-b"], "1.0.0");
+        assert_eq!(committed_files()?.contains("versions.json"), true);
+
+        assert_eq!(release("@scope/package-b", Bump::Patch)?.len(), 1);
+
+        let second_manifest = read_versions_manifest(Some(root.to_string()), None)?;
+        let changed = second_manifest
+            .iter()
+            .filter(|(package, version)| first_manifest.get(*package) != Some(version))
+            .map(|(package, _)| package.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(changed, vec![String::from("@scope/package-b")]);
+        assert_eq!(second_manifest["@scope/package-b"], "1.0.1");
+        assert_eq!(committed_files()?.contains("versions.json"), true);
+        assert_eq!(
+            verify_versions_manifest(Some(root.to_string()), None)?,
+            vec![]
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_version_progression() -> Result<(), Box<dyn std::error::Error>> {
+        let package = String::from("@scope/package-a");
+        let progression = |from: &str, to: &str, release_as: Bump| {
+            validate_version_progression(
+                &package,
+                &from.to_string(),
+                &SemVersion::parse(to).unwrap(),
+                release_as,
+            )
+        };
+
+        assert_eq!(progression("1.0.0", "1.0.1", Bump::Patch), Ok(()));
+        assert_eq!(
+            progression("2.0.0-beta.1", "2.0.0-beta.2", Bump::Patch),
+            Ok(())
+        );
+        assert_eq!(progression("2.0.0-beta.2", "2.0.0", Bump::Patch), Ok(()));
+        assert_eq!(
+            progression("1.0.0", "1.0.0-alpha.0.abc1234", Bump::Snapshot),
+            Ok(())
+        );
+        assert_eq!(
+            progression("1.2.0", "1.1.0", Bump::Minor),
+            Err(BumpError::VersionRegression {
+                package: package.to_string(),
+                from: String::from("1.2.0"),
+                to: String::from("1.1.0"),
+            })
+        );
+        assert_eq!(progression("1.2.0", "1.2.0", Bump::Patch).is_err(), true);
+        assert_eq!(
+            progression("1.2.0", "1.2.0-rc.1", Bump::Patch).is_err(),
+            true
+        );
+
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let regression = get_bumps(&BumpOptions {
+            changes: vec![Change {
+                package: String::from("@scope/package-b"),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: Some(String::from("0.9.0")),
+            }],
+            since: Some(String::from("main")),
+            sync_deps: Some(false),
+            push: Some(false),
+            cwd: Some(root.to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            regression.err(),
+            Some(BumpError::VersionRegression {
+                package: String::from("@scope/package-b"),
+                from: String::from("1.0.0"),
+                to: String::from("0.9.0"),
+            })
+        );
+
+        let package_info =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+
+        assert_eq!(package_info.version, "1.0.0");
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_version_tag_alignment() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        update_packages_field(
+            "version",
+            Value::String(String::from("1.5.0")),
+            Some(vec![String::from("@scope/package-a")]),
+            Some(root.to_string()),
+        )?;
+
+        let tag_b = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("tag")
+            .arg("-a")
+            .arg("@scope/package-b@1.3.2")
+            .arg("-m")
+            .arg("chore: release package-b@1.3.2")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git tag problem");
+
+        tag_b.wait_with_output()?;
+
+        let alignments = check_version_tag_alignment(Some(root.to_string()))?;
+        let alignment_of = |package: &str| {
+            alignments
+                .iter()
+                .find(|alignment| alignment.package == package)
+                .unwrap()
+                .to_owned()
+        };
+
+        assert_eq!(
+            alignment_of("@scope/package-a").alignment,
+            VersionAlignment::ManifestAhead
+        );
+        assert_eq!(
+            alignment_of("@scope/package-b").alignment,
+            VersionAlignment::ManifestBehind
+        );
+        assert_eq!(
+            alignment_of("@scope/package-b").tag_version,
+            Some(String::from("1.3.2"))
+        );
+        assert_eq!(
+            alignment_of("@scope/package-c").alignment,
+            VersionAlignment::Aligned
+        );
+        assert_eq!(alignment_of("@scope/package-d").tag_version, None);
+
+        assert_eq!(
+            validate_version_tag_alignment(&alignments, &None),
+            Err(BumpError::ManifestBehind {
+                package: String::from("@scope/package-b"),
+                manifest_version: String::from("1.0.0"),
+                tag_version: String::from("1.3.2"),
+            })
+        );
+        assert_eq!(
+            validate_version_tag_alignment(&alignments, &Some(VersionSource::Manifest)),
+            Ok(())
+        );
+
+        let changes = ["@scope/package-a", "@scope/package-b"]
+            .iter()
+            .map(|package| Change {
+                package: package.to_string(),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            })
+            .collect::<Vec<Change>>();
+
+        let behind = get_bumps(&BumpOptions {
+            changes: changes.to_owned(),
+            since: Some(String::from("main")),
+            cwd: Some(root.to_string()),
+            ignore_release_artifacts: Some(false),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            behind.err(),
+            Some(BumpError::ManifestBehind {
+                package: String::from("@scope/package-b"),
+                manifest_version: String::from("1.0.0"),
+                tag_version: String::from("1.3.2"),
+            })
+        );
+
+        let ahead = get_bumps(&BumpOptions {
+            changes: changes[..1].to_vec(),
+            since: Some(String::from("main")),
+            cwd: Some(root.to_string()),
+            ignore_release_artifacts: Some(false),
+            ..Default::default()
+        })?;
+
+        assert_eq!(ahead[0].to, String::from("1.5.1"));
+        assert_eq!(
+            ahead[0].warnings,
+            vec![String::from(
+                "Package @scope/package-a version 1.5.0 is ahead of its latest tag version 1.0.0"
+            )]
+        );
+
+        let mut bumps = get_bumps(&BumpOptions {
+            changes,
+            since: Some(String::from("main")),
+            cwd: Some(root.to_string()),
+            version_source: Some(VersionSource::Tags),
+            // package-a only has a version change, bump it anyway
+            ignore_release_artifacts: Some(false),
+            ..Default::default()
+        })?;
+        bumps.sort_by(|a, b| a.package_info.name.cmp(&b.package_info.name));
+
+        assert_eq!(bumps[0].from, String::from("1.0.0"));
+        assert_eq!(bumps[0].to, String::from("1.0.1"));
+        assert_eq!(bumps[1].from, String::from("1.3.2"));
+        assert_eq!(bumps[1].to, String::from("1.3.3"));
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_recommend_bump_protect_breaking_commits() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        File::create(monorepo_dir.join("packages/package-b/engines.js"))?;
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("refactor(clippy)!: drop node 16 support")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        let package_info =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+
+        let skipped = get_breaking_commits_for_package(
+            &package_info,
+            Some(root.to_string()),
+            false,
+            None,
+            &None,
+        )?;
+        let protected = get_breaking_commits_for_package(
+            &package_info,
+            Some(root.to_string()),
+            true,
+            None,
+            &None,
+        )?;
+
+        assert_eq!(skipped.len(), 0);
+        assert_eq!(protected.len(), 1);
+        assert_eq!(protected[0].message.contains("drop node 16 support"), true);
+
+        let recommend_bump = |protect_breaking_commits: Option<bool>,
+                              bump_rules: Option<Vec<BumpRule>>| {
+            get_package_recommend_bump(
+                &package_info,
+                root,
+                Some(BumpOptions {
+                    changes: vec![],
+                    since: Some(String::from("main")),
+                    sync_deps: Some(false),
+                    push: Some(false),
+                    cwd: Some(root.to_string()),
+                    protect_breaking_commits,
+                    bump_rules,
+                    ..Default::default()
+                }),
+            )
+        };
+        let refactor_rules = Some(vec![BumpRule {
+            commit_type: String::from("refactor"),
+            scope: None,
+            bump: Bump::Patch,
+            breaking: Some(Bump::Minor),
+        }]);
+
+        assert_eq!(recommend_bump(None, None)?.to, "1.0.1");
+        assert_eq!(recommend_bump(Some(true), None)?.to, "2.0.0");
+        assert_eq!(recommend_bump(Some(true), refactor_rules)?.to, "1.1.0");
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_recommend_bump_deploy_trailers() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        for (file, footer) in [("api.js", "Deploy: staging, qa"), ("web.js", "Deploy: int")] {
+            File::create(monorepo_dir.join("packages/package-c").join(file))?;
+
+            git_add_all(root)?;
+            git_commit(
+                format!("feat: add {}", file),
+                None,
+                Some(footer.to_string()),
+                None,
+                None,
+                Some(root.to_string()),
+            )?;
+        }
+
+        let recommend_bump = |package: &str| {
+            let package_info = get_package_info(package.to_string(), Some(root.to_string()))?;
+
+            get_package_recommend_bump(
+                &package_info.unwrap(),
+                root,
+                Some(BumpOptions {
+                    changes: vec![],
+                    since: Some(String::from("main")),
+                    sync_deps: Some(false),
+                    push: Some(false),
+                    cwd: Some(root.to_string()),
+                    ..Default::default()
+                }),
+            )
+        };
+
+        assert_eq!(
+            recommend_bump("@scope/package-c")?.deploy_to,
+            vec!["int", "qa", "staging"]
+        );
+        assert_eq!(
+            recommend_bump("@scope/package-b")?.deploy_to,
+            vec!["production"]
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_recommend_bump_with_bump_rules() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let commit_package_c = |file: &str, message: &str| -> Result<(), std::io::Error> {
+            File::create(monorepo_dir.join("packages/package-c").join(file))?;
+
+            git_add_all(root)?;
+            git_commit(
+                message.to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some(root.to_string()),
+            )?;
+
+            Ok(())
+        };
+
+        let recommend_bump = |bump_rules: Option<Vec<BumpRule>>| {
+            let package_info =
+                get_package_info(String::from("@scope/package-c"), Some(root.to_string())).unwrap();
+
+            get_package_recommend_bump(
+                &package_info.unwrap(),
+                root,
+                Some(BumpOptions {
+                    changes: vec![],
+                    since: Some(String::from("main")),
+                    sync_deps: Some(false),
+                    push: Some(false),
+                    cwd: Some(root.to_string()),
+                    bump_rules,
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+            .to
+        };
+        let perf_rules = Some(
+            [
+                vec![BumpRule {
+                    commit_type: String::from("perf"),
+                    scope: None,
+                    bump: Bump::Minor,
+                    breaking: None,
+                }],
+                default_bump_rules(),
+            ]
+            .concat(),
+        );
+
+        commit_package_c("cache.js", "perf: cache the lookups")?;
+
+        assert_eq!(recommend_bump(None), String::from("1.0.1"));
+        assert_eq!(
+            recommend_bump(Some(default_bump_rules())),
+            String::from("1.0.1")
+        );
+        assert_eq!(recommend_bump(perf_rules.to_owned()), String::from("1.1.0"));
+
+        commit_package_c("index.js", "perf!: drop the legacy lookups")?;
+
+        assert_eq!(recommend_bump(perf_rules.to_owned()), String::from("2.0.0"));
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_major_policy_bump_version() {
+        let version = String::from("0.3.1");
+        let policies = [
+            (
+                ZeroMajorPolicy::Standard,
+                [
+                    (Bump::Major, "1.0.0"),
+                    (Bump::Minor, "0.4.0"),
+                    (Bump::Patch, "0.3.2"),
+                ],
+            ),
+            (
+                ZeroMajorPolicy::BreakingBumpsMinor,
+                [
+                    (Bump::Major, "0.4.0"),
+                    (Bump::Minor, "0.3.2"),
+                    (Bump::Patch, "0.3.2"),
+                ],
+            ),
+        ];
+
+        for (policy, bumps) in policies {
+            for (release_as, expected) in bumps {
+                let semversion = bump_version(version.to_string(), release_as, Some(policy), None);
+
+                assert_eq!(semversion.to_string(), expected);
+            }
+        }
+
+        let semversion = bump_version(
+            String::from("1.2.3"),
+            Bump::Major,
+            Some(ZeroMajorPolicy::BreakingBumpsMinor),
+            None,
+        );
+
+        assert_eq!(semversion.to_string(), "2.0.0");
+    }
+
+    #[test]
+    fn test_bump_snapshot_with_strategy() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let version = String::from("1.2.3");
+
+        let sha = git_current_sha(project_root.to_owned());
+        let sha_version = Bump::bump_snapshot_with_strategy(
+            version.to_string(),
+            &SnapshotStrategy::Sha,
+            project_root,
+        )?;
+
+        assert_eq!(sha_version.to_string(), format!("1.2.3-alpha.0.{}", sha));
+
+        let timestamp_version = Bump::bump_snapshot_with_strategy(
+            version.to_string(),
+            &SnapshotStrategy::Timestamp,
+            None,
+        )?;
+
+        assert_eq!(timestamp_version.pre.as_str().starts_with("alpha.20"), true);
+
+        let counter_version = Bump::bump_snapshot_with_strategy(
+            version.to_string(),
+            &SnapshotStrategy::Counter,
+            None,
+        )?;
+        let next_counter_version = Bump::bump_snapshot_with_strategy(
+            counter_version.to_string(),
+            &SnapshotStrategy::Counter,
+            None,
+        )?;
+
+        assert_eq!(counter_version.to_string(), "1.2.3-alpha.0");
+        assert_eq!(next_counter_version.to_string(), "1.2.3-alpha.1");
+
+        let custom_version = Bump::bump_snapshot_with_strategy(
+            version.to_string(),
+            &SnapshotStrategy::Custom(String::from("build-1234")),
+            None,
+        )?;
+
+        assert_eq!(custom_version.to_string(), "1.2.3-build-1234");
+
+        for identifier in ["", "build_1234", "build..1234", "build/1234"] {
+            let invalid = Bump::bump_snapshot_with_strategy(
+                version.to_string(),
+                &SnapshotStrategy::Custom(String::from(identifier)),
+                None,
+            );
+
+            assert_eq!(
+                invalid,
+                Err(BumpError::InvalidPrerelease(String::from(identifier)))
+            );
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_bumps_ignores_release_artifacts() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+        let ref since = git_current_sha(Some(root.to_string()));
+
+        let commit = |message: &str| -> Result<(), std::io::Error> {
+            git_add_all(root)?;
+            git_commit(
+                message.to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some(root.to_string()),
+            )?;
+
+            Ok(())
+        };
+
+        let bumps = || {
+            get_bumps(&BumpOptions {
+                changes: vec![Change {
+                    package: String::from("@scope/package-b"),
+                    release_as: Bump::Minor,
+                    deploy: vec![String::from("production")],
+                    exact_version: None,
+                }],
+                since: Some(since.to_string()),
+                cwd: Some(root.to_string()),
+                ..Default::default()
+            })
+        };
+
+        // A release commit of package-b brought from another branch
+        update_packages_field(
+            "version",
+            Value::String(String::from("1.1.0")),
+            Some(vec![String::from("@scope/package-b")]),
+            Some(root.to_string()),
+        )?;
+        let mut changelog = File::create(monorepo_dir.join("packages/package-b/CHANGELOG.md"))?;
+        changelog.write_all("# What changed?\n\n## [1.1.0]\n".as_bytes())?;
+        commit("chore: release @scope/package-b@1.1.0")?;
+
+        let changed_packages = get_changed_packages(
+            Some(since.to_string()),
+            Some(root.to_string()),
+            &ChangedPackagesOptions {
+                ignore_release_artifacts: Some(true),
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(changed_packages.len(), 0);
+        assert_eq!(bumps()?.len(), 0);
+
+        File::create(monorepo_dir.join("packages/package-b/index.js"))?;
+        commit("feat: message to the world")?;
+
+        let bumps = bumps()?;
+
+        assert_eq!(bumps.len(), 1);
+        assert_eq!(bumps[0].from, String::from("1.1.0"));
+        assert_eq!(bumps[0].to, String::from("1.2.0"));
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_recommend_bump_package_json_format() -> Result<(), Box<dyn std::error::Error>> {
+        let package_info = serde_json::json!({
+            "name": "@scope/package-b",
+            "private": false,
+            "package_json_path": "/monorepo/packages/package-b/package.json",
+            "package_path": "/monorepo/packages/package-b",
+            "package_relative_path": "packages/package-b",
+            "pkg_json": { "name": "@scope/package-b", "version": "1.0.0" },
+            "root": false,
+            "version": "1.0.0",
+            "url": "https://github.com/websublime/monorepo/packages/package-b",
+            "repository_info": null,
+            "changed_files": ["/monorepo/packages/package-b/index.js"],
+            "dependencies": []
+        });
+        let json = serde_json::json!({
+            "from": "1.0.0",
+            "to": "1.1.0",
+            "package_info": package_info,
+            "conventional": {
+                "package_info": package_info,
+                "conventional_config": {},
+                "conventional_commits": [],
+                "changelog_output": "## [1.1.0]"
+            },
+            "changed_files": ["/monorepo/packages/package-b/index.js"],
+            "deploy_to": ["production"]
+        });
+
+        // Recommendations are cached as json, reading them back must give the same json
+        let recommend_bump = serde_json::from_value::<RecommendBumpPackage>(json.to_owned())?;
+
+        assert_eq!(recommend_bump.package_info.name, "@scope/package-b");
+        assert_eq!(serde_json::to_value(&recommend_bump)?, json);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bump_options_json_format() -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::json!({
+            "changes": [{
+                "package": "@scope/package-b",
+                "release_as": "Minor",
+                "deploy": ["production"],
+                "exact_version": null
+            }],
+            "since": "main",
+            "release_as": null,
+            "fetch_all": null,
+            "fetch_tags": null,
+            "sync_deps": true,
+            "push": false,
+            "cwd": null,
+            "include_packages": null,
+            "exclude_packages": ["@scope/package-d"],
+            "changes_path": null,
+            "collect_metrics": null,
+            "zero_major_policy": null,
+            "use_temp_worktree": null,
+            "version_source": null,
+            "train_tag": null,
+            "protect_breaking_commits": null,
+            "versions_manifest": null,
+            "snapshot_sha_source": null,
+            "single_commit": null,
+            "write_changelog": null,
+            "bump_rules": null,
+            "prerelease_policy": "StableOnly",
+            "ignore_release_artifacts": null,
+            "sync_dep_kinds": ["Prod", "Peer"],
+            "channels": null,
+            "skip_empty": null,
+            "history_mode": "MergesOnly",
+            "changelog_file": null,
+            "tag_format": null
+        });
+
+        let options = serde_json::from_value::<BumpOptions>(json.to_owned())?;
+
+        assert_eq!(options.history_mode, Some(HistoryMode::MergesOnly));
+        assert_eq!(serde_json::to_value(&options)?, json);
+        Ok(())
+    }
 }
@@ -3,7 +3,8 @@
 //! # Changes
 //!
 //! This module is responsible for managing the changes in the monorepo.
-//! The changes are stored in a `.changes.json` file in the root of the project.
+//! The changes are stored in a `.changes.json` file in the root of the project, or
+//! in a `.changes` directory with one file per branch (see `ChangesStorage`).
 //!
 //! # Example
 //! ```json
@@ -20,18 +21,26 @@
 //!   }
 //!}
 //!```
+use semver::Version as SemVersion;
 use serde::{Deserialize, Serialize};
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use std::{
     collections::BTreeMap,
-    fs::File,
+    fs::{create_dir_all, read_dir, remove_file, rename, File},
     io::BufReader,
     path::{Path, PathBuf},
 };
 
-use crate::bumps::Bump;
+use crate::bumps::{bump_version, Bump};
+use crate::config::read_release_config;
+use crate::errors::WorkspaceError;
 
-use super::git::git_current_branch;
+use super::git::{
+    get_commits_since, get_last_known_publish_tag_info_for_package_with_policy, git_current_branch,
+    git_current_sha, CommitsSinceOptions,
+};
+use super::manager::PackageManagerError;
+use super::packages::{get_changed_packages, get_packages, ChangedPackagesOptions, PackageInfo};
 use super::paths::get_project_root_path;
 
 /// Dynamic data structure to store changes
@@ -44,6 +53,7 @@ pub struct ChangesOptions {
     pub message: Option<String>,
     pub git_user_name: Option<String>,
     pub git_user_email: Option<String>,
+    pub changes_path: Option<String>,
 }
 
 #[cfg(feature = "napi")]
@@ -53,6 +63,7 @@ pub struct ChangesOptions {
     pub message: Option<String>,
     pub git_user_name: Option<String>,
     pub git_user_email: Option<String>,
+    pub changes_path: Option<String>,
 }
 
 #[cfg(not(feature = "napi"))]
@@ -91,11 +102,13 @@ pub struct Changes {
 
 #[cfg(not(feature = "napi"))]
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
-/// Data structure to store a change
+/// Data structure to store a change. When `exact_version` is set the package is released
+/// with that version (e.g. `2.0.0-beta.1`) instead of the one computed from `release_as`.
 pub struct Change {
     pub package: String,
     pub release_as: Bump,
     pub deploy: Vec<String>,
+    pub exact_version: Option<String>,
 }
 
 #[cfg(feature = "napi")]
@@ -105,6 +118,277 @@ pub struct Change {
     pub package: String,
     pub release_as: Bump,
     pub deploy: Vec<String>,
+    pub exact_version: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Status of a pending change. Versions are `None` when the package isn't in the monorepo.
+pub struct PackageChangeStatus {
+    pub package: String,
+    pub release_as: Bump,
+    pub current_version: Option<String>,
+    pub next_version: Option<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PackageChangeStatus {
+    pub package: String,
+    pub release_as: Bump,
+    pub current_version: Option<String>,
+    pub next_version: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Status of the pending changes of a branch
+pub struct BranchChangesStatus {
+    pub branch: String,
+    pub packages: Vec<PackageChangeStatus>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct BranchChangesStatus {
+    pub branch: String,
+    pub packages: Vec<PackageChangeStatus>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Status of the pending changes of every branch
+pub struct ChangesStatus {
+    pub branches: Vec<BranchChangesStatus>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ChangesStatus {
+    pub branches: Vec<BranchChangesStatus>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Audit of the changes of a branch against its changed packages. `missing` are changed
+/// packages without a change, `extraneous` are changes of packages that didn't change.
+pub struct ChangesAudit {
+    pub missing: Vec<String>,
+    pub extraneous: Vec<String>,
+    pub ok: Vec<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ChangesAudit {
+    pub missing: Vec<String>,
+    pub extraneous: Vec<String>,
+    pub ok: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Storage used to persist the changes.
+/// `SingleFile` keeps every branch in one json file (default `.changes.json`), while
+/// `PerBranchDirectory` writes one json file per branch (default `.changes/`), which avoids
+/// merge conflicts on a single shared file.
+pub enum ChangesStorage {
+    SingleFile(PathBuf),
+    PerBranchDirectory(PathBuf),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Changes of a single branch, as stored by `ChangesStorage::PerBranchDirectory`.
+struct BranchChangesFileData {
+    pub branch: String,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Settings of the changes, as stored by `ChangesStorage::PerBranchDirectory`.
+struct ChangesSettingsFileData {
+    pub message: Option<String>,
+    pub git_user_name: Option<String>,
+    pub git_user_email: Option<String>,
+}
+
+impl ChangesStorage {
+    /// Resolves the storage for the project root. A provided path (relative to the root) ending
+    /// in `.json` is a single file, any other path is a directory. Without a path, an existing
+    /// `.changes` directory is used, falling back to the `.changes.json` file.
+    pub fn resolve(root: &String, changes_path: &Option<String>) -> ChangesStorage {
+        let root_path = Path::new(root);
+
+        match changes_path {
+            Some(path) => {
+                let ref storage_path = root_path.join(path);
+
+                match storage_path.extension() {
+                    Some(extension) if extension == "json" => {
+                        ChangesStorage::SingleFile(storage_path.to_path_buf())
+                    }
+                    _ => ChangesStorage::PerBranchDirectory(storage_path.to_path_buf()),
+                }
+            }
+            None => {
+                let ref changes_dir = root_path.join(String::from(".changes"));
+
+                if changes_dir.is_dir() {
+                    ChangesStorage::PerBranchDirectory(changes_dir.to_path_buf())
+                } else {
+                    ChangesStorage::SingleFile(root_path.join(String::from(".changes.json")))
+                }
+            }
+        }
+    }
+
+    /// Encodes a branch name to be used as a file name. ASCII alphanumerics, `-`, `_` and
+    /// `.` (except a leading one) are kept and any other byte is percent-encoded, so
+    /// `feat/a` is stored in `feat%2Fa.json` and never shares a file with `feat_a`.
+    fn branch_file_name(branch: &String) -> String {
+        let encoded = branch
+            .bytes()
+            .enumerate()
+            .map(|(index, byte)| {
+                let keep = byte.is_ascii_alphanumeric()
+                    || byte == b'-'
+                    || byte == b'_'
+                    || (byte == b'.' && index > 0);
+
+                match keep {
+                    true => (byte as char).to_string(),
+                    false => format!("%{:02X}", byte),
+                }
+            })
+            .collect::<String>();
+
+        format!("{}.json", encoded)
+    }
+
+    /// Writes a json file atomically: the content goes to a temporary sibling file
+    /// that is renamed over the target, so readers never see a partial file.
+    fn write_json<T: Serialize>(path: &PathBuf, data: &T) {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        let ref temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+        let temp_file = File::create(temp_path).unwrap();
+        let mut temp_writer = BufWriter::new(temp_file);
+
+        serde_json::to_writer_pretty(&mut temp_writer, data).unwrap();
+        temp_writer.flush().unwrap();
+
+        rename(temp_path, path).unwrap();
+    }
+
+    /// Check if the storage was initialized.
+    pub fn exists(&self) -> bool {
+        match self {
+            ChangesStorage::SingleFile(path) => path.is_file(),
+            ChangesStorage::PerBranchDirectory(path) => path.join(".settings.json").is_file(),
+        }
+    }
+
+    /// Read all the changes, merging the branch files in directory mode.
+    pub fn read(&self) -> Option<ChangesFileData> {
+        if !self.exists() {
+            return None;
+        }
+
+        match self {
+            ChangesStorage::SingleFile(path) => {
+                let changes_file = File::open(path).unwrap();
+                let changes_reader = BufReader::new(changes_file);
+
+                Some(serde_json::from_reader(changes_reader).unwrap())
+            }
+            ChangesStorage::PerBranchDirectory(path) => {
+                let settings_file = File::open(path.join(".settings.json")).unwrap();
+                let settings_reader = BufReader::new(settings_file);
+                let settings: ChangesSettingsFileData =
+                    serde_json::from_reader(settings_reader).unwrap();
+
+                let mut changes = ChangesData::new();
+
+                for entry in read_dir(path).unwrap() {
+                    let branch_path = entry.unwrap().path();
+                    let is_branch_file = branch_path.extension().map_or(false, |ext| ext == "json")
+                        && !branch_path.ends_with(".settings.json");
+
+                    if is_branch_file {
+                        let branch_file = File::open(&branch_path).unwrap();
+                        let branch_reader = BufReader::new(branch_file);
+                        let branch_changes: BranchChangesFileData =
+                            serde_json::from_reader(branch_reader).unwrap();
+
+                        changes.insert(branch_changes.branch, branch_changes.changes);
+                    }
+                }
+
+                Some(ChangesFileData {
+                    message: settings.message,
+                    git_user_name: settings.git_user_name,
+                    git_user_email: settings.git_user_email,
+                    changes,
+                })
+            }
+        }
+    }
+
+    /// Write all the changes, one file per branch in directory mode.
+    pub fn write(&self, changes: &ChangesFileData) {
+        match self {
+            ChangesStorage::SingleFile(path) => {
+                // Custom locations (e.g. `.config/changes.json`) may not exist yet
+                if let Some(parent) = path.parent() {
+                    create_dir_all(parent).unwrap();
+                }
+
+                ChangesStorage::write_json(path, &changes);
+            }
+            ChangesStorage::PerBranchDirectory(path) => {
+                create_dir_all(path).unwrap();
+
+                let settings = ChangesSettingsFileData {
+                    message: changes.message.to_owned(),
+                    git_user_name: changes.git_user_name.to_owned(),
+                    git_user_email: changes.git_user_email.to_owned(),
+                };
+                ChangesStorage::write_json(&path.join(".settings.json"), &settings);
+
+                let branch_files = changes
+                    .changes
+                    .keys()
+                    .map(|branch| ChangesStorage::branch_file_name(branch))
+                    .collect::<Vec<String>>();
+
+                for entry in read_dir(path).unwrap() {
+                    let branch_path = entry.unwrap().path();
+                    let file_name = branch_path.file_name().unwrap().to_str().unwrap();
+                    let is_stale = file_name.ends_with(".json")
+                        && file_name != ".settings.json"
+                        && !branch_files.contains(&file_name.to_string());
+
+                    if is_stale {
+                        remove_file(&branch_path).unwrap();
+                    }
+                }
+
+                for (branch, branch_changes) in changes.changes.iter() {
+                    let branch_data = BranchChangesFileData {
+                        branch: branch.to_string(),
+                        changes: branch_changes.to_vec(),
+                    };
+                    ChangesStorage::write_json(
+                        &path.join(ChangesStorage::branch_file_name(branch)),
+                        &branch_data,
+                    );
+                }
+            }
+        }
+    }
 }
 
 /// Initialize the changes file. If the file does not exist, it will create it with the default message.
@@ -118,14 +402,13 @@ pub fn init_changes(
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
-
-    if changes_path.exists() {
-        let changes_file = File::open(changes_path).unwrap();
-        let changes_reader = BufReader::new(changes_file);
+    let changes_path = match &change_options {
+        Some(options) => options.changes_path.to_owned(),
+        None => None,
+    };
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
 
-        let changes: ChangesFileData = serde_json::from_reader(changes_reader).unwrap();
+    if let Some(changes) = storage.read() {
         return changes;
     } else {
         let message = match &change_options {
@@ -159,31 +442,22 @@ pub fn init_changes(
             changes: ChangesData::new(),
         };
 
-        let changes_file = File::create(changes_path).unwrap();
-        let changes_writer = BufWriter::new(changes_file);
-
-        serde_json::to_writer_pretty(changes_writer, &changes).unwrap();
+        storage.write(&changes);
 
         return changes;
     }
 }
 
 /// Add a change to the changes file in the root of the project.
-pub fn add_change(change: &Change, cwd: Option<String>) -> bool {
+pub fn add_change(change: &Change, cwd: Option<String>, changes_path: Option<String>) -> bool {
     let ref root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
-
-    if changes_path.exists() {
-        let changes_file = File::open(changes_path).unwrap();
-        let changes_reader = BufReader::new(changes_file);
-
-        let mut changes: ChangesFileData = serde_json::from_reader(changes_reader).unwrap();
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
 
+    if let Some(mut changes) = storage.read() {
         let current_branch = git_current_branch(Some(root.to_string()));
 
         let branch = match current_branch {
@@ -203,6 +477,7 @@ pub fn add_change(change: &Change, cwd: Option<String>) -> bool {
                     package: change.package.to_string(),
                     release_as: change.release_as,
                     deploy: change.deploy.to_vec(),
+                    exact_version: change.exact_version.to_owned(),
                 });
             }
         } else {
@@ -212,14 +487,12 @@ pub fn add_change(change: &Change, cwd: Option<String>) -> bool {
                     package: change.package.to_string(),
                     release_as: change.release_as,
                     deploy: change.deploy.to_vec(),
+                    exact_version: change.exact_version.to_owned(),
                 }],
             );
         }
 
-        let changes_file = File::create(changes_path).unwrap();
-        let changes_writer = BufWriter::new(changes_file);
-
-        serde_json::to_writer_pretty(changes_writer, &changes).unwrap();
+        storage.write(&changes);
 
         return true;
     }
@@ -228,28 +501,23 @@ pub fn add_change(change: &Change, cwd: Option<String>) -> bool {
 }
 
 /// Remove a change from the changes file in the root of the project.
-pub fn remove_change(branch_name: String, cwd: Option<String>) -> bool {
+pub fn remove_change(
+    branch_name: String,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> bool {
     let ref root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
-
-    if changes_path.exists() {
-        let changes_file = File::open(changes_path).unwrap();
-        let changes_reader = BufReader::new(changes_file);
-
-        let mut changes: ChangesFileData = serde_json::from_reader(changes_reader).unwrap();
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
 
+    if let Some(mut changes) = storage.read() {
         if changes.changes.contains_key(&branch_name) {
             changes.changes.remove(&branch_name);
 
-            let changes_file = File::create(changes_path).unwrap();
-            let changes_writer = BufWriter::new(changes_file);
-
-            serde_json::to_writer_pretty(changes_writer, &changes).unwrap();
+            storage.write(&changes);
 
             return true;
         }
@@ -259,21 +527,15 @@ pub fn remove_change(branch_name: String, cwd: Option<String>) -> bool {
 }
 
 /// Get all changes from the changes file in the root of the project.
-pub fn get_changes(cwd: Option<String>) -> Changes {
+pub fn get_changes(cwd: Option<String>, changes_path: Option<String>) -> Changes {
     let ref root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
-
-    if changes_path.exists() {
-        let changes_file = File::open(changes_path).unwrap();
-        let changes_reader = BufReader::new(changes_file);
-
-        let changes: ChangesFileData = serde_json::from_reader(changes_reader).unwrap();
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
 
+    if let Some(changes) = storage.read() {
         return Changes {
             changes: changes.changes,
         };
@@ -285,21 +547,19 @@ pub fn get_changes(cwd: Option<String>) -> Changes {
 }
 
 /// Get all changes for a specific branch from the changes file in the root of the project.
-pub fn get_change(branch: String, cwd: Option<String>) -> Vec<Change> {
+pub fn get_change(
+    branch: String,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> Vec<Change> {
     let ref root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
-
-    if changes_path.exists() {
-        let changes_file = File::open(changes_path).unwrap();
-        let changes_reader = BufReader::new(changes_file);
-
-        let changes: ChangesFileData = serde_json::from_reader(changes_reader).unwrap();
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
 
+    if let Some(changes) = storage.read() {
         if changes.changes.contains_key(&branch) {
             return changes.changes.get(&branch).unwrap().to_vec();
         } else {
@@ -315,21 +575,16 @@ pub fn get_package_change(
     package_name: String,
     branch: String,
     cwd: Option<String>,
+    changes_path: Option<String>,
 ) -> Option<Change> {
     let ref root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
-
-    if changes_path.exists() {
-        let changes_file = File::open(changes_path).unwrap();
-        let changes_reader = BufReader::new(changes_file);
-
-        let changes: ChangesFileData = serde_json::from_reader(changes_reader).unwrap();
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
 
+    if let Some(changes) = storage.read() {
         if changes.changes.contains_key(&branch) {
             let branch_changes = changes.changes.get(&branch).unwrap();
 
@@ -351,21 +606,20 @@ pub fn get_package_change(
 }
 
 /// Check if a change exists in the changes file in the root of the project.
-pub fn change_exist(branch: String, packages_name: Vec<String>, cwd: Option<String>) -> bool {
+pub fn change_exist(
+    branch: String,
+    packages_name: Vec<String>,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> bool {
     let ref root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
-
-    if changes_path.exists() {
-        let changes_file = File::open(changes_path).unwrap();
-        let changes_reader = BufReader::new(changes_file);
-
-        let changes: ChangesFileData = serde_json::from_reader(changes_reader).unwrap();
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
 
+    if let Some(changes) = storage.read() {
         if changes.changes.contains_key(&branch) {
             let branch_changes = changes.changes.get(&branch).unwrap();
 
@@ -396,16 +650,371 @@ pub fn change_exist(branch: String, packages_name: Vec<String>, cwd: Option<Stri
 }
 
 /// Check if a changes file exists in the root of the project.
-pub fn changes_file_exist(cwd: Option<String>) -> bool {
+pub fn changes_file_exist(cwd: Option<String>, changes_path: Option<String>) -> bool {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    ChangesStorage::resolve(root, &changes_path).exists()
+}
+
+/// Get the packages ready to be released, the ones with a change for the current branch
+/// or with commits since their last publish tag (of the release config tag format). Packages
+/// are returned only once. Fails when the packages or the publish tags can't be listed.
+pub fn get_pending_packages(
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> Result<Vec<PackageInfo>, WorkspaceError> {
     let ref root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let root_path = Path::new(root);
-    let ref changes_path = root_path.join(String::from(".changes.json"));
+    let branch_changes = match git_current_branch(Some(root.to_string())) {
+        Some(branch) => get_change(branch, Some(root.to_string()), changes_path),
+        None => vec![],
+    };
 
-    changes_path.exists()
+    let ref tag_format = read_release_config(Some(root.to_string()))
+        .unwrap_or_default()
+        .tag_format;
+    let mut pending_packages = vec![];
+
+    for package in get_packages(Some(root.to_string()))? {
+        let has_change = branch_changes
+            .iter()
+            .any(|change| change.package == package.name);
+
+        let pending = match has_change {
+            true => true,
+            false => {
+                let hash = get_last_known_publish_tag_info_for_package_with_policy(
+                    &package,
+                    Some(root.to_string()),
+                    None,
+                    tag_format,
+                )?
+                .map(|tag_info| tag_info.hash);
+
+                get_commits_since(
+                    Some(root.to_string()),
+                    hash,
+                    Some(package.package_relative_path.to_string()),
+                    &CommitsSinceOptions::default(),
+                )
+                .len()
+                    > 0
+            }
+        };
+
+        if pending {
+            pending_packages.push(package);
+        }
+    }
+
+    Ok(pending_packages)
+}
+
+/// Audit the changes of a branch (current branch by default) against the packages changed
+/// since a sha (default `origin/main`). Private packages are never required to have a change.
+pub fn audit_changes(
+    branch: Option<String>,
+    since: Option<String>,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> Result<ChangesAudit, PackageManagerError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let branch = branch
+        .or_else(|| git_current_branch(Some(root.to_string())))
+        .unwrap_or(String::from("main"));
+    let since = since.unwrap_or(String::from("origin/main"));
+
+    let changed_packages = get_changed_packages(
+        Some(since),
+        Some(root.to_string()),
+        &ChangedPackagesOptions::default(),
+    )?;
+
+    let mut changes_packages = get_change(branch, Some(root.to_string()), changes_path)
+        .iter()
+        .map(|change| change.package.to_string())
+        .collect::<Vec<String>>();
+    changes_packages.sort();
+    changes_packages.dedup();
+
+    let mut ok = vec![];
+    let mut missing = vec![];
+
+    for package in &changed_packages {
+        let name = package.name.to_string();
+
+        match (changes_packages.contains(&name), package.private) {
+            (true, _) => ok.push(name),
+            (false, false) => missing.push(name),
+            (false, true) => {}
+        }
+    }
+
+    ok.sort();
+    ok.dedup();
+    missing.sort();
+    missing.dedup();
+
+    let extraneous = changes_packages
+        .into_iter()
+        .filter(|package| !ok.contains(package))
+        .collect::<Vec<String>>();
+
+    Ok(ChangesAudit {
+        missing,
+        extraneous,
+        ok,
+    })
+}
+
+/// Get the changes, of every branch, for packages that no longer exist in the monorepo.
+pub fn find_orphaned_changes(
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> Result<Vec<Change>, PackageManagerError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let packages_names = get_packages(Some(root.to_string()))?
+        .iter()
+        .map(|package| package.name.to_string())
+        .collect::<Vec<String>>();
+
+    let orphaned = get_changes(Some(root.to_string()), changes_path)
+        .changes
+        .values()
+        .flatten()
+        .filter(|change| !packages_names.contains(&change.package))
+        .cloned()
+        .collect::<Vec<Change>>();
+
+    Ok(orphaned)
+}
+
+/// Summarize the pending changes of every branch: the packages with a change, the requested
+/// bump and the current and next version of each package.
+pub fn changes_status(
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> Result<ChangesStatus, PackageManagerError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref packages = get_packages(Some(root.to_string()))?;
+
+    let branches = get_changes(Some(root.to_string()), changes_path)
+        .changes
+        .iter()
+        .map(|(branch, branch_changes)| {
+            let packages = branch_changes
+                .iter()
+                .map(|change| {
+                    let current_version = packages
+                        .iter()
+                        .find(|package| package.name == change.package)
+                        .map(|package| package.version.to_string());
+                    let next_version = current_version.as_ref().map(|version| {
+                        let snapshot_sha = (change.release_as == Bump::Snapshot)
+                            .then(|| git_current_sha(Some(root.to_string())));
+
+                        bump_version(version.to_string(), change.release_as, None, snapshot_sha)
+                            .to_string()
+                    });
+
+                    PackageChangeStatus {
+                        package: change.package.to_string(),
+                        release_as: change.release_as,
+                        current_version,
+                        next_version,
+                    }
+                })
+                .collect::<Vec<PackageChangeStatus>>();
+
+            BranchChangesStatus {
+                branch: branch.to_string(),
+                packages,
+            }
+        })
+        .collect::<Vec<BranchChangesStatus>>();
+
+    Ok(ChangesStatus { branches })
+}
+
+/// Validates the changes before they are written: branch and package names can't be empty
+/// and a package can only have one change per branch. With `check_packages`, every package
+/// must also exist in the monorepo.
+fn validate_changes(
+    data: &ChangesFileData,
+    root: &String,
+    check_packages: bool,
+) -> Result<(), WorkspaceError> {
+    let packages_names = match check_packages {
+        true => get_packages(Some(root.to_string()))?
+            .iter()
+            .map(|package| package.name.to_string())
+            .collect::<Vec<String>>(),
+        false => vec![],
+    };
+
+    for (branch, branch_changes) in data.changes.iter() {
+        if branch.trim().is_empty() {
+            return Err(WorkspaceError::InvalidChanges(String::from(
+                "branch name can't be empty",
+            )));
+        }
+
+        let mut seen_packages: Vec<&String> = vec![];
+
+        for change in branch_changes.iter() {
+            if change.package.trim().is_empty() {
+                return Err(WorkspaceError::InvalidChanges(format!(
+                    "package name can't be empty on branch {}",
+                    branch
+                )));
+            }
+
+            if seen_packages.contains(&&change.package) {
+                return Err(WorkspaceError::InvalidChanges(format!(
+                    "package {} has more than one change on branch {}",
+                    change.package, branch
+                )));
+            }
+
+            if check_packages && !packages_names.contains(&change.package) {
+                return Err(WorkspaceError::PackageNotFound(change.package.to_string()));
+            }
+
+            if let Some(ref exact_version) = change.exact_version {
+                if SemVersion::parse(exact_version).is_err() {
+                    return Err(WorkspaceError::InvalidChanges(format!(
+                        "exact version {} of package {} is not a valid semver version",
+                        exact_version, change.package
+                    )));
+                }
+            }
+
+            seen_packages.push(&change.package);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a release as value (`major`, `minor`, `patch` or `snapshot`, case insensitive).
+fn parse_release_as(release_as: &String) -> Result<Bump, WorkspaceError> {
+    match release_as.trim().to_lowercase().as_str() {
+        "major" => Ok(Bump::Major),
+        "minor" => Ok(Bump::Minor),
+        "patch" => Ok(Bump::Patch),
+        "snapshot" => Ok(Bump::Snapshot),
+        _ => Err(WorkspaceError::InvalidChanges(format!(
+            "invalid release as value: {}",
+            release_as
+        ))),
+    }
+}
+
+/// Write the whole changes data back to the changes storage, replacing its content.
+/// The data is validated before anything is written (see `validate_changes`), and
+/// `check_packages` also requires every package to exist in the monorepo.
+pub fn write_changes(
+    data: ChangesFileData,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+    check_packages: Option<bool>,
+) -> Result<(), WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    validate_changes(&data, root, check_packages.unwrap_or(false))?;
+
+    ChangesStorage::resolve(root, &changes_path).write(&data);
+
+    Ok(())
+}
+
+/// Update the change of a package on a branch with the given mutation and write it back.
+/// Fails if the changes storage wasn't initialized or the package has no change on the branch.
+pub fn update_change(
+    package: String,
+    branch: String,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+    f: impl FnOnce(&mut Change),
+) -> Result<(), WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref storage = ChangesStorage::resolve(root, &changes_path);
+
+    let mut changes = match storage.read() {
+        Some(changes) => changes,
+        None => return Err(WorkspaceError::FileNotFound(String::from("changes"))),
+    };
+
+    let change = changes
+        .changes
+        .get_mut(&branch)
+        .and_then(|branch_changes| {
+            branch_changes
+                .iter_mut()
+                .find(|change| change.package == package)
+        })
+        .ok_or(WorkspaceError::PackageNotFound(format!(
+            "{} on branch {}",
+            package, branch
+        )))?;
+
+    f(change);
+
+    write_changes(changes, Some(root.to_string()), changes_path, None)
+}
+
+/// Set the release as value (`major`, `minor`, `patch` or `snapshot`) of a package change.
+/// Invalid values are rejected before anything is written.
+pub fn set_change_release_as(
+    package: String,
+    branch: String,
+    release_as: String,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> Result<(), WorkspaceError> {
+    let release_as = parse_release_as(&release_as)?;
+
+    update_change(package, branch, cwd, changes_path, |change| {
+        change.release_as = release_as;
+    })
+}
+
+/// Set the deploy targets of a package change.
+pub fn set_change_deploy(
+    package: String,
+    branch: String,
+    deploy: Vec<String>,
+    cwd: Option<String>,
+    changes_path: Option<String>,
+) -> Result<(), WorkspaceError> {
+    update_change(package, branch, cwd, changes_path, |change| {
+        change.deploy = deploy;
+    })
 }
 
 #[cfg(test)]
@@ -415,7 +1024,8 @@ mod tests {
     use crate::manager::PackageManager;
     use crate::paths::get_project_root_path;
     use crate::utils::create_test_monorepo;
-    use std::fs::remove_dir_all;
+    use std::fs::{remove_dir_all, File};
+    use std::process::{Command, Stdio};
 
     #[test]
     fn test_init_changes() -> Result<(), Box<dyn std::error::Error>> {
@@ -444,12 +1054,13 @@ mod tests {
             package: String::from("test-package"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        let result = add_change(&change, Some(root.to_string()));
+        let result = add_change(&change, Some(root.to_string()), None);
 
         assert_eq!(result, true);
         assert_eq!(changes_path.is_file(), true);
@@ -457,6 +1068,213 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_changes_status() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        init_changes(Some(root.to_string()), &None);
+
+        for (package, release_as) in [
+            ("@scope/package-a", Bump::Major),
+            ("@scope/package-b", Bump::Minor),
+        ] {
+            let change = Change {
+                package: String::from(package),
+                release_as,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            };
+
+            add_change(&change, Some(root.to_string()), None);
+        }
+
+        let status = changes_status(Some(root.to_string()), None)?;
+        let branch = &status.branches[0];
+
+        assert_eq!(status.branches.len(), 1);
+        assert_eq!(branch.branch, String::from("main"));
+        assert_eq!(
+            branch.packages,
+            vec![
+                PackageChangeStatus {
+                    package: String::from("@scope/package-a"),
+                    release_as: Bump::Major,
+                    current_version: Some(String::from("1.0.0")),
+                    next_version: Some(String::from("2.0.0")),
+                },
+                PackageChangeStatus {
+                    package: String::from("@scope/package-b"),
+                    release_as: Bump::Minor,
+                    current_version: Some(String::from("1.0.0")),
+                    next_version: Some(String::from("1.1.0")),
+                },
+            ]
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let change = Change {
+            package: String::from("@scope/package-a"),
+            release_as: Bump::Major,
+            deploy: vec![String::from("production")],
+            exact_version: None,
+        };
+
+        init_changes(Some(root.to_string()), &None);
+        add_change(&change, Some(root.to_string()), None);
+
+        let mut changes_data = init_changes(Some(root.to_string()), &None);
+        let branch_changes = changes_data.changes.get_mut("main").unwrap();
+        branch_changes[0].release_as = Bump::Minor;
+        branch_changes[0].deploy = vec![String::from("int"), String::from("stg")];
+
+        write_changes(
+            changes_data.to_owned(),
+            Some(root.to_string()),
+            None,
+            Some(true),
+        )?;
+
+        assert_eq!(init_changes(Some(root.to_string()), &None), changes_data);
+
+        set_change_release_as(
+            String::from("@scope/package-a"),
+            String::from("main"),
+            String::from("patch"),
+            Some(root.to_string()),
+            None,
+        )?;
+        set_change_deploy(
+            String::from("@scope/package-a"),
+            String::from("main"),
+            vec![String::from("production")],
+            Some(root.to_string()),
+            None,
+        )?;
+
+        let changes = get_change(String::from("main"), Some(root.to_string()), None);
+
+        assert_eq!(changes[0].release_as, Bump::Patch);
+        assert_eq!(changes[0].deploy, vec![String::from("production")]);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_changes_invalid() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let change = Change {
+            package: String::from("@scope/package-a"),
+            release_as: Bump::Major,
+            deploy: vec![String::from("production")],
+            exact_version: None,
+        };
+
+        init_changes(Some(root.to_string()), &None);
+        add_change(&change, Some(root.to_string()), None);
+
+        let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
+        let content = std::fs::read_to_string(changes_path)?;
+
+        let result = set_change_release_as(
+            String::from("@scope/package-a"),
+            String::from("main"),
+            String::from("huge"),
+            Some(root.to_string()),
+            None,
+        );
+
+        assert_eq!(result.is_err(), true);
+        assert_eq!(std::fs::read_to_string(changes_path)?, content);
+
+        let mut changes_data = init_changes(Some(root.to_string()), &None);
+        changes_data.changes.get_mut("main").unwrap()[0].package = String::from(" ");
+
+        let result = write_changes(changes_data, Some(root.to_string()), None, None);
+
+        assert_eq!(result.is_err(), true);
+        assert_eq!(std::fs::read_to_string(changes_path)?, content);
+
+        let mut changes_data = init_changes(Some(root.to_string()), &None);
+        changes_data.changes.get_mut("main").unwrap()[0].package = String::from("unknown");
+
+        let result = write_changes(changes_data, Some(root.to_string()), None, Some(true));
+
+        assert_eq!(result.is_err(), true);
+        assert_eq!(std::fs::read_to_string(changes_path)?, content);
+
+        let mut changes_data = init_changes(Some(root.to_string()), &None);
+        changes_data.changes.get_mut("main").unwrap()[0].exact_version = Some(String::from("2.0"));
+
+        let result = write_changes(changes_data, Some(root.to_string()), None, None);
+
+        assert_eq!(result.is_err(), true);
+        assert_eq!(std::fs::read_to_string(changes_path)?, content);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_changes_file_path() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+        let changes_path = Some(String::from(".config/release-changes.json"));
+
+        let change = Change {
+            package: String::from("@scope/package-a"),
+            release_as: Bump::Minor,
+            deploy: vec![String::from("production")],
+            exact_version: None,
+        };
+
+        init_changes(
+            Some(root.to_string()),
+            &Some(ChangesOptions {
+                message: None,
+                git_user_name: None,
+                git_user_email: None,
+                changes_path: changes_path.to_owned(),
+            }),
+        );
+        add_change(&change, Some(root.to_string()), changes_path.to_owned());
+
+        let changes = get_change(
+            String::from("main"),
+            Some(root.to_string()),
+            changes_path.to_owned(),
+        );
+
+        assert_eq!(changes, vec![change]);
+        assert_eq!(
+            monorepo_dir.join(".config/release-changes.json").is_file(),
+            true
+        );
+        assert_eq!(monorepo_dir.join(".changes.json").exists(), false);
+        assert_eq!(
+            get_change(String::from("main"), Some(root.to_string()), None).len(),
+            0
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
     #[test]
     fn test_duplicate_add_change() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
@@ -468,15 +1286,16 @@ mod tests {
             package: String::from("test-package"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        add_change(&change, Some(root.to_string()));
-        add_change(&change, Some(root.to_string()));
+        add_change(&change, Some(root.to_string()), None);
+        add_change(&change, Some(root.to_string()), None);
 
-        let changes = get_changes(Some(root.to_string()));
+        let changes = get_changes(Some(root.to_string()), None);
         let length = changes.changes["main"].len();
 
         assert_eq!(length, 1);
@@ -496,14 +1315,15 @@ mod tests {
             package: String::from("test-package"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        add_change(&change, Some(root.to_string()));
+        add_change(&change, Some(root.to_string()), None);
 
-        let result = remove_change(String::from("main"), Some(root.to_string()));
+        let result = remove_change(String::from("main"), Some(root.to_string()), None);
 
         assert_eq!(result, true);
         assert_eq!(changes_path.is_file(), true);
@@ -522,14 +1342,15 @@ mod tests {
             package: String::from("test-package"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        add_change(&change, Some(root.to_string()));
+        add_change(&change, Some(root.to_string()), None);
 
-        let changes = get_changes(Some(root.to_string()));
+        let changes = get_changes(Some(root.to_string()), None);
 
         assert_eq!(changes.changes.contains_key(&String::from("main")), true);
         assert_eq!(changes.changes.get(&String::from("main")).unwrap().len(), 1);
@@ -549,14 +1370,15 @@ mod tests {
             package: String::from("test-package"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        add_change(&change, Some(root.to_string()));
+        add_change(&change, Some(root.to_string()), None);
 
-        let changes = get_change(String::from("main"), Some(root.to_string()));
+        let changes = get_change(String::from("main"), Some(root.to_string()), None);
 
         assert_eq!(changes.len(), 1);
         assert_eq!(changes_path.is_file(), true);
@@ -575,17 +1397,19 @@ mod tests {
             package: String::from("test-package"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        add_change(&change, Some(root.to_string()));
+        add_change(&change, Some(root.to_string()), None);
 
         let result = change_exist(
             String::from("main"),
             vec!["test-package".to_string()],
             Some(root.to_string()),
+            None,
         );
 
         assert_eq!(result, true);
@@ -605,19 +1429,21 @@ mod tests {
             package: String::from("@scope/package-a"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         let change_package_b = Change {
             package: String::from("@scope/package-b"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        add_change(&change_package_a, Some(root.to_string()));
-        add_change(&change_package_b, Some(root.to_string()));
+        add_change(&change_package_a, Some(root.to_string()), None);
+        add_change(&change_package_b, Some(root.to_string()), None);
 
         let result = change_exist(
             String::from("main"),
@@ -626,6 +1452,7 @@ mod tests {
                 "@scope/package-b".to_string(),
             ],
             Some(root.to_string()),
+            None,
         );
 
         assert_eq!(result, true);
@@ -644,17 +1471,19 @@ mod tests {
             package: String::from("test-package"),
             release_as: Bump::Major,
             deploy: vec![String::from("production")],
+            exact_version: None,
         };
 
         init_changes(Some(root.to_string()), &None);
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        add_change(&change, Some(root.to_string()));
+        add_change(&change, Some(root.to_string()), None);
 
         let result = change_exist(
             String::from("main"),
             vec!["test-package".to_string(), "@scope/package-a".to_string()],
             Some(root.to_string()),
+            None,
         );
 
         assert_eq!(result, false);
@@ -678,6 +1507,7 @@ mod tests {
             String::from("main"),
             vec!["test-package".to_string(), "@scope/package-a".to_string()],
             Some(root.to_string()),
+            None,
         );
 
         assert_eq!(result, false);
@@ -686,6 +1516,140 @@ mod tests {
         Ok(())
     }
 
+    fn run_changes_scenario(
+        monorepo_dir: &PathBuf,
+        changes_path: Option<String>,
+    ) -> Result<(Changes, Vec<Change>, bool, Changes), Box<dyn std::error::Error>> {
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let ref root = project_root.unwrap().to_string();
+
+        let change_package_a = Change {
+            package: String::from("@scope/package-a"),
+            release_as: Bump::Major,
+            deploy: vec![String::from("production")],
+            exact_version: None,
+        };
+
+        let change_package_b = Change {
+            package: String::from("@scope/package-b"),
+            release_as: Bump::Minor,
+            deploy: vec![String::from("int")],
+            exact_version: None,
+        };
+
+        init_changes(
+            Some(root.to_string()),
+            &Some(ChangesOptions {
+                message: None,
+                git_user_name: None,
+                git_user_email: None,
+                changes_path: changes_path.to_owned(),
+            }),
+        );
+        add_change(
+            &change_package_a,
+            Some(root.to_string()),
+            changes_path.to_owned(),
+        );
+
+        let branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("-b")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git branch problem");
+
+        branch.wait_with_output()?;
+
+        add_change(
+            &change_package_b,
+            Some(root.to_string()),
+            changes_path.to_owned(),
+        );
+
+        let all_changes = get_changes(Some(root.to_string()), changes_path.to_owned());
+        let branch_changes = get_change(
+            String::from("feat/message"),
+            Some(root.to_string()),
+            changes_path.to_owned(),
+        );
+        let removed = remove_change(
+            String::from("main"),
+            Some(root.to_string()),
+            changes_path.to_owned(),
+        );
+        let remaining_changes = get_changes(Some(root.to_string()), changes_path.to_owned());
+
+        Ok((all_changes, branch_changes, removed, remaining_changes))
+    }
+
+    #[test]
+    fn test_changes_storages_behave_the_same() -> Result<(), Box<dyn std::error::Error>> {
+        let ref file_monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref dir_monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+
+        let file_result = run_changes_scenario(file_monorepo_dir, None)?;
+        let dir_result = run_changes_scenario(dir_monorepo_dir, Some(String::from(".changes")))?;
+
+        assert_eq!(file_result, dir_result);
+        assert_eq!(file_result.0.changes.len(), 2);
+        assert_eq!(file_result.1.len(), 1);
+        assert_eq!(file_result.2, true);
+        assert_eq!(file_result.3.changes.len(), 1);
+
+        let ref changes_dir = dir_monorepo_dir.join(".changes");
+
+        assert_eq!(dir_monorepo_dir.join(".changes.json").exists(), false);
+        assert_eq!(changes_dir.join("feat%2Fmessage.json").is_file(), true);
+        assert_eq!(changes_dir.join("main.json").exists(), false);
+        assert_eq!(
+            ChangesStorage::resolve(&dir_monorepo_dir.display().to_string(), &None),
+            ChangesStorage::PerBranchDirectory(changes_dir.to_path_buf())
+        );
+
+        remove_dir_all(&file_monorepo_dir)?;
+        remove_dir_all(&dir_monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_changes_branch_files_do_not_collide() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref changes_dir = monorepo_dir.join(".changes");
+        let ref storage = ChangesStorage::PerBranchDirectory(changes_dir.to_path_buf());
+
+        let change = Change {
+            package: String::from("@scope/package-a"),
+            release_as: Bump::Patch,
+            deploy: vec![],
+            exact_version: None,
+        };
+
+        let mut changes = ChangesData::new();
+        changes.insert(String::from("feat/a"), vec![change.to_owned()]);
+        changes.insert(String::from("feat_a"), vec![change.to_owned()]);
+
+        storage.write(&ChangesFileData {
+            message: None,
+            git_user_name: None,
+            git_user_email: None,
+            changes,
+        });
+
+        let stored = storage.read().unwrap();
+
+        assert_eq!(changes_dir.join("feat%2Fa.json").is_file(), true);
+        assert_eq!(changes_dir.join("feat_a.json").is_file(), true);
+        assert_eq!(stored.changes.len(), 2);
+        assert_eq!(stored.changes.contains_key("feat/a"), true);
+        assert_eq!(stored.changes.contains_key("feat_a"), true);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
     #[test]
     fn test_changes_file_exist() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
@@ -694,11 +1658,233 @@ mod tests {
         let ref root = project_root.unwrap().to_string();
 
         let ref changes_path = monorepo_dir.join(String::from(".changes.json"));
-        let result = changes_file_exist(Some(root.to_string()));
+        let result = changes_file_exist(Some(root.to_string()), None);
 
         assert_eq!(result, false);
         assert_eq!(changes_path.is_file(), false);
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
+
+    #[test]
+    fn test_get_pending_packages() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let tag = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("tag")
+            .arg("@scope/package-d@1.0.0")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git tag problem");
+
+        tag.wait_with_output()?;
+
+        File::create(monorepo_dir.join("packages/package-a/index.js"))?;
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("feat: message to the world")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        let changes_path = Some(String::from(".config/release-changes.json"));
+
+        init_changes(
+            Some(root.to_string()),
+            &Some(ChangesOptions {
+                message: None,
+                git_user_name: None,
+                git_user_email: None,
+                changes_path: changes_path.to_owned(),
+            }),
+        );
+
+        for package in ["@scope/package-a", "@scope/package-c"] {
+            let change = Change {
+                package: String::from(package),
+                release_as: Bump::Minor,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            };
+
+            add_change(&change, Some(root.to_string()), changes_path.to_owned());
+        }
+
+        let mut pending = get_pending_packages(Some(root.to_string()), changes_path)?
+            .iter()
+            .map(|package| package.name.to_string())
+            .collect::<Vec<String>>();
+
+        pending.sort();
+
+        assert_eq!(
+            pending,
+            vec![
+                String::from("@scope/package-a"),
+                String::from("@scope/package-c")
+            ]
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("-b")
+            .arg("feat/audit")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Git branch problem");
+
+        branch.wait_with_output()?;
+
+        File::create(monorepo_dir.join("packages/package-b/index.js"))?;
+        File::create(monorepo_dir.join("packages/package-c/index.js"))?;
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("feat: message to the world")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        init_changes(Some(root.to_string()), &None);
+
+        let add_package_change = |package: &str| {
+            add_change(
+                &Change {
+                    package: String::from(package),
+                    release_as: Bump::Minor,
+                    deploy: vec![String::from("production")],
+                    exact_version: None,
+                },
+                Some(root.to_string()),
+                None,
+            )
+        };
+
+        add_package_change("@scope/package-b");
+
+        let audit = audit_changes(
+            None,
+            Some(String::from("main")),
+            Some(root.to_string()),
+            None,
+        )?;
+
+        assert_eq!(audit.ok, vec![String::from("@scope/package-b")]);
+        assert_eq!(audit.missing, vec![String::from("@scope/package-c")]);
+        assert_eq!(audit.extraneous.len(), 0);
+
+        add_package_change("@scope/package-d");
+
+        let audit = audit_changes(
+            Some(String::from("feat/audit")),
+            Some(String::from("main")),
+            Some(root.to_string()),
+            None,
+        )?;
+
+        assert_eq!(audit.missing, vec![String::from("@scope/package-c")]);
+        assert_eq!(audit.extraneous, vec![String::from("@scope/package-d")]);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_orphaned_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let changes_path = Some(String::from(".config/release-changes.json"));
+
+        init_changes(
+            Some(root.to_string()),
+            &Some(ChangesOptions {
+                message: None,
+                git_user_name: None,
+                git_user_email: None,
+                changes_path: changes_path.to_owned(),
+            }),
+        );
+
+        for package in ["@scope/package-a", "@scope/removed-package"] {
+            let change = Change {
+                package: String::from(package),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            };
+
+            add_change(&change, Some(root.to_string()), changes_path.to_owned());
+        }
+
+        let orphaned = find_orphaned_changes(Some(root.to_string()), changes_path)?;
+
+        assert_eq!(orphaned.len(), 1);
+        assert_eq!(orphaned[0].package, String::from("@scope/removed-package"));
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_json_format() -> Result<(), Box<dyn std::error::Error>> {
+        let change = Change {
+            package: String::from("@scope/package-a"),
+            release_as: Bump::Minor,
+            deploy: vec![String::from("production")],
+            exact_version: None,
+        };
+
+        let json = r#"{"package":"@scope/package-a","release_as":"Minor","deploy":["production"],"exact_version":null}"#;
+
+        assert_eq!(serde_json::to_string(&change)?, json);
+        assert_eq!(serde_json::from_str::<Change>(json)?, change);
+        Ok(())
+    }
 }
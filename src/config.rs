@@ -0,0 +1,161 @@
+#![allow(clippy::all)]
+
+//! # Config
+//!
+//! This module reads the optional release config, a `release.config.json` file in the root of
+//! the project with the defaults of the bump options, so they don't have to be given on every
+//! call. Options given explicitly always win over the config.
+//!
+//! # Example
+//! ```json
+//! {
+//!   "default_branch": "origin/develop",
+//!   "sync_deps": true,
+//!   "history_mode": "MergesOnly",
+//!   "changelog_file": "CHANGES.md",
+//!   "tag_format": "{name}/v{version}",
+//!   "ignored_packages": ["@scope/internal"],
+//!   "bump_rules": [{ "commit_type": "perf", "scope": null, "bump": "Minor", "breaking": null }],
+//!   "network_timeout_ms": 120000
+//! }
+//!```
+use serde::{Deserialize, Serialize};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use crate::errors::WorkspaceError;
+
+use super::bumps::BumpRule;
+use super::git::HistoryMode;
+use super::paths::get_project_root_path;
+
+/// File name of the release config, in the project root.
+pub const RELEASE_CONFIG_FILE: &str = "release.config.json";
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct ReleaseConfig {
+    pub default_branch: Option<String>,
+    pub sync_deps: Option<bool>,
+    pub history_mode: Option<HistoryMode>,
+    pub changelog_file: Option<String>,
+    pub tag_format: Option<String>,
+    pub ignored_packages: Option<Vec<String>>,
+    pub bump_rules: Option<Vec<BumpRule>>,
+    pub network_timeout_ms: Option<u32>,
+    pub command_timeout_ms: Option<u32>,
+    pub max_output_bytes: Option<u32>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+/// A struct that represents the release config. `default_branch` is the branch the changes are
+/// compared to (the `since` bump option), `history_mode` the commits range strategy,
+/// `tag_format` the format of the publish tags and `ignored_packages` the packages never
+/// released (the `exclude_packages` bump option). `bump_rules` map the commits of a package
+/// without change to a bump (see `BumpRule`). `network_timeout_ms`, `command_timeout_ms`
+/// and `max_output_bytes` override the limits of the spawned commands (see `CommandLimits`).
+pub struct ReleaseConfig {
+    pub default_branch: Option<String>,
+    pub sync_deps: Option<bool>,
+    pub history_mode: Option<HistoryMode>,
+    pub changelog_file: Option<String>,
+    pub tag_format: Option<String>,
+    pub ignored_packages: Option<Vec<String>>,
+    pub bump_rules: Option<Vec<BumpRule>>,
+    pub network_timeout_ms: Option<u32>,
+    pub command_timeout_ms: Option<u32>,
+    pub max_output_bytes: Option<u32>,
+}
+
+/// Reads the release config of the project root. Fails with `FileNotFound` when the project
+/// has none, and with `InvalidConfig` when it can't be parsed.
+pub fn read_release_config(cwd: Option<String>) -> Result<ReleaseConfig, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref path = Path::new(root).join(RELEASE_CONFIG_FILE);
+
+    if !path.is_file() {
+        return Err(WorkspaceError::FileNotFound(path.display().to_string()));
+    }
+
+    let content = read_to_string(path)?;
+
+    serde_json::from_str::<ReleaseConfig>(&content).map_err(|error| {
+        WorkspaceError::InvalidConfig(format!("{}: {}", RELEASE_CONFIG_FILE, error))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bumps::Bump;
+    use crate::manager::PackageManager;
+    use crate::utils::create_test_monorepo;
+    use std::fs::{remove_dir_all, write};
+
+    #[test]
+    fn test_read_release_config() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref root = monorepo_dir.display().to_string();
+
+        assert_eq!(
+            matches!(
+                read_release_config(Some(root.to_string())),
+                Err(WorkspaceError::FileNotFound(_))
+            ),
+            true
+        );
+
+        write(
+            monorepo_dir.join(RELEASE_CONFIG_FILE),
+            r#"{
+                "sync_deps": true,
+                "history_mode": "MergesOnly",
+                "ignored_packages": ["@scope/package-d"],
+                "bump_rules": [{ "commit_type": "perf", "bump": "Minor" }]
+            }"#,
+        )?;
+
+        let config = read_release_config(Some(root.to_string()))?;
+
+        assert_eq!(
+            config,
+            ReleaseConfig {
+                default_branch: None,
+                sync_deps: Some(true),
+                history_mode: Some(HistoryMode::MergesOnly),
+                changelog_file: None,
+                tag_format: None,
+                ignored_packages: Some(vec![String::from("@scope/package-d")]),
+                bump_rules: Some(vec![BumpRule {
+                    commit_type: String::from("perf"),
+                    scope: None,
+                    bump: Bump::Minor,
+                    breaking: None,
+                }]),
+                ..Default::default()
+            }
+        );
+
+        write(
+            monorepo_dir.join(RELEASE_CONFIG_FILE),
+            r#"{ "sync_deps": "yes" }"#,
+        )?;
+
+        assert_eq!(
+            matches!(
+                read_release_config(Some(root.to_string())),
+                Err(WorkspaceError::InvalidConfig(_))
+            ),
+            true
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+}
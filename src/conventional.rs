@@ -13,15 +13,31 @@ use git_cliff_core::{
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::path::PathBuf;
 
+use semver::Version as SemVersion;
+
+use super::errors::WorkspaceError;
 use super::git::{
-    get_commits_since, get_last_known_publish_tag_info_for_package, git_fetch_all, Commit,
+    format_publish_tag, get_commits_between, get_commits_since,
+    get_last_known_publish_tag_info_for_package_with_policy, get_last_known_publish_tag_info_in,
+    get_previous_publish_tag_version_in, get_publish_tags, get_remote_or_local_tags,
+    git_fetch_all_with_timeout, git_remote_url, normalize_publish_tags, split_publish_tag,
+    CommandLimits, Commit, CommitsSinceOptions, GitError, HistoryMode, PrereleasePolicy,
 };
 use super::packages::PackageInfo;
 use super::packages::PackageRepositoryInfo;
+use super::packages::{get_package_info, get_package_repository_info};
 use super::paths::get_project_root_path;
+use super::utils::package_scope_name_version;
+
+/// File name of the package changelogs, in the package root.
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Line of a release changelog without conventional commits.
+pub const EMPTY_CHANGELOG_PLACEHOLDER: &str = "No notable changes";
 
 #[cfg(feature = "napi")]
 #[napi(object)]
@@ -45,18 +61,172 @@ pub struct ConventionalPackage {
 
 #[cfg(feature = "napi")]
 #[napi(object)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConventionalPackageOptions {
     pub version: Option<String>,
     pub title: Option<String>,
+    pub group_by_scope: Option<bool>,
+    pub skip_commits: Option<Vec<String>>,
+    pub link_issues: Option<bool>,
+    pub protect_breaking_commits: Option<bool>,
+    pub footer: Option<String>,
+    pub heading_level: Option<u8>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub empty_placeholder: Option<String>,
+    pub history_mode: Option<HistoryMode>,
+    pub changelog_file: Option<String>,
+    pub tag_format: Option<String>,
 }
 
 #[cfg(not(feature = "napi"))]
-#[derive(Debug, Clone)]
-/// A struct that represents options for a conventional package
+#[derive(Debug, Clone, Default)]
+/// A struct that represents options for a conventional package.
+/// `skip_commits` lists full or short commit shas to leave out of the changelog.
+/// `link_issues` (default true) expands `(#N)` references into issue links.
+/// `protect_breaking_commits` keeps breaking commits even when a parser would skip them.
+/// `title` and `footer` replace the changelog header and the default releases count footer.
+/// `heading_level` (default 2, from 1 to 4) sets the depth of the version headings, the group
+/// and scope headings below them follow it.
+/// `prerelease_policy` picks the previous release tag, see `PrereleasePolicy`.
+/// `empty_placeholder` (default `EMPTY_CHANGELOG_PLACEHOLDER`) is the line of a release without
+/// conventional commits, below its version heading.
+/// `history_mode` (default `AllCommits`) picks the commits of the changelog, see `HistoryMode`.
+/// `changelog_file` (default `CHANGELOG_FILE`) is the changelog file name in the package root.
+/// `tag_format` (default `DEFAULT_TAG_FORMAT`) is the format of the publish tags, see
+/// `format_publish_tag`.
 pub struct ConventionalPackageOptions {
     pub version: Option<String>,
     pub title: Option<String>,
+    pub group_by_scope: Option<bool>,
+    pub skip_commits: Option<Vec<String>>,
+    pub link_issues: Option<bool>,
+    pub protect_breaking_commits: Option<bool>,
+    pub footer: Option<String>,
+    pub heading_level: Option<u8>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub empty_placeholder: Option<String>,
+    pub history_mode: Option<HistoryMode>,
+    pub changelog_file: Option<String>,
+    pub tag_format: Option<String>,
+}
+
+#[derive(Debug)]
+/// An error that represents a changelog config that could not be parsed
+pub enum ConfigError {
+    Parse(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Parse(message) => {
+                write!(f, "Unable to parse changelog config: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Parses a git-cliff config from a TOML string.
+/// Tables namespaced under `cliff` (e.g. `[cliff.changelog]`, `[[cliff.git.commit_parsers]]`) are also accepted.
+pub fn parse_changelog_config(toml: &str) -> Result<Config, ConfigError> {
+    let namespace_regex = Regex::new(r"(?m)^(\s*\[+)\s*cliff\.").unwrap();
+    let contents = namespace_regex.replace_all(toml, "$1");
+
+    Config::parse_from_str(&contents).map_err(|error| ConfigError::Parse(error.to_string()))
+}
+
+/// Changelog body template, commits grouped by type listing scoped commits first
+const BODY_TEMPLATE: &str = r#"
+                        {%- macro remote_url() -%}
+                          <REPO>
+                        {%- endmacro -%}
+
+                        {% macro print_commit(commit) -%}
+                            - {% if commit.scope %}*({{ commit.scope }})* {% endif %}{% if commit.breaking %}[**breaking**] {% endif %}{{ commit.message | upper_first }} - ([{{ commit.id | truncate(length=7, end="") }}]({{ self::remote_url() }}/commit/{{ commit.id }}))
+                        {% endmacro -%}
+
+                        {% if version %}
+                            {% if previous.version %}
+                                ## [{{ version | trim_start_matches(pat="v") }}]
+                                  ({{ self::remote_url() }}/compare/{{ previous.extra.tag }}..{{ extra.tag }}) - {{ now() | date(format="%Y-%m-%d") }}
+                            {% else %}
+                                ## [{{ version | trim_start_matches(pat="v") }}] - {{ now() | date(format="%Y-%m-%d") }}
+                            {% endif %}
+                        {% else %}
+                            ## [unreleased]
+                        {% endif %}
+
+                        {% for group, commits in commits | group_by(attribute="group") %}
+                            ### {{ group | striptags | trim | upper_first }}
+                            {% for commit in commits
+                            | filter(attribute="scope")
+                            | sort(attribute="scope") %}
+                                {{ self::print_commit(commit=commit) }}
+                            {%- endfor -%}
+                            {% raw %}
+                            {% endraw %}
+                            {%- for commit in commits %}
+                                {%- if not commit.scope -%}
+                                    {{ self::print_commit(commit=commit) }}
+                                {% endif -%}
+                            {% endfor -%}
+                        {% endfor %}"#;
+
+/// Changelog body template, commits grouped by type and then by scope subheadings
+const SCOPED_BODY_TEMPLATE: &str = r#"
+                        {%- macro remote_url() -%}
+                          <REPO>
+                        {%- endmacro -%}
+
+                        {% macro print_commit(commit) -%}
+                            - {% if commit.breaking %}[**breaking**] {% endif %}{{ commit.message | upper_first }} - ([{{ commit.id | truncate(length=7, end="") }}]({{ self::remote_url() }}/commit/{{ commit.id }}))
+                        {% endmacro -%}
+
+                        {% if version %}
+                            {% if previous.version %}
+                                ## [{{ version | trim_start_matches(pat="v") }}]
+                                  ({{ self::remote_url() }}/compare/{{ previous.extra.tag }}..{{ extra.tag }}) - {{ now() | date(format="%Y-%m-%d") }}
+                            {% else %}
+                                ## [{{ version | trim_start_matches(pat="v") }}] - {{ now() | date(format="%Y-%m-%d") }}
+                            {% endif %}
+                        {% else %}
+                            ## [unreleased]
+                        {% endif %}
+
+                        {% for group, commits in commits | group_by(attribute="group") %}
+                            ### {{ group | striptags | trim | upper_first }}
+                            {% for scope, scoped_commits in commits
+                            | filter(attribute="scope")
+                            | group_by(attribute="scope") %}
+                                #### {{ scope }}
+                                {% raw %}
+                                {% endraw %}
+                                {%- for commit in scoped_commits %}
+                                    {{- self::print_commit(commit=commit) }}
+                                {%- endfor %}
+                            {% endfor -%}
+                            {% raw %}
+                            {% endraw %}
+                            {%- for commit in commits %}
+                                {%- if not commit.scope -%}
+                                    {{ self::print_commit(commit=commit) }}
+                                {% endif -%}
+                            {% endfor -%}
+                        {% endfor %}"#;
+
+/// Shifts the headings of a body template so the version headings get `heading_level` depth
+fn set_template_heading_level(template: &str, heading_level: u8) -> String {
+    let heading_level = heading_level.clamp(1, 4) as usize;
+    let heading_regex = Regex::new(r"(?m)^(\s*)(#{2,4}) ").unwrap();
+
+    heading_regex
+        .replace_all(template, |captures: &regex::Captures| {
+            let depth = captures[2].len() - 2 + heading_level;
+            format!("{}{} ", &captures[1], "#".repeat(depth))
+        })
+        .to_string()
 }
 
 /// Process commits for groupint type, extracting data
@@ -79,7 +249,7 @@ fn process_commits<'a>(commits: &Vec<Commit>, config: &GitConfig) -> Vec<GitComm
 
             git_commit.into_conventional().is_ok()
         })
-        .map(|commit| {
+        .filter_map(|commit| {
             let timestamp = chrono::DateTime::parse_from_rfc2822(&commit.author_date).unwrap();
 
             let git_commit = GitCommit {
@@ -93,7 +263,8 @@ fn process_commits<'a>(commits: &Vec<Commit>, config: &GitConfig) -> Vec<GitComm
                 ..GitCommit::default()
             };
 
-            git_commit.process(config).unwrap()
+            // Commits matched by a skip parser are dropped
+            git_commit.process(config).ok()
         })
         .collect::<Vec<GitCommit>>()
 }
@@ -104,6 +275,11 @@ fn define_config(
     repo: String,
     domain: String,
     title: Option<String>,
+    footer: Option<String>,
+    group_by_scope: bool,
+    heading_level: u8,
+    link_issues: bool,
+    protect_breaking_commits: bool,
     options: &Option<Config>,
 ) -> Config {
     let github_url = format!("{}/{}/{}", domain, owner, repo);
@@ -124,56 +300,35 @@ fn define_config(
                 },
                 changelog: ChangelogConfig {
                     header: title,
-                    body: Some(String::from(
-                        r#"
-                        {%- macro remote_url() -%}
-                          <REPO>
-                        {%- endmacro -%}
-
-                        {% macro print_commit(commit) -%}
-                            - {% if commit.scope %}*({{ commit.scope }})* {% endif %}{% if commit.breaking %}[**breaking**] {% endif %}{{ commit.message | upper_first }} - ([{{ commit.id | truncate(length=7, end="") }}]({{ self::remote_url() }}/commit/{{ commit.id }}))
-                        {% endmacro -%}
-
-                        {% if version %}
-                            {% if previous.version %}
-                                ## [{{ version | trim_start_matches(pat="v") }}]
-                                  ({{ self::remote_url() }}/compare/{{ previous.version }}..{{ version }}) - {{ now() | date(format="%Y-%m-%d") }}
-                            {% else %}
-                                ## [{{ version | trim_start_matches(pat="v") }}] - {{ now() | date(format="%Y-%m-%d") }}
-                            {% endif %}
-                        {% else %}
-                            ## [unreleased]
-                        {% endif %}
-
-                        {% for group, commits in commits | group_by(attribute="group") %}
-                            ### {{ group | striptags | trim | upper_first }}
-                            {% for commit in commits
-                            | filter(attribute="scope")
-                            | sort(attribute="scope") %}
-                                {{ self::print_commit(commit=commit) }}
-                            {%- endfor -%}
-                            {% raw %}
-                            {% endraw %}
-                            {%- for commit in commits %}
-                                {%- if not commit.scope -%}
-                                    {{ self::print_commit(commit=commit) }}
-                                {% endif -%}
-                            {% endfor -%}
-                        {% endfor %}"#,
+                    body: Some(set_template_heading_level(
+                        match group_by_scope {
+                            true => SCOPED_BODY_TEMPLATE,
+                            false => BODY_TEMPLATE,
+                        },
+                        heading_level,
                     )),
-                    footer: Some(String::from(
+                    footer: Some(footer.unwrap_or(String::from(
                         r#"-- Total Releases: {{ releases | length }} --"#,
-                    )),
+                    ))),
                     trim: Some(true),
                     postprocessors: Some(vec![TextProcessor {
                         pattern: Regex::new("<REPO>").expect("failed to compile regex"),
-                        replace: Some(String::from(github_url)),
+                        replace: Some(github_url.to_string()),
                         replace_command: None,
                     }]),
                     render_always: Some(false),
                     ..ChangelogConfig::default()
                 },
                 git: GitConfig {
+                    commit_preprocessors: Some(match link_issues {
+                        true => vec![TextProcessor {
+                            pattern: Regex::new(r"\((\w+\s)?#([0-9]+)\)")
+                                .expect("failed to compile regex"),
+                            replace: Some(format!("([#${{2}}]({}/issues/${{2}}))", github_url)),
+                            replace_command: None,
+                        }],
+                        false => vec![],
+                    }),
                     commit_parsers: Some(vec![
                         CommitParser {
                             message: Regex::new("^feat").ok(),
@@ -231,7 +386,7 @@ fn define_config(
                             ..CommitParser::default()
                         },
                     ]),
-                    protect_breaking_commits: Some(false),
+                    protect_breaking_commits: Some(protect_breaking_commits),
                     filter_commits: Some(false),
                     filter_unconventional: Some(true),
                     conventional_commits: Some(true),
@@ -251,18 +406,38 @@ fn define_config(
     cliff_config
 }
 
-/// Generate changelog output
-fn generate_changelog(
-    commits: &Vec<GitCommit>,
-    config: &Config,
+/// Release of a package with its previous release. Both carry their publish tag, formatted
+/// with the tag format, as `extra.tag` (the compare link of the body templates).
+fn package_release<'a>(
+    name: &str,
+    tag_format: &Option<String>,
     version: Option<String>,
-) -> String {
-    let releases = Release {
+    commits: Vec<GitCommit<'a>>,
+    previous_version: Option<String>,
+) -> Release<'a> {
+    let extra = |version: &Option<String>| {
+        version
+            .as_ref()
+            .map(|version| json!({ "tag": format_publish_tag(tag_format, name, version) }))
+    };
+
+    Release {
+        extra: extra(&version),
+        previous: previous_version.map(|previous_version| {
+            Box::new(Release {
+                extra: extra(&Some(previous_version.to_string())),
+                version: Some(previous_version),
+                ..Release::default()
+            })
+        }),
         version,
-        commits: commits.to_vec().to_owned(),
+        commits,
         ..Release::default()
-    };
+    }
+}
 
+/// Generate changelog output
+fn generate_changelog(releases: Release, config: &Config) -> String {
     let changelog = Changelog::new(vec![releases], config);
     let mut changelog_output = Vec::new();
 
@@ -273,57 +448,131 @@ fn generate_changelog(
 
 /// Prepend changelog output
 fn prepend_generate_changelog(
-    commits: &Vec<GitCommit>,
+    releases: Release,
     config: &Config,
     changelog_content: &String,
-    version: Option<String>,
 ) -> String {
-    let releases = Release {
-        version,
-        commits: commits.to_vec().to_owned(),
-        ..Release::default()
-    };
-
-    let changelog = Changelog::new(vec![releases], config);
     let mut changelog_output = Vec::new();
 
-    changelog
-        .unwrap()
-        .prepend(changelog_content.to_string(), &mut changelog_output)
-        .unwrap();
+    // Keep the header of the existing changelog instead of emitting the configured one again
+    match split_changelog_header(changelog_content) {
+        Some((header, content)) => {
+            let mut config = config.to_owned();
+            config.changelog.header = None;
 
-    String::from_utf8(changelog_output).unwrap_or_default()
+            Changelog::new(vec![releases], &config)
+                .unwrap()
+                .generate(&mut changelog_output)
+                .unwrap();
+
+            let release = String::from_utf8(changelog_output).unwrap_or_default();
+
+            format!("{}\n\n{}\n\n{}", header, release.trim(), content)
+        }
+        None => {
+            Changelog::new(vec![releases], config)
+                .unwrap()
+                .prepend(changelog_content.to_string(), &mut changelog_output)
+                .unwrap();
+
+            String::from_utf8(changelog_output).unwrap_or_default()
+        }
+    }
 }
 
-/// Give info about commits in a package, generate changelog output
-pub fn get_conventional_for_package(
+/// Changelog output of a release without conventional commits: a minimal version heading and
+/// the placeholder line, prepended to the existing changelog (below its header) if any.
+fn generate_empty_changelog(
+    version: &String,
+    heading_level: u8,
+    placeholder: &String,
+    title: &Option<String>,
+    changelog_content: &String,
+) -> String {
+    let release = format!(
+        "{} [{}] - {}\n\n{}\n",
+        "#".repeat(heading_level.clamp(1, 4) as usize),
+        version.trim_start_matches('v'),
+        chrono::Utc::now().format("%Y-%m-%d"),
+        placeholder.trim()
+    );
+    let title = title.as_ref().filter(|title| !title.trim().is_empty());
+
+    match (
+        split_changelog_header(changelog_content),
+        changelog_content.trim().is_empty(),
+    ) {
+        (Some((header, content)), _) => format!("{}\n\n{}\n{}", header, release, content),
+        (None, true) => match title {
+            Some(title) => format!("{}\n\n{}", title.trim(), release),
+            None => release,
+        },
+        (None, false) => format!("{}\n{}", release, changelog_content.trim_start()),
+    }
+}
+
+/// Splits an existing changelog into its header (a leading `# ` title line) and the rest.
+/// A leading `# [version]` line is a release heading (heading level 1), not a header.
+/// Returns None when the changelog has no header.
+fn split_changelog_header(changelog_content: &String) -> Option<(&str, &str)> {
+    let content = changelog_content.trim_start();
+    let (first_line, rest) = content.split_once('\n').unwrap_or((content, ""));
+
+    match first_line.starts_with("# ") && !first_line.starts_with("# [") {
+        true => Some((first_line.trim_end(), rest.trim_start())),
+        false => None,
+    }
+}
+
+/// Commits and config of a package, shared by the changelog output and the release context
+struct PreparedConventional {
+    options: ConventionalPackageOptions,
+    config: Config,
+    commits: Vec<Commit>,
+    previous_version: Option<String>,
+    previous_hash: Option<String>,
+}
+
+/// Resolves the previous publish tag, the repository info, the options defaults and
+/// the commits of a package since its last release. Fails when the tags can't be listed.
+fn prepare_conventional(
     package_info: &PackageInfo,
     no_fetch_all: Option<bool>,
-    cwd: Option<String>,
+    current_working_dir: &String,
     conventional_options: &Option<ConventionalPackageOptions>,
-) -> ConventionalPackage {
-    let current_working_dir = match cwd {
-        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
-        None => get_project_root_path(None).unwrap(),
-    };
-
-    let changelog_dir =
-        PathBuf::from(package_info.package_path.to_string()).join(String::from("CHANGELOG.md"));
-
+) -> Result<PreparedConventional, GitError> {
     if no_fetch_all.is_some() {
-        git_fetch_all(Some(current_working_dir.to_string()), no_fetch_all).expect("Fetch all");
+        let timeout = CommandLimits::network(&Some(current_working_dir.to_string())).timeout;
+        git_fetch_all_with_timeout(Some(current_working_dir.to_string()), no_fetch_all, timeout)?;
     }
 
-    let tag_info = get_last_known_publish_tag_info_for_package(
-        package_info,
-        Some(current_working_dir.to_string()),
+    let tag_format = conventional_options
+        .as_ref()
+        .and_then(|options| options.tag_format.to_owned());
+    let prerelease_policy = conventional_options
+        .as_ref()
+        .and_then(|options| options.prerelease_policy.to_owned());
+    let ref tags = normalize_publish_tags(
+        get_publish_tags(Some(current_working_dir.to_string()))?,
+        &tag_format,
     );
 
+    // The same tag is the since hash of the commits and the previous release (compare link)
+    let tag_info = get_last_known_publish_tag_info_in(tags, package_info, prerelease_policy);
+
     let hash = match tag_info {
-        Some(tag) => Some(tag.hash),
+        Some(ref tag) => Some(tag.hash.to_string()),
         None => None,
     };
 
+    let previous_version = tag_info.and_then(|tag| {
+        let tag = tag.tag.replace("refs/tags/", "");
+
+        package_scope_name_version(tag.trim_end_matches("^{}"))
+            .map(|metadata| metadata.version)
+            .filter(|version| !version.is_empty())
+    });
+
     let conventional_default_options = match conventional_options {
         Some(options) => {
             let opt_version = options.version.as_ref();
@@ -337,38 +586,89 @@ pub fn get_conventional_for_package(
             ConventionalPackageOptions {
                 version: Some(version.to_string()),
                 title: Some(title.to_string()),
+                group_by_scope: options.group_by_scope.to_owned(),
+                skip_commits: options.skip_commits.to_owned(),
+                link_issues: options.link_issues.to_owned(),
+                protect_breaking_commits: options.protect_breaking_commits.to_owned(),
+                footer: options.footer.to_owned(),
+                heading_level: options.heading_level.to_owned(),
+                prerelease_policy: options.prerelease_policy.to_owned(),
+                empty_placeholder: options.empty_placeholder.to_owned(),
+                history_mode: options.history_mode.to_owned(),
+                changelog_file: options.changelog_file.to_owned(),
+                tag_format: options.tag_format.to_owned(),
             }
         }
         None => ConventionalPackageOptions {
             version: Some(String::from("0.0.0")),
-            title: None,
+            ..Default::default()
         },
     };
 
+    // A release already tagged (regenerated changelog) compares against the tag below it
+    let release_version = conventional_default_options
+        .version
+        .to_owned()
+        .unwrap_or_default();
+    let previous_version = match (
+        previous_version
+            .as_ref()
+            .and_then(|version| SemVersion::parse(version).ok()),
+        SemVersion::parse(&release_version).ok(),
+    ) {
+        (Some(previous), Some(release)) if previous >= release => {
+            get_previous_publish_tag_version_in(
+                tags,
+                &package_info.name,
+                &release_version,
+                prerelease_policy,
+            )
+        }
+        _ => previous_version,
+    };
+
     let repo_info = &package_info.repository_info;
+    let default_repository_info = PackageRepositoryInfo {
+        orga: String::from("my-orga"),
+        project: String::from("my-repo"),
+        domain: String::from("https://github.com"),
+    };
+    let is_default_repository = |info: &PackageRepositoryInfo| {
+        info.orga == default_repository_info.orga && info.project == default_repository_info.project
+    };
+
+    // Without a repository field in package.json, fallback to the origin remote url
     let repository_info = match repo_info {
-        Some(info) => info.to_owned(),
-        None => PackageRepositoryInfo {
-            orga: String::from("my-orga"),
-            project: String::from("my-repo"),
-            domain: String::from("https://github.com"),
-        },
+        Some(info) if !is_default_repository(info) => info.to_owned(),
+        _ => git_remote_url(None, Some(current_working_dir.to_string()))
+            .and_then(|url| get_package_repository_info(&url))
+            .unwrap_or(default_repository_info.to_owned()),
     };
 
     let package_relative_path = &package_info.package_relative_path;
     let commits_since = get_commits_since(
         Some(current_working_dir.to_string()),
-        hash,
+        hash.to_owned(),
         Some(package_relative_path.to_string()),
+        &CommitsSinceOptions {
+            history_mode: conventional_default_options.history_mode.to_owned(),
+            ..Default::default()
+        },
     );
 
-    let pkg_info = package_info;
-    let mut conventional_package = ConventionalPackage {
-        package_info: pkg_info.to_owned(),
-        conventional_config: json!({}),
-        conventional_commits: json!([]),
-        changelog_output: String::new(),
-    };
+    // Skipped commits can be given as full or short shas
+    let skip_commits = conventional_default_options
+        .skip_commits
+        .to_owned()
+        .unwrap_or_default()
+        .iter()
+        .map(|sha| sha.trim().to_lowercase())
+        .filter(|sha| !sha.is_empty())
+        .collect::<Vec<String>>();
+    let commits_since = commits_since
+        .into_iter()
+        .filter(|commit| !skip_commits.iter().any(|sha| commit.hash.starts_with(sha)))
+        .collect::<Vec<Commit>>();
 
     let orga = &repository_info.orga;
     let project = &repository_info.project;
@@ -378,26 +678,106 @@ pub fn get_conventional_for_package(
         orga.to_string(),
         project.to_string(),
         domain.to_string(),
-        conventional_default_options.title,
+        conventional_default_options.title.to_owned(),
+        conventional_default_options.footer.to_owned(),
+        conventional_default_options.group_by_scope.unwrap_or(false),
+        conventional_default_options.heading_level.unwrap_or(2),
+        conventional_default_options.link_issues.unwrap_or(true),
+        conventional_default_options
+            .protect_breaking_commits
+            .unwrap_or(false),
         &None,
     );
 
+    Ok(PreparedConventional {
+        options: conventional_default_options,
+        config: conventional_config,
+        commits: commits_since,
+        previous_version,
+        previous_hash: hash,
+    })
+}
+
+/// Give info about commits in a package, generate changelog output. Fails when the publish
+/// tags can't be listed (e.g. `GitError::Timeout`).
+pub fn get_conventional_for_package(
+    package_info: &PackageInfo,
+    no_fetch_all: Option<bool>,
+    cwd: Option<String>,
+    conventional_options: &Option<ConventionalPackageOptions>,
+) -> Result<ConventionalPackage, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let changelog_file = conventional_options
+        .as_ref()
+        .and_then(|options| options.changelog_file.to_owned())
+        .unwrap_or(String::from(CHANGELOG_FILE));
+    let changelog_dir = PathBuf::from(package_info.package_path.to_string()).join(changelog_file);
+
+    let PreparedConventional {
+        options: conventional_default_options,
+        config: conventional_config,
+        commits: commits_since,
+        previous_version,
+        ..
+    } = prepare_conventional(
+        package_info,
+        no_fetch_all,
+        &current_working_dir,
+        conventional_options,
+    )?;
+
+    let pkg_info = package_info;
+    let mut conventional_package = ConventionalPackage {
+        package_info: pkg_info.to_owned(),
+        conventional_config: json!({}),
+        conventional_commits: json!([]),
+        changelog_output: String::new(),
+    };
+
     let conventional_commits = process_commits(&commits_since, &conventional_config.git);
 
-    let changelog = match changelog_dir.exists() {
-        true => {
+    // Without conventional commits git-cliff has nothing to render, the release only gets
+    // its version heading and the placeholder line
+    let changelog = match (conventional_commits.is_empty(), changelog_dir.exists()) {
+        (true, exists) => generate_empty_changelog(
+            &conventional_default_options.version.unwrap_or_default(),
+            conventional_default_options.heading_level.unwrap_or(2),
+            &conventional_default_options
+                .empty_placeholder
+                .unwrap_or(String::from(EMPTY_CHANGELOG_PLACEHOLDER)),
+            &conventional_default_options.title,
+            &match exists {
+                true => read_to_string(&changelog_dir).unwrap_or_default(),
+                false => String::new(),
+            },
+        ),
+        (false, true) => {
             let changelog_content = read_to_string(&changelog_dir).unwrap();
             prepend_generate_changelog(
-                &conventional_commits,
+                package_release(
+                    &package_info.name,
+                    &conventional_default_options.tag_format,
+                    conventional_default_options.version,
+                    conventional_commits.to_owned(),
+                    previous_version,
+                ),
                 &conventional_config,
                 &changelog_content,
-                conventional_default_options.version,
             )
         }
-        false => generate_changelog(
-            &conventional_commits,
+        (false, false) => generate_changelog(
+            package_release(
+                &package_info.name,
+                &conventional_default_options.tag_format,
+                conventional_default_options.version,
+                conventional_commits.to_owned(),
+                previous_version,
+            ),
             &conventional_config,
-            conventional_default_options.version,
         ),
     };
 
@@ -408,44 +788,357 @@ pub fn get_conventional_for_package(
     conventional_package.conventional_config =
         serde_json::to_value(&conventional_config.git).unwrap();
 
-    conventional_package
+    Ok(conventional_package)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Returns the git-cliff context of the next release of a package as json (the equivalent of
+/// `git-cliff --context`): a list with the release, its commits with the groups assigned by the
+/// commit parsers and the previous release resolved from the last publish tag. Nothing is written.
+pub fn get_release_context(
+    package_info: &PackageInfo,
+    cwd: Option<String>,
+    conventional_options: &Option<ConventionalPackageOptions>,
+) -> Result<Value, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
 
-    use crate::manager::PackageManager;
-    use crate::packages::get_packages;
-    use crate::paths::get_project_root_path;
-    use crate::utils::create_test_monorepo;
-    use std::fs::remove_dir_all;
-    use std::fs::File;
-    use std::io::Write;
-    use std::process::Command;
-    use std::process::Stdio;
+    let prepared = prepare_conventional(
+        package_info,
+        None,
+        &current_working_dir,
+        conventional_options,
+    )?;
+
+    let commits = process_commits(&prepared.commits, &prepared.config.git);
+
+    let mut release = package_release(
+        &package_info.name,
+        &prepared.options.tag_format,
+        prepared.options.version,
+        commits,
+        prepared.previous_version,
+    );
 
-    fn create_package_change(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        let js_path = monorepo_dir.join("packages/package-b/index.js");
+    if let Some(ref mut previous) = release.previous {
+        previous.commit_id = prepared.previous_hash;
+    }
 
-        let branch = Command::new("git")
-            .current_dir(&monorepo_dir)
-            .arg("checkout")
-            .arg("-b")
-            .arg("feat/message")
-            .stdout(Stdio::piped())
-            .spawn()
-            .expect("Git branch problem");
+    Ok(serde_json::to_value(vec![release]).unwrap())
+}
 
-        branch.wait_with_output()?;
+/// Give info about commits of several packages at once. `package_options` overrides the shared
+/// `conventional_options` per package name, so each package can have its own header or footer.
+pub fn get_conventional_for_packages(
+    packages: &Vec<PackageInfo>,
+    no_fetch_all: Option<bool>,
+    cwd: Option<String>,
+    conventional_options: &Option<ConventionalPackageOptions>,
+    package_options: &HashMap<String, ConventionalPackageOptions>,
+) -> Result<Vec<ConventionalPackage>, GitError> {
+    packages
+        .iter()
+        .map(|package_info| {
+            let options = match package_options.get(&package_info.name) {
+                Some(options) => Some(options.to_owned()),
+                None => conventional_options.to_owned(),
+            };
 
-        let mut js_file = File::create(&js_path)?;
-        js_file
-            .write_all(r#"export const message = "hello";"#.as_bytes())
-            .unwrap();
+            get_conventional_for_package(package_info, no_fetch_all, cwd.to_owned(), &options)
+        })
+        .collect::<Result<Vec<ConventionalPackage>, GitError>>()
+}
 
-        let add = Command::new("git")
-            .current_dir(&monorepo_dir)
+/// Returns the breaking commits of a package since its last publish tag, of the tag format and
+/// allowed by the prerelease policy. With `protect_breaking_commits` breaking commits matching
+/// a skip parser (e.g. `refactor(clippy)!:`) are kept, otherwise they are dropped like any
+/// other skipped commit.
+pub fn get_breaking_commits_for_package(
+    package_info: &PackageInfo,
+    cwd: Option<String>,
+    protect_breaking_commits: bool,
+    prerelease_policy: Option<PrereleasePolicy>,
+    tag_format: &Option<String>,
+) -> Result<Vec<Commit>, GitError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let hash = get_last_known_publish_tag_info_for_package_with_policy(
+        package_info,
+        Some(root.to_string()),
+        prerelease_policy,
+        tag_format,
+    )?
+    .map(|tag| tag.hash.to_string());
+
+    let commits_since = get_commits_since(
+        Some(root.to_string()),
+        hash,
+        Some(package_info.package_relative_path.to_string()),
+        &CommitsSinceOptions::default(),
+    );
+
+    let config = define_config(
+        String::from("my-orga"),
+        String::from("my-repo"),
+        String::from("https://github.com"),
+        None,
+        None,
+        false,
+        2,
+        false,
+        protect_breaking_commits,
+        &None,
+    );
+
+    let breaking_hashes = process_commits(&commits_since, &config.git)
+        .iter()
+        .filter(|commit| {
+            commit
+                .conv
+                .as_ref()
+                .map(|conv| conv.breaking())
+                .unwrap_or(false)
+        })
+        .map(|commit| commit.id.to_string())
+        .collect::<Vec<String>>();
+
+    let breaking_commits = commits_since
+        .into_iter()
+        .filter(|commit| breaking_hashes.contains(&commit.hash))
+        .collect::<Vec<Commit>>();
+
+    Ok(breaking_commits)
+}
+
+/// Reads the current changelog of a package (`changelog_file`, default `CHANGELOG_FILE`), `None`
+/// when it doesn't have one yet.
+pub fn read_changelog(package: &PackageInfo, changelog_file: &Option<String>) -> Option<String> {
+    let changelog_path = PathBuf::from(package.package_path.to_string())
+        .join(changelog_file.as_deref().unwrap_or(CHANGELOG_FILE));
+
+    read_to_string(changelog_path).ok()
+}
+
+/// Regenerates the changelog section of a single released version of a package in place.
+/// The section spans from the version header up to the next header (or footer), it's re-rendered
+/// from the commits between the previous publish tag and the version publish tag, and spliced
+/// into the changelog (`changelog_file`, default `CHANGELOG_FILE`) keeping everything else
+/// untouched. Publish tags are read with the tag format (see `format_publish_tag`). Returns the
+/// new section.
+pub fn regenerate_changelog_section(
+    package: String,
+    version: String,
+    cwd: Option<String>,
+    options: &Option<Config>,
+    changelog_file: &Option<String>,
+    tag_format: &Option<String>,
+) -> Result<String, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let package_info = match get_package_info(package.to_string(), Some(root.to_string()))? {
+        Some(package_info) => package_info,
+        None => return Err(WorkspaceError::PackageNotFound(package)),
+    };
+
+    let changelog_path = PathBuf::from(package_info.package_path.to_string())
+        .join(changelog_file.as_deref().unwrap_or(CHANGELOG_FILE));
+
+    if !changelog_path.exists() {
+        return Err(WorkspaceError::FileNotFound(
+            changelog_path.display().to_string(),
+        ));
+    }
+
+    let changelog_content = read_to_string(&changelog_path)?;
+
+    let header_regex =
+        Regex::new(&format!(r"(?m)^(#+)\s*\[v?{}\]", regex::escape(&version))).unwrap();
+
+    let (section_start, heading_depth) = match header_regex.captures(&changelog_content) {
+        Some(header) => (header.get(0).unwrap().start(), header[1].len()),
+        None => return Err(WorkspaceError::VersionNotFound { package, version }),
+    };
+    // Sections end at the next heading as deep as the version heading (any heading level)
+    let boundary_regex = Regex::new(&format!(
+        r"(?m)^(#{{1,{}}}\s|-- Total Releases)",
+        heading_depth
+    ))
+    .unwrap();
+    let header_end = changelog_content[section_start..]
+        .find('\n')
+        .map(|index| section_start + index + 1)
+        .unwrap_or(changelog_content.len());
+    let section_end = boundary_regex
+        .find(&changelog_content[header_end..])
+        .map(|boundary| header_end + boundary.start())
+        .unwrap_or(changelog_content.len());
+
+    let package_tags = normalize_publish_tags(
+        get_remote_or_local_tags(Some(root.to_string()), Some(true))
+            .map_err(|error| WorkspaceError::Git(error.to_string()))?,
+        tag_format,
+    )
+    .iter()
+    .filter_map(|item| {
+        let tag = item.tag.replace("refs/tags/", "");
+        let (name, tag_version) = split_publish_tag(&tag)?;
+
+        match name == package {
+            true => SemVersion::parse(tag_version)
+                .ok()
+                .map(|semversion| (semversion, tag_version.to_string())),
+            false => None,
+        }
+    })
+    .collect::<Vec<(SemVersion, String)>>();
+
+    let target_tag = format_publish_tag(tag_format, &package, &version);
+
+    if !package_tags
+        .iter()
+        .any(|(_, tag_version)| tag_version == &version)
+    {
+        return Err(WorkspaceError::TagNotFound(target_tag));
+    }
+
+    let previous_version = match SemVersion::parse(&version) {
+        Ok(target_version) => package_tags
+            .iter()
+            .filter(|(semversion, _)| semversion < &target_version)
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, tag_version)| tag_version.to_string()),
+        Err(_) => None,
+    };
+    let previous_tag = previous_version
+        .as_ref()
+        .map(|previous_version| format_publish_tag(tag_format, &package, previous_version));
+
+    // The tag points to the release commit, the released commits are the ones before it
+    let commits = get_commits_between(
+        Some(root.to_string()),
+        previous_tag,
+        format!("{}^", target_tag),
+        Some(package_info.package_relative_path.to_string()),
+    );
+    let timestamp = get_commits_between(
+        Some(root.to_string()),
+        Some(format!("{}^", target_tag)),
+        target_tag.to_string(),
+        None,
+    )
+    .first()
+    .and_then(|commit| chrono::DateTime::parse_from_rfc2822(&commit.author_date).ok())
+    .map(|date| date.timestamp())
+    .unwrap_or(chrono::Utc::now().timestamp());
+
+    let repository_info = match package_info.repository_info {
+        Some(ref info) => info.to_owned(),
+        None => PackageRepositoryInfo {
+            orga: String::from("my-orga"),
+            project: String::from("my-repo"),
+            domain: String::from("https://github.com"),
+        },
+    };
+
+    let mut config = define_config(
+        repository_info.orga.to_string(),
+        repository_info.project.to_string(),
+        repository_info.domain.to_string(),
+        None,
+        None,
+        false,
+        2,
+        true,
+        false,
+        options,
+    );
+
+    // Only the release body is rendered, dated with the release commit instead of today
+    config.changelog.header = None;
+    config.changelog.footer = None;
+    config.changelog.body = config
+        .changelog
+        .body
+        .map(|body| body.replace("now()", "timestamp"));
+
+    let conventional_commits = process_commits(&commits, &config.git);
+    let release = Release {
+        timestamp,
+        ..package_release(
+            &package,
+            tag_format,
+            Some(version.to_string()),
+            conventional_commits,
+            previous_version,
+        )
+    };
+
+    let mut changelog_output = Vec::new();
+
+    Changelog::new(vec![release], &config)
+        .and_then(|changelog| changelog.generate(&mut changelog_output))
+        .map_err(|error| WorkspaceError::Changelog(error.to_string()))?;
+
+    let rendered = String::from_utf8(changelog_output).unwrap_or_default();
+    let section = match section_end < changelog_content.len() {
+        true => format!("{}\n\n", rendered.trim()),
+        false => format!("{}\n", rendered.trim()),
+    };
+
+    let new_changelog_content = format!(
+        "{}{}{}",
+        &changelog_content[..section_start],
+        section,
+        &changelog_content[section_end..]
+    );
+
+    std::fs::write(&changelog_path, new_changelog_content)?;
+
+    Ok(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::manager::PackageManager;
+    use crate::packages::get_packages;
+    use crate::paths::get_project_root_path;
+    use crate::utils::{create_test_monorepo, run_git};
+    use std::fs::remove_dir_all;
+    use std::fs::File;
+    use std::io::Write;
+    use std::process::Command;
+    use std::process::Stdio;
+
+    fn create_package_change(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        let js_path = monorepo_dir.join("packages/package-b/index.js");
+
+        let branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("-b")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git branch problem");
+
+        branch.wait_with_output()?;
+
+        let mut js_file = File::create(&js_path)?;
+        js_file
+            .write_all(r#"export const message = "hello";"#.as_bytes())
+            .unwrap();
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
             .arg("add")
             .arg(".")
             .stdout(Stdio::piped())
@@ -508,17 +1201,55 @@ mod tests {
 
         let ref root = project_root.unwrap().to_string();
 
-        let packages = get_packages(Some(root.to_string()));
+        let packages = get_packages(Some(root.to_string()))?;
         let package = packages.first();
 
         let conventional =
-            get_conventional_for_package(package.unwrap(), None, Some(root.to_string()), &None);
+            get_conventional_for_package(package.unwrap(), None, Some(root.to_string()), &None)?;
 
         assert_eq!(conventional.package_info, package.unwrap().to_owned());
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
+    #[test]
+    fn test_get_conventional_for_package_path_remote() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        // A local path remote has no domain, organization nor project
+        run_git(&monorepo_dir, &["remote", "add", "origin", "/srv/repo.git"])?;
+
+        std::fs::write(
+            monorepo_dir.join("packages/package-b/index.js"),
+            r#"export const message = "hello";"#,
+        )?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(
+            &monorepo_dir,
+            &["commit", "-m", "feat: message to the world"],
+        )?;
+
+        // Without a repository field, the changelog links fall back to the origin remote
+        let package = PackageInfo {
+            repository_info: None,
+            ..get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap()
+        };
+        let conventional =
+            get_conventional_for_package(&package, None, Some(root.to_string()), &None)?;
+
+        assert_eq!(
+            conventional
+                .changelog_output
+                .contains("https://github.com/my-orga/my-repo/commit/"),
+            true
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
     #[test]
     fn test_get_conventional_for_package_with_changes() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
@@ -528,13 +1259,13 @@ mod tests {
 
         let ref root = project_root.unwrap().to_string();
 
-        let packages = get_packages(Some(root.to_string()));
+        let packages = get_packages(Some(root.to_string()))?;
         let package = packages
             .iter()
             .find(|pkg| pkg.name.contains("@scope/package-b"));
 
         let conventional =
-            get_conventional_for_package(package.unwrap(), None, Some(root.to_string()), &None);
+            get_conventional_for_package(package.unwrap(), None, Some(root.to_string()), &None)?;
 
         assert_eq!(
             conventional
@@ -545,4 +1276,971 @@ mod tests {
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
+
+    #[test]
+    fn test_get_conventional_for_package_heading_level() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_package_change(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let package = packages
+            .iter()
+            .find(|pkg| pkg.name.contains("@scope/package-b"));
+
+        let conventional = get_conventional_for_package(
+            package.unwrap(),
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.1.0")),
+                heading_level: Some(3),
+                ..Default::default()
+            }),
+        )?;
+        let changelog = conventional.changelog_output;
+
+        assert_eq!(changelog.contains("\n### [1.1.0]"), true);
+        assert_eq!(changelog.contains("\n## [1.1.0]"), false);
+        assert_eq!(changelog.contains("#### ⛰️  Features"), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_without_commits() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let package =
+            get_package_info(String::from("@scope/package-c"), Some(root.to_string()))?.unwrap();
+        let conventional_options = |empty_placeholder: Option<String>| {
+            Some(ConventionalPackageOptions {
+                version: Some(String::from("1.0.1")),
+                title: Some(String::from("# What changed?")),
+                empty_placeholder,
+                ..Default::default()
+            })
+        };
+
+        let conventional = get_conventional_for_package(
+            &package,
+            None,
+            Some(root.to_string()),
+            &conventional_options(None),
+        )?;
+        let changelog = conventional.changelog_output;
+
+        assert_eq!(conventional.conventional_commits, json!([]));
+        assert_eq!(
+            changelog.starts_with("# What changed?\n\n## [1.0.1] - "),
+            true
+        );
+        assert_eq!(changelog.contains(EMPTY_CHANGELOG_PLACEHOLDER), true);
+
+        // An existing changelog keeps its header, the release goes right below it
+        std::fs::write(
+            PathBuf::from(&package.package_path).join(CHANGELOG_FILE),
+            "# What changed?\n\n## [1.0.0] - 2024-01-01\n\n- Initial release\n",
+        )?;
+
+        let conventional = get_conventional_for_package(
+            &package,
+            None,
+            Some(root.to_string()),
+            &conventional_options(Some(String::from("Dependencies only"))),
+        )?;
+        let changelog = conventional.changelog_output;
+        let release = changelog.find("## [1.0.1]").unwrap();
+        let previous_release = changelog.find("## [1.0.0]").unwrap();
+
+        assert_eq!(changelog.matches("# What changed?").count(), 1);
+        assert_eq!(changelog.contains("Dependencies only"), true);
+        assert_eq!(changelog.contains(EMPTY_CHANGELOG_PLACEHOLDER), false);
+        assert_eq!(release < previous_release, true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_prerelease_policy(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref package_json_path = monorepo_dir.join("packages/package-b/package.json");
+        let package_json = std::fs::read_to_string(package_json_path)?;
+        std::fs::write(
+            package_json_path,
+            package_json.replace(r#""version": "1.0.0""#, r#""version": "2.0.0-rc.1""#),
+        )?;
+
+        File::create(monorepo_dir.join("packages/package-b/candidate.js"))?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(
+            &monorepo_dir,
+            &["commit", "-m", "feat: release candidate feature"],
+        )?;
+        run_git(&monorepo_dir, &["tag", "@scope/package-b@2.0.0-rc.1"])?;
+
+        File::create(monorepo_dir.join("packages/package-b/final.js"))?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "fix: final fix"])?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        let package_info =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+
+        let changelog = |prerelease_policy: PrereleasePolicy| {
+            get_conventional_for_package(
+                &package_info,
+                None,
+                Some(root.to_string()),
+                &Some(ConventionalPackageOptions {
+                    version: Some(String::from("2.0.0")),
+                    prerelease_policy: Some(prerelease_policy),
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+            .changelog_output
+        };
+
+        let include_prerelease = changelog(PrereleasePolicy::IncludePrerelease);
+        let stable_only = changelog(PrereleasePolicy::StableOnly);
+        let prefer_stable = changelog(PrereleasePolicy::PreferStable);
+
+        assert_eq!(include_prerelease.contains("Final fix"), true);
+        assert_eq!(
+            include_prerelease.contains("Release candidate feature"),
+            false
+        );
+        assert_eq!(stable_only.contains("Final fix"), true);
+        assert_eq!(stable_only.contains("Release candidate feature"), true);
+        assert_eq!(prefer_stable, stable_only);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_changelog_config() -> Result<(), Box<dyn std::error::Error>> {
+        let toml = r###"
+[cliff.changelog]
+header = "## What's Changed"
+body = "{% for commit in commits %}- {{ commit.message }}{% endfor %}"
+trim = true
+
+[cliff.git]
+conventional_commits = true
+filter_unconventional = true
+tag_pattern = "@scope/package-a@[0-9].*"
+
+[[cliff.git.commit_parsers]]
+message = "^feat"
+group = "Features"
+"###;
+
+        let config = parse_changelog_config(toml)?;
+        let parsers = config.git.commit_parsers.unwrap();
+
+        assert_eq!(
+            config.changelog.header,
+            Some(String::from("## What's Changed"))
+        );
+        assert_eq!(config.git.conventional_commits, Some(true));
+        assert_eq!(parsers.len(), 1);
+        assert_eq!(parsers[0].group, Some(String::from("Features")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_changelog_config_invalid() -> Result<(), Box<dyn std::error::Error>> {
+        let config = parse_changelog_config("[cliff.changelog\nheader = ");
+
+        assert_eq!(matches!(config, Err(ConfigError::Parse(_))), true);
+        Ok(())
+    }
+
+    fn create_package_releases(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        let releases = [
+            ("feature.js", "feat: first feature", "1.1.0"),
+            ("fix.js", "fix: second fix", "1.2.0"),
+        ];
+
+        for (file, message, version) in releases {
+            File::create(monorepo_dir.join("packages/package-b").join(file))?;
+
+            run_git(&monorepo_dir, &["add", "."])?;
+            run_git(&monorepo_dir, &["commit", "-m", message])?;
+            run_git(
+                &monorepo_dir,
+                &["commit", "--allow-empty", "-m", "chore: release version"],
+            )?;
+            run_git(
+                &monorepo_dir,
+                &["tag", &format!("@scope/package-b@{}", version)],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_changelog() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let package_info =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+
+        assert_eq!(read_changelog(&package_info, &None), None);
+
+        let changelog = "# What changed?\n\n## [1.0.0] - 2024-01-01\n\n- First release\n";
+        let mut changelog_file =
+            File::create(monorepo_dir.join("packages/package-b/CHANGELOG.md"))?;
+        changelog_file.write_all(changelog.as_bytes())?;
+
+        assert_eq!(
+            read_changelog(&package_info, &None),
+            Some(String::from(changelog))
+        );
+        assert_eq!(
+            read_changelog(&package_info, &Some(String::from("CHANGES.md"))),
+            None
+        );
+
+        std::fs::rename(
+            monorepo_dir.join("packages/package-b/CHANGELOG.md"),
+            monorepo_dir.join("packages/package-b/CHANGES.md"),
+        )?;
+
+        assert_eq!(
+            read_changelog(&package_info, &Some(String::from("CHANGES.md"))),
+            Some(String::from(changelog))
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_regenerate_changelog_section() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let changelog_path = monorepo_dir.join("packages/package-b/CHANGELOG.md");
+
+        create_package_releases(monorepo_dir)?;
+
+        let header = "# What changed?\n\n";
+        let newer_section =
+            "## [1.2.0] - 2024-01-02\n\n### Bug Fixes\n\n- Second fix - (abc1234)\n\n";
+        let footer = "-- Total Releases: 1 --\n";
+        let corrupted = format!(
+            "{}{}## [1.1.0] - {{{{ broken }}}}\n\n%%% mangled %%%\n\n{}",
+            header, newer_section, footer
+        );
+
+        let mut changelog_file = File::create(&changelog_path)?;
+        changelog_file.write_all(corrupted.as_bytes())?;
+
+        let section = regenerate_changelog_section(
+            String::from("@scope/package-b"),
+            String::from("1.1.0"),
+            project_root.to_owned(),
+            &None,
+            &None,
+            &None,
+        )?;
+        let changelog = std::fs::read_to_string(&changelog_path)?;
+
+        assert_eq!(section.starts_with("## [1.1.0]"), true);
+        assert_eq!(section.contains("First feature"), true);
+        assert_eq!(section.contains("Second fix"), false);
+        assert_eq!(changelog.contains("mangled"), false);
+        assert_eq!(
+            changelog,
+            format!("{}{}{}{}", header, newer_section, section, footer)
+        );
+
+        let missing = regenerate_changelog_section(
+            String::from("@scope/package-b"),
+            String::from("9.9.9"),
+            project_root,
+            &None,
+            &None,
+            &None,
+        );
+
+        assert_eq!(
+            matches!(missing, Err(WorkspaceError::VersionNotFound { .. })),
+            true
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepend_changelog_header_once() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let changelog_path = monorepo_dir.join("packages/package-b/CHANGELOG.md");
+
+        let ref root = project_root.unwrap().to_string();
+
+        let releases = [
+            ("feature.js", "feat: first feature", "1.1.0"),
+            ("fix.js", "fix: second fix", "1.2.0"),
+        ];
+
+        for (file, message, version) in releases {
+            File::create(monorepo_dir.join("packages/package-b").join(file))?;
+
+            let add = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("add")
+                .arg(".")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git add problem");
+
+            add.wait_with_output()?;
+
+            let commit = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("commit")
+                .arg("-m")
+                .arg(message)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git commit problem");
+
+            commit.wait_with_output()?;
+
+            let packages = get_packages(Some(root.to_string()))?;
+            let package = packages
+                .iter()
+                .find(|pkg| pkg.name.contains("@scope/package-b"));
+
+            let conventional = get_conventional_for_package(
+                package.unwrap(),
+                None,
+                Some(root.to_string()),
+                &Some(ConventionalPackageOptions {
+                    version: Some(String::from(version)),
+                    title: Some(String::from("# What changed?")),
+                    ..Default::default()
+                }),
+            )?;
+
+            let mut changelog_file = File::create(&changelog_path)?;
+            changelog_file.write_all(conventional.changelog_output.as_bytes())?;
+
+            let tag = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("tag")
+                .arg(format!("@scope/package-b@{}", version))
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git tag problem");
+
+            tag.wait_with_output()?;
+        }
+
+        let changelog = std::fs::read_to_string(&changelog_path)?;
+
+        assert_eq!(changelog.matches("# What changed?").count(), 1);
+        assert_eq!(changelog.starts_with("# What changed?"), true);
+        assert_eq!(changelog.contains("## [1.1.0]"), true);
+        assert_eq!(changelog.contains("## [1.2.0]"), true);
+
+        // An existing header is kept, even if it differs from the configured title
+        let renamed = changelog.replacen("# What changed?", "# Changelog", 1);
+        let mut changelog_file = File::create(&changelog_path)?;
+        changelog_file.write_all(renamed.as_bytes())?;
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let package = packages
+            .iter()
+            .find(|pkg| pkg.name.contains("@scope/package-b"));
+
+        let conventional = get_conventional_for_package(
+            package.unwrap(),
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.3.0")),
+                title: Some(String::from("# What changed?")),
+                ..Default::default()
+            }),
+        )?;
+        let changelog = conventional.changelog_output;
+
+        assert_eq!(changelog.starts_with("# Changelog\n\n## [1.3.0]"), true);
+        assert_eq!(changelog.contains("# What changed?"), false);
+        assert_eq!(changelog.contains("## [1.2.0]"), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_group_by_scope() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let commits = [
+            ("core.js", "feat(core): add core module"),
+            ("button.js", "feat(ui): add button"),
+            ("plain.js", "feat: add plain feature"),
+        ];
+
+        for (file, message) in commits {
+            File::create(monorepo_dir.join("packages/package-b").join(file))?;
+
+            let add = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("add")
+                .arg(".")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git add problem");
+
+            add.wait_with_output()?;
+
+            let commit = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("commit")
+                .arg("-m")
+                .arg(message)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git commit problem");
+
+            commit.wait_with_output()?;
+        }
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let package = packages
+            .iter()
+            .find(|pkg| pkg.name.contains("@scope/package-b"));
+
+        let conventional = get_conventional_for_package(
+            package.unwrap(),
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.1.0")),
+                group_by_scope: Some(true),
+                ..Default::default()
+            }),
+        )?;
+        let changelog = conventional.changelog_output;
+
+        let core_heading = changelog.find("#### core").unwrap();
+        let ui_heading = changelog.find("#### ui").unwrap();
+        let core_commit = changelog.find("- Add core module").unwrap();
+        let ui_commit = changelog.find("- Add button").unwrap();
+
+        assert_eq!(core_heading < core_commit && core_commit < ui_heading, true);
+        assert_eq!(ui_heading < ui_commit, true);
+        assert_eq!(changelog.contains("- Add plain feature"), true);
+        assert_eq!(changelog.contains("*(core)*"), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_skip_commits() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let commits = [
+            ("kept.js", "feat: add kept feature"),
+            ("reverted.js", "feat: add accidental feature"),
+        ];
+
+        for (file, message) in commits {
+            File::create(monorepo_dir.join("packages/package-b").join(file))?;
+
+            let add = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("add")
+                .arg(".")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git add problem");
+
+            add.wait_with_output()?;
+
+            let commit = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("commit")
+                .arg("-m")
+                .arg(message)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git commit problem");
+
+            commit.wait_with_output()?;
+        }
+
+        let short_sha = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("rev-parse")
+            .arg("--short")
+            .arg("HEAD")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git rev-parse problem");
+        let short_sha = String::from_utf8(short_sha.wait_with_output()?.stdout)?;
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let package = packages
+            .iter()
+            .find(|pkg| pkg.name.contains("@scope/package-b"));
+
+        let conventional = get_conventional_for_package(
+            package.unwrap(),
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.1.0")),
+                skip_commits: Some(vec![short_sha.trim().to_string()]),
+                ..Default::default()
+            }),
+        )?;
+        let changelog = conventional.changelog_output;
+
+        assert_eq!(changelog.contains("Add kept feature"), true);
+        assert_eq!(changelog.contains("Add accidental feature"), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_merges_only() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        run_git(&monorepo_dir, &["checkout", "-b", "feat/login"])?;
+
+        let commits = [
+            ("login.js", "feat: wip login form"),
+            ("login.css", "fix: typo in login styles"),
+        ];
+
+        for (file, message) in commits {
+            File::create(monorepo_dir.join("packages/package-b").join(file))?;
+
+            run_git(&monorepo_dir, &["add", "."])?;
+            run_git(&monorepo_dir, &["commit", "-m", message])?;
+        }
+
+        run_git(&monorepo_dir, &["checkout", "main"])?;
+        run_git(
+            &monorepo_dir,
+            &[
+                "merge",
+                "--no-ff",
+                "feat/login",
+                "-m",
+                "feat: add login page",
+            ],
+        )?;
+
+        let package =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+        let changelog = |history_mode: Option<HistoryMode>| {
+            get_conventional_for_package(
+                &package,
+                None,
+                Some(root.to_string()),
+                &Some(ConventionalPackageOptions {
+                    version: Some(String::from("1.1.0")),
+                    history_mode,
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+            .changelog_output
+        };
+
+        let all_commits = changelog(None);
+
+        assert_eq!(all_commits.contains("Wip login form"), true);
+        assert_eq!(all_commits.contains("Typo in login styles"), true);
+
+        let merges_only = changelog(Some(HistoryMode::MergesOnly));
+
+        assert_eq!(merges_only.contains("Add login page"), true);
+        assert_eq!(merges_only.contains("Wip login form"), false);
+        assert_eq!(merges_only.contains("Typo in login styles"), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_link_issues() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        File::create(monorepo_dir.join("packages/package-b/linked.js"))?;
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("feat: add linked feature (#42)")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let package = packages
+            .iter()
+            .find(|pkg| pkg.name.contains("@scope/package-b"))
+            .unwrap();
+        let repository_info = package.repository_info.as_ref().unwrap();
+        let issue_link = format!(
+            "([#42]({}/{}/{}/issues/42))",
+            repository_info.domain, repository_info.orga, repository_info.project
+        );
+
+        let changelog_for = |link_issues: Option<bool>| {
+            get_conventional_for_package(
+                package,
+                None,
+                Some(root.to_string()),
+                &Some(ConventionalPackageOptions {
+                    version: Some(String::from("1.1.0")),
+                    link_issues,
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+            .changelog_output
+        };
+
+        let linked_changelog = changelog_for(None);
+        let plain_changelog = changelog_for(Some(false));
+
+        assert_eq!(linked_changelog.contains(&issue_link), true);
+        assert_eq!(plain_changelog.contains(&issue_link), false);
+        assert_eq!(plain_changelog.contains("(#42)"), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_packages_options() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        File::create(monorepo_dir.join("packages/package-a/feature.js"))?;
+        File::create(monorepo_dir.join("packages/package-b/feature.js"))?;
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("feat: add shared feature")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        let packages = get_packages(Some(root.to_string()))?
+            .into_iter()
+            .filter(|pkg| pkg.name == "@scope/package-a" || pkg.name == "@scope/package-b")
+            .collect::<Vec<PackageInfo>>();
+
+        let package_options = |title: &str, footer: Option<String>| ConventionalPackageOptions {
+            version: Some(String::from("1.1.0")),
+            title: Some(String::from(title)),
+            footer,
+            ..Default::default()
+        };
+
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            String::from("@scope/package-b"),
+            package_options("# Package B", Some(String::from("-- Package B footer --"))),
+        );
+
+        let conventionals = get_conventional_for_packages(
+            &packages,
+            None,
+            Some(root.to_string()),
+            &Some(package_options("# Shared", None)),
+            &overrides,
+        )?;
+
+        let changelog_for = |name: &str| {
+            conventionals
+                .iter()
+                .find(|conventional| conventional.package_info.name == name)
+                .map(|conventional| conventional.changelog_output.to_string())
+                .unwrap()
+        };
+
+        let changelog_a = changelog_for("@scope/package-a");
+        let changelog_b = changelog_for("@scope/package-b");
+
+        assert_eq!(conventionals.len(), 2);
+        assert_eq!(changelog_a.starts_with("# Shared"), true);
+        assert_eq!(changelog_a.contains("-- Total Releases: 1 --"), true);
+        assert_eq!(changelog_b.starts_with("# Package B"), true);
+        assert_eq!(changelog_b.contains("-- Package B footer --"), true);
+        assert_eq!(changelog_b.contains("Total Releases"), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_release_context() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        File::create(monorepo_dir.join("packages/package-b/context.js"))?;
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("feat: add context feature")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        let head = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("rev-parse")
+            .arg("HEAD")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git rev-parse problem");
+        let head = String::from_utf8(head.wait_with_output()?.stdout)?;
+
+        let package =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+        let package_path = package.package_path.to_string();
+
+        let context = get_release_context(
+            &package,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.1.0")),
+                ..Default::default()
+            }),
+        )?;
+
+        let release = &context.as_array().unwrap()[0];
+        let commits = release["commits"].as_array().unwrap();
+        let feature = commits
+            .iter()
+            .find(|commit| commit["id"] == head.trim())
+            .unwrap();
+
+        assert_eq!(release["version"], "1.1.0");
+        assert_eq!(release["previous"]["version"], "1.0.0");
+        assert_eq!(
+            feature["group"].as_str().unwrap().contains("Features"),
+            true
+        );
+        assert_eq!(
+            PathBuf::from(package_path).join("CHANGELOG.md").exists(),
+            false
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_previous_version() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_package_change(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let package = packages
+            .iter()
+            .find(|pkg| pkg.name.contains("@scope/package-b"));
+
+        let conventional = get_conventional_for_package(
+            package.unwrap(),
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.1.0")),
+                ..Default::default()
+            }),
+        )?;
+
+        assert_eq!(
+            conventional
+                .changelog_output
+                .contains("/compare/@scope/package-b@1.0.0..@scope/package-b@1.1.0"),
+            true
+        );
+
+        // The compare link uses the publish tags of the configured tag format
+        run_git(
+            &monorepo_dir,
+            &[
+                "tag",
+                "@scope/package-b-v1.0.0",
+                "@scope/package-b@1.0.0^{}",
+            ],
+        )?;
+
+        let conventional = get_conventional_for_package(
+            package.unwrap(),
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.1.0")),
+                tag_format: Some(String::from("{name}-v{version}")),
+                ..Default::default()
+            }),
+        )?;
+
+        assert_eq!(
+            conventional
+                .changelog_output
+                .contains("/compare/@scope/package-b-v1.0.0..@scope/package-b-v1.1.0"),
+            true
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_conventional_for_package_previous_version_tagged_release(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_package_releases(monorepo_dir)?;
+        create_package_change(monorepo_dir)?;
+
+        let ref root = project_root.unwrap().to_string();
+
+        let mut package_info =
+            get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?.unwrap();
+        package_info.version = String::from("1.2.0");
+
+        let conventional = get_conventional_for_package(
+            &package_info,
+            None,
+            Some(root.to_string()),
+            &Some(ConventionalPackageOptions {
+                version: Some(String::from("1.2.0")),
+                ..Default::default()
+            }),
+        )?;
+
+        assert_eq!(
+            conventional
+                .changelog_output
+                .contains("/compare/@scope/package-b@1.1.0..@scope/package-b@1.2.0"),
+            true
+        );
+
+        // The release candidate is the previous release unless the policy skips it
+        run_git(
+            &monorepo_dir,
+            &["tag", "@scope/package-b@1.2.0-rc.1", "HEAD~1"],
+        )?;
+
+        let compare_link = |prerelease_policy: Option<PrereleasePolicy>| {
+            let conventional = get_conventional_for_package(
+                &package_info,
+                None,
+                Some(root.to_string()),
+                &Some(ConventionalPackageOptions {
+                    version: Some(String::from("1.2.0")),
+                    prerelease_policy,
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+
+            (
+                conventional
+                    .changelog_output
+                    .contains("/compare/@scope/package-b@1.2.0-rc.1..@scope/package-b@1.2.0"),
+                conventional
+                    .changelog_output
+                    .contains("/compare/@scope/package-b@1.1.0..@scope/package-b@1.2.0"),
+            )
+        };
+
+        assert_eq!(compare_link(None), (true, false));
+        assert_eq!(
+            compare_link(Some(PrereleasePolicy::StableOnly)),
+            (false, true)
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
 use petgraph::{stable_graph::StableDiGraph, Direction};
 
+use super::manager::PackageManagerError;
+use super::packages::{get_packages, PackageInfo};
+
 /// Must be implemented by the type you wish
 /// to build a dependency graph for. See the README.md for an example
 pub trait Node {
@@ -136,11 +139,50 @@ where
     }
 }
 
+/// Renders the dependency graph of the workspace packages in Graphviz DOT format, an edge
+/// from each package to the workspace packages it depends on
+/// (e.g. `"@scope/package-a" -> "@scope/package-b";`). External dependencies are left out.
+pub fn dependency_graph_dot(cwd: Option<String>) -> Result<String, PackageManagerError> {
+    let packages = get_packages(cwd)?;
+    let dependency_graph = DependencyGraph::from(&packages[..]);
+    let graph = &dependency_graph.graph;
+
+    let mut nodes = packages
+        .iter()
+        .map(|package| format!("    \"{}\";", package.name))
+        .collect::<Vec<String>>();
+    nodes.sort();
+
+    let mut edges = graph
+        .edge_indices()
+        .filter_map(|edge| {
+            let (from, to) = graph.edge_endpoints(edge)?;
+            let from: &PackageInfo = graph[from].as_resolved()?;
+            let to: &PackageInfo = graph[to].as_resolved()?;
+
+            Some(format!("    \"{}\" -> \"{}\";", from.name, to.name))
+        })
+        .collect::<Vec<String>>();
+    edges.sort();
+    edges.dedup();
+
+    let mut dot = vec![String::from("digraph {")];
+    dot.append(&mut nodes);
+    dot.append(&mut edges);
+    dot.push(String::from("}"));
+
+    Ok(dot.join("\n"))
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::{DependencyGraph, Node, Step};
+    use super::{dependency_graph_dot, DependencyGraph, Node, Step};
+    use crate::manager::PackageManager;
+    use crate::paths::get_project_root_path;
+    use crate::utils::create_test_monorepo;
     use semver::{BuildMetadata, Prerelease, Version, VersionReq};
+    use std::fs::remove_dir_all;
 
     #[derive(Debug)]
     struct Package {
@@ -302,7 +344,7 @@ mod tests {
 
     #[test]
     fn test_internally_resolved() {
-        let packages = vec![
+        let packages = [
             Package {
                 name: "@scope/package-a",
                 version: semver::Version {
@@ -361,4 +403,21 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_dependency_graph_dot() -> Result<(), Box<dyn std::error::Error>> {
+        let monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let dot = dependency_graph_dot(project_root)?;
+
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.ends_with('}'));
+        assert!(dot.contains(r#"    "@scope/package-d";"#));
+        assert!(dot.contains(r#"    "@scope/package-a" -> "@scope/package-b";"#));
+        assert!(!dot.contains(r#""@scope/package-b" -> "#));
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
 }
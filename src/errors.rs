@@ -0,0 +1,81 @@
+#![allow(clippy::all)]
+
+//! # Errors
+//!
+//! This module provides the error type shared by the workspace operations that can fail.
+use std::fmt;
+
+use super::git::GitError;
+use super::manager::PackageManagerError;
+
+#[derive(Debug)]
+/// An error that represents a failed workspace operation
+pub enum WorkspaceError {
+    PackageNotFound(String),
+    FileNotFound(String),
+    VersionNotFound { package: String, version: String },
+    TagNotFound(String),
+    Changelog(String),
+    InvalidChanges(String),
+    InvalidConfig(String),
+    PathOutsideRepository(String),
+    UnexpectedStagedFiles(Vec<String>),
+    Git(String),
+    PackageManager(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkspaceError::PackageNotFound(package) => write!(f, "Package not found: {}", package),
+            WorkspaceError::FileNotFound(path) => write!(f, "File not found: {}", path),
+            WorkspaceError::VersionNotFound { package, version } => {
+                write!(f, "Version {} not found for package {}", version, package)
+            }
+            WorkspaceError::TagNotFound(tag) => write!(f, "Tag not found: {}", tag),
+            WorkspaceError::Changelog(message) => write!(f, "Changelog error: {}", message),
+            WorkspaceError::InvalidChanges(message) => write!(f, "Invalid changes: {}", message),
+            WorkspaceError::InvalidConfig(message) => write!(f, "Invalid config: {}", message),
+            WorkspaceError::PathOutsideRepository(path) => {
+                write!(f, "Path is outside the repository: {}", path)
+            }
+            WorkspaceError::UnexpectedStagedFiles(files) => {
+                write!(f, "Unexpected staged files: {}", files.join(", "))
+            }
+            WorkspaceError::Git(stderr) => write!(f, "Git command failed: {}", stderr),
+            WorkspaceError::PackageManager(message) => write!(f, "{}", message),
+            WorkspaceError::Io(error) => write!(f, "IO error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for WorkspaceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WorkspaceError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<GitError> for WorkspaceError {
+    fn from(error: GitError) -> Self {
+        match error {
+            GitError::Command(stderr) => WorkspaceError::Git(stderr),
+            error => WorkspaceError::Git(error.to_string()),
+        }
+    }
+}
+
+impl From<PackageManagerError> for WorkspaceError {
+    fn from(error: PackageManagerError) -> Self {
+        WorkspaceError::PackageManager(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for WorkspaceError {
+    fn from(error: std::io::Error) -> Self {
+        WorkspaceError::Io(error)
+    }
+}
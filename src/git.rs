@@ -4,21 +4,46 @@
 #![allow(clippy::all)]
 use execute::Execute;
 use icu::collator::{Collator, CollatorOptions, Numeric, Strength};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{Read, Write};
+use std::path::{Component, PathBuf};
 use std::{
     env::temp_dir,
-    fs::{remove_file, File},
+    fs::{canonicalize, remove_file, File},
     path::Path,
-    process::{Command, Stdio},
+    process::{Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
 };
-use version_compare::{Cmp, Version};
 
+use super::config::read_release_config;
+use super::errors::WorkspaceError;
 use super::packages::PackageInfo;
-use super::paths::get_project_root_path;
-use super::utils::{package_scope_name_version, strip_trailing_newline};
+use super::paths::{get_project_root_path, is_within, to_workspace_relative};
+use super::utils::strip_trailing_newline;
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CommitTrailer {
+    pub key: String,
+    pub value: String,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents a commit message trailer (e.g. `Deploy: staging`)
+pub struct CommitTrailer {
+    pub key: String,
+    pub value: String,
+}
 
 #[cfg(feature = "napi")]
 #[napi(object)]
@@ -29,17 +54,24 @@ pub struct Commit {
     pub author_email: String,
     pub author_date: String,
     pub message: String,
+    pub subject: String,
+    pub body: String,
+    pub trailers: Vec<CommitTrailer>,
 }
 
 #[cfg(not(feature = "napi"))]
 #[derive(Debug, Clone, Deserialize, Serialize)]
-/// A struct that represents a commit information
+/// A struct that represents a commit information. `message` is the full raw message,
+/// `subject` its first line and `body` the rest (trailers included).
 pub struct Commit {
     pub hash: String,
     pub author_name: String,
     pub author_email: String,
     pub author_date: String,
     pub message: String,
+    pub subject: String,
+    pub body: String,
+    pub trailers: Vec<CommitTrailer>,
 }
 
 #[cfg(feature = "napi")]
@@ -76,6 +108,515 @@ pub struct PublishTagInfo {
     pub package: String,
 }
 
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Divergence {
+    pub merge_base: String,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents how far HEAD diverged from a reference.
+/// `ahead` counts commits only in HEAD and `behind` commits only in the reference.
+pub struct Divergence {
+    pub merge_base: String,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct CloneOptions {
+    pub depth: Option<u32>,
+    pub branch: Option<String>,
+    pub single_branch: Option<bool>,
+    pub filter: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+/// A struct that represents the options of a clone, mapping to `--depth`, `--branch`,
+/// `--single-branch` and `--filter` (e.g. `blob:none`).
+pub struct CloneOptions {
+    pub depth: Option<u32>,
+    pub branch: Option<String>,
+    pub single_branch: Option<bool>,
+    pub filter: Option<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub files: Vec<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents a commit created from a list of paths, with the
+/// commit sha and the committed files relative to the repository root.
+pub struct CommitInfo {
+    pub sha: String,
+    pub files: Vec<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum PrereleasePolicy {
+    IncludePrerelease,
+    StableOnly,
+    PreferStable,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+/// An enum that represents which publish tags can be the previous release of a package.
+/// `IncludePrerelease` (default) takes any tag, `StableOnly` skips prerelease tags
+/// (e.g. `@scope/pkg@2.0.0-rc.1`) and `PreferStable` only takes a prerelease tag when the
+/// package has no stable one. `StableOnly` is recommended for changelogs, so the commits
+/// released in a prerelease are listed again in the stable release.
+pub enum PrereleasePolicy {
+    IncludePrerelease,
+    StableOnly,
+    PreferStable,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum HistoryMode {
+    AllCommits,
+    FirstParentOnly,
+    MergesOnly,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+/// An enum that represents which commits of the history are collected. `AllCommits` (default)
+/// takes every commit, `FirstParentOnly` follows the first parent of merges (the commits landed
+/// on the branch itself) and `MergesOnly` only takes its merge commits, whose titles are the
+/// pull request titles in merge-commit workflows.
+pub enum HistoryMode {
+    AllCommits,
+    FirstParentOnly,
+    MergesOnly,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct CommitsSinceOptions {
+    pub author: Option<String>,
+    pub exclude_authors: Option<Vec<String>>,
+    pub history_mode: Option<HistoryMode>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+/// A struct that represents the options of `get_commits_since`. `author` is passed to
+/// `git log --author`, while commits whose author name or email contains one of the
+/// `exclude_authors` (e.g. `dependabot`) are dropped. `history_mode` defaults to `AllCommits`.
+pub struct CommitsSinceOptions {
+    pub author: Option<String>,
+    pub exclude_authors: Option<Vec<String>>,
+    pub history_mode: Option<HistoryMode>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum BranchScope {
+    Local,
+    Remote,
+    All,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+/// An enum that represents which branches to list, local (`refs/heads`), remote tracking
+/// (`refs/remotes`) or both
+pub enum BranchScope {
+    Local,
+    Remote,
+    All,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct BranchInfo {
+    pub name: String,
+    pub head_sha: String,
+    pub upstream: Option<String>,
+    pub is_current: bool,
+    pub last_commit_date: String,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents a branch. `name` has no `refs/heads/` or `refs/remotes/<remote>/`
+/// prefix, `upstream` is the short upstream name of a local branch (e.g. `origin/main`) and
+/// `last_commit_date` the rfc2822 committer date of its head.
+pub struct BranchInfo {
+    pub name: String,
+    pub head_sha: String,
+    pub upstream: Option<String>,
+    pub is_current: bool,
+    pub last_commit_date: String,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct GraphNode {
+    pub sha: String,
+    pub parents: Vec<String>,
+    pub refs: Vec<String>,
+    pub subject: String,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A commit of the history graph. `parents` are full shas (two or more for a merge) and
+/// `refs` the decorations of the commit, e.g. `HEAD -> main` or `tag: @scope/package-a@1.0.0`.
+pub struct GraphNode {
+    pub sha: String,
+    pub parents: Vec<String>,
+    pub refs: Vec<String>,
+    pub subject: String,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FileStat {
+    pub path: String,
+    pub insertions: Option<u32>,
+    pub deletions: Option<u32>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// The lines inserted and deleted in a file (`git diff --numstat`), relative to the repository
+/// root. Binary files have no line counts.
+pub struct FileStat {
+    pub path: String,
+    pub insertions: Option<u32>,
+    pub deletions: Option<u32>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Worktree {
+    pub path: String,
+    pub branch: Option<String>,
+    pub sha: String,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A worktree of the repository, the main one included. `branch` is the checked out branch
+/// (e.g. `main`), `None` for a detached HEAD.
+pub struct Worktree {
+    pub path: String,
+    pub branch: Option<String>,
+    pub sha: String,
+}
+
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+    Untracked,
+    Ignored,
+    Conflicted,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+/// An enum that represents the status of a file in the working tree or the index.
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+    Untracked,
+    Ignored,
+    Conflicted,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct StatusEntry {
+    pub path: String,
+    pub original_path: Option<String>,
+    pub status: FileStatus,
+    pub staged: bool,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A changed file of the working tree, relative to the repository root. `staged` is true when
+/// the change is in the index, `original_path` is the source of a rename or copy.
+pub struct StatusEntry {
+    pub path: String,
+    pub original_path: Option<String>,
+    pub status: FileStatus,
+    pub staged: bool,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct HeadSha {
+    pub short: String,
+    pub full: String,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// The sha of HEAD, short for display and full for precise refs
+pub struct HeadSha {
+    pub short: String,
+    pub full: String,
+}
+
+#[derive(Debug)]
+/// An error that represents a failed git operation
+pub enum GitError {
+    UnknownRef(String),
+    NoMergeBase(String),
+    Command(String),
+    Conflict(Vec<String>),
+    Timeout(String),
+    OutputTooLarge(String),
+}
+
+impl std::fmt::Display for GitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitError::UnknownRef(refer) => write!(f, "Unknown git reference: {}", refer),
+            GitError::NoMergeBase(refer) => {
+                write!(f, "No common ancestor between HEAD and {}", refer)
+            }
+            GitError::Command(stderr) => write!(f, "Git command failed: {}", stderr),
+            GitError::Conflict(files) => write!(f, "Git conflict in: {}", files.join(", ")),
+            GitError::Timeout(command) => write!(f, "Git command timed out: {}", command),
+            GitError::OutputTooLarge(command) => {
+                write!(f, "Git command output too large: {}", command)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GitError {}
+
+/// Default timeout of the network commands (fetch, push, ls-remote), overridden by the
+/// `network_timeout_ms` release config or the `WORKSPACE_TOOLS_NETWORK_TIMEOUT_MS` environment
+/// variable.
+pub const DEFAULT_NETWORK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default timeout of the other spawned commands (e.g. `pnpm list`), overridden by the
+/// `command_timeout_ms` release config or the `WORKSPACE_TOOLS_COMMAND_TIMEOUT_MS` environment
+/// variable.
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default maximum size of stdout (and of stderr) of a spawned command, overridden by the
+/// `max_output_bytes` release config or the `WORKSPACE_TOOLS_MAX_OUTPUT_BYTES` environment
+/// variable.
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Default format of the publish tags, `{name}` and `{version}` are the package name and version.
+pub const DEFAULT_TAG_FORMAT: &str = "{name}@{version}";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Limits of a spawned command, the command is killed when one is breached. `None` is no limit.
+pub struct CommandLimits {
+    pub timeout: Option<Duration>,
+    pub max_output_bytes: Option<usize>,
+}
+
+impl CommandLimits {
+    /// Limits of the network commands run in the project of `cwd`, `DEFAULT_NETWORK_TIMEOUT` and
+    /// `DEFAULT_MAX_OUTPUT_BYTES` unless overridden by the release config of the project, itself
+    /// overridden by the environment.
+    pub fn network(cwd: &Option<String>) -> Self {
+        let config = read_release_config(cwd.to_owned()).unwrap_or_default();
+
+        CommandLimits {
+            timeout: Some(get_limit_timeout(
+                "WORKSPACE_TOOLS_NETWORK_TIMEOUT_MS",
+                config.network_timeout_ms,
+                DEFAULT_NETWORK_TIMEOUT,
+            )),
+            max_output_bytes: Some(get_limit_max_output_bytes(config.max_output_bytes)),
+        }
+    }
+
+    /// Limits of the local commands run in the project of `cwd`, `DEFAULT_COMMAND_TIMEOUT` and
+    /// `DEFAULT_MAX_OUTPUT_BYTES` unless overridden the same way as the network ones.
+    pub fn local(cwd: &Option<String>) -> Self {
+        let config = read_release_config(cwd.to_owned()).unwrap_or_default();
+
+        CommandLimits {
+            timeout: Some(get_limit_timeout(
+                "WORKSPACE_TOOLS_COMMAND_TIMEOUT_MS",
+                config.command_timeout_ms,
+                DEFAULT_COMMAND_TIMEOUT,
+            )),
+            max_output_bytes: Some(get_limit_max_output_bytes(config.max_output_bytes)),
+        }
+    }
+}
+
+/// Reads a limit from the environment, ignored when it isn't a number.
+fn env_limit(name: &str) -> Option<usize> {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+}
+
+/// Timeout from the environment, then from the release config, then the default.
+fn get_limit_timeout(name: &str, config_timeout_ms: Option<u32>, default: Duration) -> Duration {
+    env_limit(name)
+        .map(|timeout| timeout as u64)
+        .or(config_timeout_ms.map(|timeout| timeout as u64))
+        .map_or(default, Duration::from_millis)
+}
+
+/// Maximum output size from the environment, then from the release config, then the default.
+fn get_limit_max_output_bytes(config_max_output_bytes: Option<u32>) -> usize {
+    env_limit("WORKSPACE_TOOLS_MAX_OUTPUT_BYTES")
+        .or(config_max_output_bytes.map(|bytes| bytes as usize))
+        .unwrap_or(DEFAULT_MAX_OUTPUT_BYTES)
+}
+
+/// Runs a git command, killing it when it runs longer than the timeout (no limit when `None`).
+/// Stdout and stderr are read while the command runs, so a verbose command can't block on
+/// full pipes. Meant for network operations that can hang, e.g. on a credentials prompt.
+pub fn git_execute_with_timeout(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> Result<Output, GitError> {
+    execute_with_limits(
+        command,
+        &CommandLimits {
+            timeout,
+            max_output_bytes: None,
+        },
+    )
+}
+
+/// Runs a command within limits. It's killed (and reaped) when it runs longer than the timeout,
+/// failing with `GitError::Timeout`, or when stdout or stderr outgrow the maximum output size,
+/// failing with `GitError::OutputTooLarge`. Both errors carry the command line.
+pub fn execute_with_limits(
+    command: &mut Command,
+    limits: &CommandLimits,
+) -> Result<Output, GitError> {
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    if limits.timeout.is_none() && limits.max_output_bytes.is_none() {
+        return command
+            .execute_output()
+            .map_err(|error| GitError::Command(error.to_string()));
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|error| GitError::Command(error.to_string()))?;
+
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let read_pipe = |mut pipe: Box<dyn Read + Send>| {
+        let exceeded = Arc::clone(&exceeded);
+        let max_output_bytes = limits.max_output_bytes;
+
+        thread::spawn(move || {
+            let mut buffer = vec![];
+            let mut chunk = [0u8; 8192];
+
+            loop {
+                match pipe.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) if max_output_bytes.map_or(false, |max| buffer.len() + read > max) => {
+                        exceeded.store(true, Ordering::SeqCst);
+                        break;
+                    }
+                    Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                }
+            }
+
+            buffer
+        })
+    };
+    let stdout_reader = read_pipe(Box::new(child.stdout.take().unwrap()));
+    let stderr_reader = read_pipe(Box::new(child.stderr.take().unwrap()));
+
+    let command_line = || {
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+
+    let started = Instant::now();
+
+    let status = loop {
+        let timed_out = limits
+            .timeout
+            .map_or(false, |timeout| started.elapsed() >= timeout);
+        let too_large = exceeded.load(Ordering::SeqCst);
+
+        if timed_out || too_large {
+            child.kill().ok();
+            child.wait().ok();
+
+            return Err(match too_large {
+                true => GitError::OutputTooLarge(command_line()),
+                false => GitError::Timeout(command_line()),
+            });
+        }
+
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(error) => return Err(GitError::Command(error.to_string())),
+        }
+    };
+
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    // The command can exit right after outgrowing the limit
+    if exceeded.load(Ordering::SeqCst) {
+        return Err(GitError::OutputTooLarge(command_line()));
+    }
+
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
 /// Stage all uncommitted changes
 pub fn git_add_all(cwd: &String) -> Result<bool, std::io::Error> {
     let mut git_add = Command::new("git");
@@ -125,7 +666,7 @@ pub fn git_config(username: &String, email: &String, cwd: &String) -> Result<boo
     git_config_user.stdout(Stdio::piped());
     git_config_user.stderr(Stdio::piped());
 
-    let output_user = git_config_user.execute_output().unwrap();
+    let output_user = git_config_user.execute_output()?;
 
     let mut git_config_email = Command::new("git");
     git_config_email
@@ -137,7 +678,7 @@ pub fn git_config(username: &String, email: &String, cwd: &String) -> Result<boo
     git_config_email.stdout(Stdio::piped());
     git_config_email.stderr(Stdio::piped());
 
-    let output_email = git_config_email.execute_output().unwrap();
+    let output_email = git_config_email.execute_output()?;
     let status = output_user.status.success() == output_email.status.success();
 
     if status {
@@ -147,29 +688,82 @@ pub fn git_config(username: &String, email: &String, cwd: &String) -> Result<boo
     }
 }
 
-/// Fetch everything from origin including tags
+/// Fetch everything from origin including tags, within the network command limits
+/// (see `CommandLimits::network`).
 pub fn git_fetch_all(
     cwd: Option<String>,
     fetch_tags: Option<bool>,
 ) -> Result<bool, std::io::Error> {
+    let timeout = CommandLimits::network(&cwd).timeout;
+
+    git_fetch_all_with_timeout(cwd, fetch_tags, timeout)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))
+}
+
+/// Fetch everything from origin including tags, failing with `GitError::Timeout` when the
+/// fetch runs longer than the timeout.
+pub fn git_fetch_all_with_timeout(
+    cwd: Option<String>,
+    fetch_tags: Option<bool>,
+    timeout: Option<Duration>,
+) -> Result<bool, GitError> {
+    git_fetch_with_timeout(
+        &String::from("origin"),
+        fetch_tags.unwrap_or(false),
+        None,
+        cwd,
+        timeout,
+    )
+}
+
+/// Fetch everything from the given remote, tags included with `fetch_tags`. With a depth the
+/// history is truncated to that many commits (`--depth`), within the network command limits
+/// (see `CommandLimits::network`).
+pub fn git_fetch(
+    remote: &String,
+    fetch_tags: bool,
+    depth: Option<u32>,
+    cwd: Option<String>,
+) -> Result<bool, GitError> {
+    let timeout = CommandLimits::network(&cwd).timeout;
+
+    git_fetch_with_timeout(remote, fetch_tags, depth, cwd, timeout)
+}
+
+/// Fetch everything from the given remote the same way as `git_fetch`, failing with
+/// `GitError::Timeout` when the fetch runs longer than the timeout.
+pub fn git_fetch_with_timeout(
+    remote: &String,
+    fetch_tags: bool,
+    depth: Option<u32>,
+    cwd: Option<String>,
+    timeout: Option<Duration>,
+) -> Result<bool, GitError> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
     let mut command = Command::new("git");
-    command.arg("fetch").arg("origin");
+    command.arg("fetch").arg(remote);
 
-    if fetch_tags.unwrap_or(false) {
+    if fetch_tags {
         command.arg("--tags").arg("--force");
     }
 
-    command.current_dir(&current_working_dir);
+    if let Some(depth) = depth {
+        command.arg("--depth").arg(depth.to_string());
+    }
 
-    command.stdout(Stdio::piped());
-    command.stderr(Stdio::piped());
+    command.current_dir(&current_working_dir);
 
-    let output = command.execute_output().unwrap();
+    let output = execute_with_limits(
+        &mut command,
+        &CommandLimits {
+            timeout,
+            ..CommandLimits::network(&Some(current_working_dir.to_string()))
+        },
+    )?;
 
     if output.status.success() {
         Ok(true)
@@ -203,16 +797,19 @@ pub fn get_diverged_commit(refer: String, cwd: Option<String>) -> Option<String>
     Some(strip_trailing_newline(&output))
 }
 
-/// Get the current commit id
-pub fn git_current_sha(cwd: Option<String>) -> String {
+/// Resolves a git SHA, branch or tag (annotated tags are peeled) to the full sha of its commit.
+pub fn git_resolve_commit(refer: String, cwd: Option<String>) -> Option<String> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
     let mut command = Command::new("git");
-    command.arg("rev-parse").arg("--short").arg("HEAD");
-
+    command
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{}^{{commit}}", refer));
     command.current_dir(&current_working_dir);
 
     command.stdout(Stdio::piped());
@@ -220,20 +817,28 @@ pub fn git_current_sha(cwd: Option<String>) -> String {
 
     let output = command.execute_output().unwrap();
 
-    let hash = String::from_utf8(output.stdout).unwrap();
-    strip_trailing_newline(&hash)
+    if !output.status.success() {
+        return None;
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    Some(strip_trailing_newline(&output))
 }
 
-/// Get the previous commit id
-pub fn git_previous_sha(cwd: Option<String>) -> String {
+/// Get how far HEAD diverged from a particular git SHA, branch or tag.
+pub fn get_divergence(refer: String, cwd: Option<String>) -> Result<Divergence, GitError> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
     let mut command = Command::new("git");
-    command.arg("rev-parse").arg("--short").arg("HEAD~1");
-
+    command
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{}^{{commit}}", refer));
     command.current_dir(&current_working_dir);
 
     command.stdout(Stdio::piped());
@@ -241,33 +846,22 @@ pub fn git_previous_sha(cwd: Option<String>) -> String {
 
     let output = command.execute_output().unwrap();
 
-    let hash = String::from_utf8(output.stdout).unwrap();
-
-    strip_trailing_newline(&hash)
-}
-
-/// Get the first commit in a branch
-pub fn git_first_sha(cwd: Option<String>, branch: Option<String>) -> String {
-    let current_working_dir = match cwd {
-        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
-        None => get_project_root_path(None).unwrap(),
-    };
+    if !output.status.success() {
+        return Err(GitError::UnknownRef(refer));
+    }
 
-    let branch = match branch {
-        Some(branch) => branch,
-        None => String::from("main"),
-    };
+    let merge_base =
+        match get_diverged_commit(refer.to_string(), Some(current_working_dir.to_string())) {
+            Some(merge_base) => merge_base,
+            None => return Err(GitError::NoMergeBase(refer)),
+        };
 
     let mut command = Command::new("git");
     command
-        .arg("log")
-        .arg(format!("{}..HEAD", branch))
-        .arg("--online")
-        .arg("--pretty=format:%h")
-        .arg("|")
-        .arg("tail")
-        .arg("-1");
-
+        .arg("rev-list")
+        .arg("--left-right")
+        .arg("--count")
+        .arg(format!("{}...HEAD", refer));
     command.current_dir(&current_working_dir);
 
     command.stdout(Stdio::piped());
@@ -275,20 +869,64 @@ pub fn git_first_sha(cwd: Option<String>, branch: Option<String>) -> String {
 
     let output = command.execute_output().unwrap();
 
-    let hash = String::from_utf8(output.stdout).unwrap();
+    if !output.status.success() {
+        return Err(GitError::UnknownRef(refer));
+    }
 
-    strip_trailing_newline(&hash)
+    let output = String::from_utf8(output.stdout).unwrap();
+    let counts = strip_trailing_newline(&output)
+        .split_whitespace()
+        .map(|count| count.parse::<u32>().unwrap_or(0))
+        .collect::<Vec<u32>>();
+
+    Ok(Divergence {
+        merge_base,
+        ahead: counts.get(1).copied().unwrap_or(0),
+        behind: counts.get(0).copied().unwrap_or(0),
+    })
 }
 
-/// Verify if as uncommited changes in the current working directory
-pub fn git_workdir_unclean(cwd: Option<String>) -> bool {
+/// Normalizes a repository url (ssh, scp-like, git+https, ...) to its https form,
+/// e.g. `git@github.com:org/repo.git` becomes `https://github.com/org/repo`.
+pub fn normalize_repository_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.strip_prefix("git+").unwrap_or(url);
+
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => match rest.split_once('/') {
+            Some((authority, path)) => (authority.to_string(), path.to_string()),
+            None => (rest.to_string(), String::new()),
+        },
+        None => match url.split_once(':') {
+            Some((authority, path)) => (authority.to_string(), path.to_string()),
+            None => return url.to_string(),
+        },
+    };
+
+    let host = host
+        .rsplit_once('@')
+        .map_or(host.as_str(), |(_, host)| host);
+    let host = host.split_once(':').map_or(host, |(host, _)| host);
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    format!("https://{}/{}", host, path)
+}
+
+/// Get the url of a remote (defaults to origin), normalized to https.
+pub fn git_remote_url(remote: Option<String>, cwd: Option<String>) -> Option<String> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
+    let remote = match remote {
+        Some(remote) => remote,
+        None => String::from("origin"),
+    };
+
     let mut command = Command::new("git");
-    command.arg("status").arg("--porcelain");
+    command.arg("remote").arg("get-url").arg(&remote);
 
     command.current_dir(&current_working_dir);
 
@@ -297,61 +935,72 @@ pub fn git_workdir_unclean(cwd: Option<String>) -> bool {
 
     let output = command.execute_output().unwrap();
 
+    if !output.status.success() {
+        return None;
+    }
+
     let output = String::from_utf8(output.stdout).unwrap();
-    let result = strip_trailing_newline(&output);
+    let url = strip_trailing_newline(&output);
 
-    if result.is_empty() {
-        return false;
+    if url.is_empty() {
+        return None;
     }
 
-    true
+    Some(normalize_repository_url(&url))
 }
 
-/// Get the current branch name
-pub fn git_current_branch(cwd: Option<String>) -> Option<String> {
-    let current_working_dir = match cwd {
-        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
-        None => get_project_root_path(None).unwrap(),
-    };
-
+/// Clone a repository into the destination directory. Returns the path of the clone.
+/// Failures (auth, missing refs, existing destination) carry the git stderr.
+pub fn git_clone(
+    url: &String,
+    destination: &Path,
+    options: &CloneOptions,
+) -> Result<String, GitError> {
     let mut command = Command::new("git");
-    command.arg("rev-parse").arg("--abbrev-ref").arg("HEAD");
+    command.arg("clone");
 
-    command.current_dir(&current_working_dir);
+    if let Some(depth) = options.depth {
+        command.arg("--depth").arg(depth.to_string());
+    }
+
+    if let Some(ref branch) = options.branch {
+        command.arg("--branch").arg(branch);
+    }
+
+    if options.single_branch.unwrap_or(false) {
+        command.arg("--single-branch");
+    }
+
+    if let Some(ref filter) = options.filter {
+        command.arg(format!("--filter={}", filter));
+    }
+
+    command.arg(url).arg(destination);
 
     command.stdout(Stdio::piped());
     command.stderr(Stdio::piped());
 
     let output = command.execute_output().unwrap();
 
-    let output = String::from_utf8(output.stdout).unwrap();
-    let result = strip_trailing_newline(&output);
-
-    if result.is_empty() {
-        return None;
+    if !output.status.success() {
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        return Err(GitError::Command(strip_trailing_newline(&stderr)));
     }
 
-    Some(result)
+    let destination = std::fs::canonicalize(destination).unwrap();
+
+    Ok(destination.display().to_string())
 }
 
-/// Get the branch (last) name for a commit
-pub fn git_branch_from_commit(commit: String, cwd: Option<String>) -> Option<String> {
+/// Add a remote to the repository. Fails with the git stderr if the remote already exists.
+pub fn git_add_remote(name: &String, url: &String, cwd: Option<String>) -> Result<(), GitError> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    // git --no-pager branch --no-color --no-column --format "%(refname:lstrip=2)" --contains <commit>
     let mut command = Command::new("git");
-    command
-        .arg("--no-pager")
-        .arg("branch")
-        .arg("--no-color")
-        .arg("--no-column")
-        .arg("--format")
-        .arg(r#""%(refname:lstrip=2)""#)
-        .arg("--contains")
-        .arg(&commit);
+    command.arg("remote").arg("add").arg(name).arg(url);
 
     command.current_dir(&current_working_dir);
 
@@ -360,32 +1009,23 @@ pub fn git_branch_from_commit(commit: String, cwd: Option<String>) -> Option<Str
 
     let output = command.execute_output().unwrap();
 
-    let output = String::from_utf8(output.stdout).unwrap();
-    let result = strip_trailing_newline(&output);
-
-    if result.is_empty() {
-        return None;
+    if !output.status.success() {
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        return Err(GitError::Command(strip_trailing_newline(&stderr)));
     }
 
-    Some(result)
+    Ok(())
 }
 
-/// Tags the current commit with a message
-pub fn git_tag(
-    tag: String,
-    message: Option<String>,
-    cwd: Option<String>,
-) -> Result<bool, std::io::Error> {
+/// Get the remotes of the repository as (name, fetch url) pairs.
+pub fn git_get_remotes(cwd: Option<String>) -> Vec<(String, String)> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let default_message = &tag;
-    let msg = message.or(Some(default_message.to_string())).unwrap();
-
     let mut command = Command::new("git");
-    command.arg("tag").arg("-a").arg(&tag).arg("-m").arg(&msg);
+    command.arg("remote").arg("-v");
 
     command.current_dir(&current_working_dir);
 
@@ -394,28 +1034,35 @@ pub fn git_tag(
 
     let output = command.execute_output().unwrap();
 
-    if output.status.success() {
-        Ok(true)
-    } else {
-        Ok(false)
+    if !output.status.success() {
+        return vec![];
     }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    output
+        .lines()
+        .filter(|line| line.ends_with("(fetch)"))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let url = parts.next()?;
+
+            Some((name.to_string(), url.to_string()))
+        })
+        .collect::<Vec<(String, String)>>()
 }
 
-/// Pushes all changes in the monorepo without verification and follow tags
-pub fn git_push(cwd: Option<String>, follow_tags: Option<bool>) -> Result<bool, std::io::Error> {
+/// Get the current commit id
+pub fn git_current_sha(cwd: Option<String>) -> String {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
     let mut command = Command::new("git");
-    command.arg("push");
-
-    if follow_tags.unwrap_or(false) {
-        command.arg("--follow-tags");
-    }
+    command.arg("rev-parse").arg("--short").arg("HEAD");
 
-    command.arg("--no-verify");
     command.current_dir(&current_working_dir);
 
     command.stdout(Stdio::piped());
@@ -423,49 +1070,54 @@ pub fn git_push(cwd: Option<String>, follow_tags: Option<bool>) -> Result<bool,
 
     let output = command.execute_output().unwrap();
 
-    if output.status.success() {
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let hash = String::from_utf8(output.stdout).unwrap();
+    strip_trailing_newline(&hash)
 }
 
-// Commit all changes in the monorepo
-pub fn git_commit(
-    mut message: String,
-    body: Option<String>,
-    footer: Option<String>,
-    cwd: Option<String>,
-) -> Result<bool, std::io::Error> {
+/// Get the short and full sha of HEAD with a single git call
+pub fn git_head_sha(cwd: Option<String>) -> HeadSha {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    if body.is_some() {
-        message.push_str("\n\n");
-        message.push_str(body.unwrap().as_str());
-    }
+    let mut command = Command::new("git");
+    command
+        .arg("rev-parse")
+        .arg("HEAD")
+        .arg("--short")
+        .arg("HEAD");
 
-    if footer.is_some() {
-        message.push_str("\n\n");
-        message.push_str(footer.unwrap().as_str());
-    }
+    command.current_dir(&current_working_dir);
 
-    let temp_dir = temp_dir();
-    let temp_file_path = temp_dir.join("commit_message.txt");
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
 
-    let mut file = File::create(&temp_file_path).unwrap();
-    file.write_all(message.as_bytes()).unwrap();
+    let output = command.execute_output().unwrap();
 
-    let file_path = temp_file_path.as_path();
+    let output = String::from_utf8(output.stdout).unwrap();
+    let mut lines = output.lines().map(|line| line.trim().to_string());
+
+    let full = lines.next().unwrap_or_default();
+    let short = lines.next().unwrap_or_default();
+
+    HeadSha { short, full }
+}
+
+/// Get the short sha of the last commit that touched a path (e.g. a package relative path)
+pub fn git_last_commit_sha_for_path(path: String, cwd: Option<String>) -> Option<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
 
     let mut command = Command::new("git");
     command
-        .arg("commit")
-        .arg("-F")
-        .arg(&file_path.to_str().unwrap())
-        .arg("--no-verify");
+        .arg("log")
+        .arg("-1")
+        .arg("--format=%h")
+        .arg("--")
+        .arg(&path);
 
     command.current_dir(&current_working_dir);
 
@@ -474,29 +1126,28 @@ pub fn git_commit(
 
     let output = command.execute_output().unwrap();
 
-    remove_file(file_path).expect("Commit file not deleted");
+    if !output.status.success() {
+        return None;
+    }
 
-    if output.status.success() {
-        Ok(true)
-    } else {
-        Ok(false)
+    let hash = strip_trailing_newline(&String::from_utf8(output.stdout).unwrap());
+
+    match hash.is_empty() {
+        true => None,
+        false => Some(hash),
     }
 }
 
-/// Given a specific git sha, finds all files that have been modified
-/// since the sha and returns the absolute filepaths.
-pub fn git_all_files_changed_since_sha(sha: String, cwd: Option<String>) -> Vec<String> {
+/// Get the previous commit id
+pub fn git_previous_sha(cwd: Option<String>) -> String {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
     let mut command = Command::new("git");
-    command
-        .arg("--no-pager")
-        .arg("diff")
-        .arg("--name-only")
-        .arg(format!("{}", sha));
+    command.arg("rev-parse").arg("--short").arg("HEAD~1");
+
     command.current_dir(&current_working_dir);
 
     command.stdout(Stdio::piped());
@@ -504,56 +1155,30 @@ pub fn git_all_files_changed_since_sha(sha: String, cwd: Option<String>) -> Vec<
 
     let output = command.execute_output().unwrap();
 
-    if !output.status.success() {
-        return vec![];
-    }
-
-    let output = String::from_utf8(output.stdout).unwrap();
-    let root = Path::new(&current_working_dir);
+    let hash = String::from_utf8(output.stdout).unwrap();
 
-    output
-        .split("\n")
-        .filter(|item| !item.trim().is_empty())
-        .map(|item| root.join(item))
-        .filter(|item| item.exists())
-        .map(|item| item.to_str().unwrap().to_string())
-        .collect::<Vec<String>>()
+    strip_trailing_newline(&hash)
 }
 
-/// Returns commits since a particular git SHA or tag.
-/// If the "since" parameter isn't provided, all commits
-/// from the dawn of man are returned
-pub fn get_commits_since(
-    cwd: Option<String>,
-    since: Option<String>,
-    relative: Option<String>,
-) -> Vec<Commit> {
+/// Get the first commit of the current branch since it left a branch (default `main`), the
+/// oldest commit of `<branch>..HEAD`. Fails when there is no commit in that range.
+pub fn git_first_sha(cwd: Option<String>, branch: Option<String>) -> Result<String, GitError> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    const DELIMITER: &str = r#"#=#"#;
-    const BREAK_LINE: &str = r#"#+#"#;
+    let branch = match branch {
+        Some(branch) => branch,
+        None => String::from("main"),
+    };
 
     let mut command = Command::new("git");
     command
-        .arg("--no-pager")
         .arg("log")
-        .arg(format!(
-            "--format={}%H{}%an{}%ae{}%ad{}%B{}",
-            DELIMITER, DELIMITER, DELIMITER, DELIMITER, DELIMITER, BREAK_LINE
-        ))
-        .arg("--date=rfc2822");
-
-    if let Some(since) = since {
-        command.arg(format!("{}..", since));
-    }
-
-    if let Some(relative) = relative {
-        command.arg("--");
-        command.arg(&relative);
-    }
+        .arg(format!("{}..HEAD", branch))
+        .arg("--reverse")
+        .arg("--format=%h");
 
     command.current_dir(&current_working_dir);
 
@@ -563,43 +1188,34 @@ pub fn get_commits_since(
     let output = command.execute_output().unwrap();
 
     if !output.status.success() {
-        return vec![];
+        return Err(GitError::UnknownRef(branch));
     }
 
     let output = String::from_utf8(output.stdout).unwrap();
 
-    output
-        .split(BREAK_LINE)
-        .filter(|item| !item.trim().is_empty())
-        .map(|item| {
-            let item_trimmed = item.trim();
-            let items = item_trimmed.split(DELIMITER).collect::<Vec<&str>>();
-
-            Commit {
-                hash: items.get(1).unwrap().to_string(),
-                author_name: items.get(2).unwrap().to_string(),
-                author_email: items.get(3).unwrap().to_string(),
-                author_date: items.get(4).unwrap().to_string(),
-                message: items.get(5).unwrap().to_string(),
-            }
-        })
-        .collect::<Vec<Commit>>()
+    match output.lines().find(|line| !line.trim().is_empty()) {
+        Some(sha) => Ok(sha.trim().to_string()),
+        None => Err(GitError::Command(format!("No commits in {}..HEAD", branch))),
+    }
+}
+
+/// Verify if as uncommited changes in the current working directory
+pub fn git_workdir_unclean(cwd: Option<String>) -> bool {
+    git_status(cwd)
+        .map(|entries| entries.len() > 0)
+        .unwrap_or(false)
 }
 
-/// Grabs the full list of all tags available on upstream or local
-pub fn get_remote_or_local_tags(cwd: Option<String>, local: Option<bool>) -> Vec<RemoteTags> {
+/// Get the uncommitted changes of the working tree (`git status --porcelain=v1 -z`), untracked
+/// files included. A file both staged and changed again is reported with its staged status.
+pub fn git_status(cwd: Option<String>) -> Result<Vec<StatusEntry>, GitError> {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
     let mut command = Command::new("git");
-
-    match local {
-        Some(true) => command.arg("show-ref").arg("--tags"),
-        Some(false) => command.arg("ls-remote").arg("--tags").arg("origin"),
-        None => command.arg("ls-remote").arg("--tags").arg("origin"),
-    };
+    command.arg("status").arg("--porcelain=v1").arg("-z");
 
     command.current_dir(&current_working_dir);
 
@@ -609,330 +1225,3539 @@ pub fn get_remote_or_local_tags(cwd: Option<String>, local: Option<bool>) -> Vec
     let output = command.execute_output().unwrap();
 
     if !output.status.success() {
-        return vec![];
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        return Err(GitError::Command(strip_trailing_newline(&stderr)));
     }
 
     let output = String::from_utf8(output.stdout).unwrap();
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+    let mut entries = vec![];
 
-    #[cfg(windows)]
-    const LINE_ENDING: &'static str = "\r\n";
-    #[cfg(not(windows))]
-    const LINE_ENDING: &'static str = "\n";
+    // Each record is `XY <path>`, a rename or copy is followed by a record with its source
+    while let Some(record) = records.next() {
+        if record.len() < 4 {
+            continue;
+        }
 
-    output
-        .trim()
-        .split(LINE_ENDING)
-        .filter(|tags| !tags.trim().is_empty())
-        .map(|tags| {
-            let hash_tags = Regex::new(r"\s+")
-                .unwrap()
-                .split(tags)
-                .collect::<Vec<&str>>();
+        let (code, path) = record.split_at(2);
+        let mut code = code.chars();
+        let index = code.next().unwrap_or(' ');
+        let worktree = code.next().unwrap_or(' ');
+
+        let to_status = |code: char| match code {
+            'A' => Some(FileStatus::Added),
+            'M' => Some(FileStatus::Modified),
+            'D' => Some(FileStatus::Deleted),
+            'R' => Some(FileStatus::Renamed),
+            'C' => Some(FileStatus::Copied),
+            'T' => Some(FileStatus::TypeChanged),
+            _ => None,
+        };
+
+        let (status, staged) = match (index, worktree) {
+            ('?', '?') => (FileStatus::Untracked, false),
+            ('!', '!') => (FileStatus::Ignored, false),
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => (FileStatus::Conflicted, false),
+            (index, worktree) => match (to_status(index), to_status(worktree)) {
+                (Some(status), _) => (status, true),
+                (None, Some(status)) => (status, false),
+                (None, None) => continue,
+            },
+        };
+
+        let original_path = match index {
+            'R' | 'C' => records.next().map(|source| source.to_string()),
+            _ => None,
+        };
+
+        entries.push(StatusEntry {
+            path: path[1..].to_string(),
+            original_path,
+            status,
+            staged,
+        });
+    }
 
-            RemoteTags {
-                hash: hash_tags.get(0).unwrap().to_string(),
-                tag: hash_tags.get(1).unwrap().to_string(),
-            }
-        })
-        .collect::<Vec<RemoteTags>>()
+    Ok(entries)
 }
 
-/// Given an input of the "main" branch name,
-/// returns all the files that have changed since the current branch was created
-pub fn get_all_files_changed_since_branch(
-    package_info: &Vec<PackageInfo>,
-    branch: &String,
-    cwd: Option<String>,
-) -> Vec<String> {
+/// Verify if as uncommited changes inside the package directory
+pub fn package_workdir_unclean(package: &PackageInfo, cwd: Option<String>) -> bool {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let mut all_files = vec![];
+    let mut command = Command::new("git");
+    command
+        .arg("status")
+        .arg("--porcelain")
+        .arg("--")
+        .arg(&package.package_relative_path);
 
-    package_info.iter().for_each(|item| {
-        let files = git_all_files_changed_since_sha(
-            branch.to_string(),
-            Some(current_working_dir.to_string()),
-        );
+    command.current_dir(&current_working_dir);
 
-        let pkg_files = files
-            .iter()
-            .filter(|file| file.starts_with(item.package_path.as_str()))
-            .collect::<Vec<&String>>();
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
 
-        all_files.append(
-            &mut pkg_files
-                .iter()
-                .map(|file| file.to_string())
-                .collect::<Vec<String>>(),
-        );
-    });
+    let output = command.execute_output().unwrap();
 
-    all_files
+    let output = String::from_utf8(output.stdout).unwrap();
+    let result = strip_trailing_newline(&output);
+
+    !result.is_empty()
 }
 
-/// Grabs the last known publish tag info for a package
-pub fn get_last_known_publish_tag_info_for_package(
-    package_info: &PackageInfo,
-    cwd: Option<String>,
-) -> Option<PublishTagInfo> {
+/// Verify if the working tree matches the tree of a tag (tracked files only).
+/// Returns false when there are differences or the tag doesn't exist.
+pub fn verify_tag_tree_clean(tag: String, cwd: Option<String>) -> bool {
     let current_working_dir = match cwd {
         Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let mut remote_tags =
-        get_remote_or_local_tags(Some(current_working_dir.to_string()), Some(false));
-    let mut local_tags =
-        get_remote_or_local_tags(Some(current_working_dir.to_string()), Some(true));
-
-    /*let mut remote_tags = vec![
-        RemoteTags {
-            hash: String::from("ddd1fa69be3e6c6a8b2f18af8f8f5607106188db"),
-            tag: String::from("refs/tags/@b2x/workspace-node@1.0.4")
-        },
-        RemoteTags {
-            hash: String::from("c5353e1f3c9385c35f64e838a0a09dc4bb8f7b07"),
-            tag: String::from("refs/tags/@b2x/workspace-node@1.0.2")
-        }
-    ];
+    let mut command = Command::new("git");
+    command
+        .arg("diff")
+        .arg("--quiet")
+        .arg(&tag)
+        .arg("--")
+        .arg(".");
 
-    let mut local_tags = vec![
-        RemoteTags {
-            hash: String::from("4a16b15bb5cfeca493c79231452e94e56487d6b4"),
-            tag: String::from("refs/tags/@b2x/workspace-node@0.9.9")
-        },
-        RemoteTags {
-            hash: String::from("ee5f8209e6d3b06fbf5712e424652e909a4cb5c2"),
-            tag: String::from("refs/tags/@b2x/workspace-node@1.0.5")
-        }
-    ];*/
+    command.current_dir(&current_working_dir);
 
-    remote_tags.append(&mut local_tags);
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
 
-    let mut options = CollatorOptions::new();
-    options.strength = Some(Strength::Secondary);
-    options.numeric = Some(Numeric::On);
+    let output = command.execute_output().unwrap();
 
-    let collator = Collator::try_new(&Default::default(), options).unwrap();
+    output.status.success()
+}
+
+/// Get the content of a file at a given revision (tag, branch or sha).
+/// Returns None when the revision or the file doesn't exist.
+pub fn git_show_file(rev: String, path: String, cwd: Option<String>) -> Option<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("show").arg(format!("{}:{}", rev, path));
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8(output.stdout).unwrap())
+}
+
+/// Verify if a path (e.g. a package relative path) has no differences between a revision and
+/// the working tree. Returns false when there are differences or the revision doesn't exist.
+pub fn git_path_unchanged_since(rev: String, path: String, cwd: Option<String>) -> bool {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("diff")
+        .arg("--quiet")
+        .arg(&rev)
+        .arg("--")
+        .arg(&path);
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    output.status.success()
+}
+
+/// Get the tags pointing at a revision (tag, branch or sha).
+/// Returns an empty list when there are none or the revision doesn't exist.
+pub fn git_tags_pointing_at(rev: String, cwd: Option<String>) -> Vec<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("tag").arg("--points-at").arg(&rev);
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect::<Vec<String>>()
+}
+
+/// Get the current branch name
+pub fn git_current_branch(cwd: Option<String>) -> Option<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("rev-parse").arg("--abbrev-ref").arg("HEAD");
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    let output = String::from_utf8(output.stdout).unwrap();
+    let result = strip_trailing_newline(&output);
+
+    if result.is_empty() {
+        return None;
+    }
+
+    Some(result)
+}
+
+/// Verify if the current HEAD is already pushed to the remote branch of the current branch.
+/// Returns false if the remote branch doesn't exist. Remote defaults to `origin`.
+pub fn git_is_head_pushed(remote: Option<String>, cwd: Option<String>) -> bool {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let remote = match remote {
+        Some(remote) => remote,
+        None => String::from("origin"),
+    };
+
+    let branch = match git_current_branch(Some(current_working_dir.to_string())) {
+        Some(branch) => branch,
+        None => return false,
+    };
+
+    let rev_parse = |refer: &String| -> Option<String> {
+        let mut command = Command::new("git");
+        command
+            .arg("rev-parse")
+            .arg("--verify")
+            .arg("--quiet")
+            .arg(refer);
+
+        command.current_dir(&current_working_dir);
+
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+
+        let output = command.execute_output().unwrap();
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let output = String::from_utf8(output.stdout).unwrap();
+
+        Some(strip_trailing_newline(&output))
+    };
+
+    let head = rev_parse(&String::from("HEAD"));
+    let remote_head = rev_parse(&format!("{}/{}", remote, branch));
+
+    match (head, remote_head) {
+        (Some(head), Some(remote_head)) => head == remote_head,
+        _ => false,
+    }
+}
+
+/// Get the branch (last) name for a commit
+pub fn git_branch_from_commit(commit: String, cwd: Option<String>) -> Option<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    // git --no-pager branch --no-color --no-column --format "%(refname:lstrip=2)" --contains <commit>
+    let mut command = Command::new("git");
+    command
+        .arg("--no-pager")
+        .arg("branch")
+        .arg("--no-color")
+        .arg("--no-column")
+        .arg("--format")
+        .arg(r#""%(refname:lstrip=2)""#)
+        .arg("--contains")
+        .arg(&commit);
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    let output = String::from_utf8(output.stdout).unwrap();
+    let result = strip_trailing_newline(&output);
+
+    if result.is_empty() {
+        return None;
+    }
+
+    Some(result)
+}
+
+/// Lists the branches of the repository in the given scope. Remote branches are listed by
+/// their remote tracking refs (fetched state) and the remote `HEAD` symbolic refs are skipped.
+pub fn get_branches(cwd: Option<String>, scope: BranchScope) -> Vec<BranchInfo> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let refs = match scope {
+        BranchScope::Local => vec!["refs/heads"],
+        BranchScope::Remote => vec!["refs/remotes"],
+        BranchScope::All => vec!["refs/heads", "refs/remotes"],
+    };
+
+    // Fields separated by the unit separator, no quotes around the format
+    let mut command = Command::new("git");
+    command
+        .arg("for-each-ref")
+        .arg("--format=%(refname)%1f%(objectname)%1f%(upstream:short)%1f%(HEAD)%1f%(committerdate:rfc2822)")
+        .args(refs);
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split('\u{1f}').collect::<Vec<&str>>();
+
+            if fields.len() != 5 {
+                return None;
+            }
+
+            let name = match fields[0].strip_prefix("refs/heads/") {
+                Some(name) => name,
+                None => fields[0]
+                    .strip_prefix("refs/remotes/")?
+                    .split_once('/')
+                    .map(|(_remote, name)| name)?,
+            };
+
+            if name == "HEAD" {
+                return None;
+            }
+
+            Some(BranchInfo {
+                name: name.to_string(),
+                head_sha: fields[1].to_string(),
+                upstream: Some(fields[2].to_string()).filter(|upstream| !upstream.is_empty()),
+                is_current: fields[3] == "*",
+                last_commit_date: fields[4].to_string(),
+            })
+        })
+        .collect::<Vec<BranchInfo>>()
+}
+
+/// Verify if a branch exists in the given scope, the name without any refs prefix
+pub fn branch_exists(name: String, scope: BranchScope, cwd: Option<String>) -> bool {
+    get_branches(cwd, scope)
+        .iter()
+        .any(|branch| branch.name == name)
+}
+
+/// Builds the signing arguments of `git commit` and `git tag`. A signing key always signs with
+/// that key, while `sign` alone uses the key configured in the repository (`user.signingkey`).
+fn git_signing_args(
+    subcommand: &str,
+    sign: Option<bool>,
+    signing_key: Option<String>,
+) -> Vec<String> {
+    match (subcommand, signing_key, sign.unwrap_or(false)) {
+        ("tag", Some(key), _) => vec![String::from("--local-user"), key],
+        ("tag", None, true) => vec![String::from("-s")],
+        (_, Some(key), _) => vec![format!("-S{}", key)],
+        (_, None, true) => vec![String::from("-S")],
+        (_, None, false) => vec![],
+    }
+}
+
+/// Tags the current commit with a message, optionally signed (see `git_signing_args`)
+pub fn git_tag(
+    tag: String,
+    message: Option<String>,
+    sign: Option<bool>,
+    signing_key: Option<String>,
+    cwd: Option<String>,
+) -> Result<bool, std::io::Error> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let default_message = &tag;
+    let msg = message.or(Some(default_message.to_string())).unwrap();
+
+    let mut command = Command::new("git");
+    command
+        .arg("tag")
+        .arg("-a")
+        .args(git_signing_args("tag", sign, signing_key))
+        .arg(&tag)
+        .arg("-m")
+        .arg(&msg);
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Pushes all changes in the monorepo without verification and follow tags, within the network
+/// command limits (see `CommandLimits::network`).
+pub fn git_push(cwd: Option<String>, follow_tags: Option<bool>) -> Result<bool, std::io::Error> {
+    let timeout = CommandLimits::network(&cwd).timeout;
+
+    git_push_with_timeout(cwd, follow_tags, timeout)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))
+}
+
+/// Pushes all changes in the monorepo without verification and follow tags, failing with
+/// `GitError::Timeout` when the push runs longer than the timeout.
+pub fn git_push_with_timeout(
+    cwd: Option<String>,
+    follow_tags: Option<bool>,
+    timeout: Option<Duration>,
+) -> Result<bool, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("push");
+
+    if follow_tags.unwrap_or(false) {
+        command.arg("--follow-tags");
+    }
+
+    command.arg("--no-verify");
+    command.current_dir(&current_working_dir);
+
+    let output = execute_with_limits(
+        &mut command,
+        &CommandLimits {
+            timeout,
+            ..CommandLimits::network(&Some(current_working_dir.to_string()))
+        },
+    )?;
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Push the current HEAD to a branch of the remote (default `origin`).
+/// Unlike `git_push`, it doesn't need a checked out branch, so it works on a detached HEAD.
+pub fn git_push_head_to_branch(
+    branch: String,
+    remote: Option<String>,
+    cwd: Option<String>,
+    follow_tags: Option<bool>,
+) -> Result<bool, std::io::Error> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let remote = match remote {
+        Some(remote) => remote,
+        None => String::from("origin"),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("push");
+
+    if follow_tags.unwrap_or(false) {
+        command.arg("--follow-tags");
+    }
+
+    command
+        .arg("--no-verify")
+        .arg(remote)
+        .arg(format!("HEAD:refs/heads/{}", branch));
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Create a worktree of the current HEAD (detached) at the given path.
+pub fn git_worktree_add(path: &String, cwd: Option<String>) -> Result<bool, std::io::Error> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("worktree")
+        .arg("add")
+        .arg("--detach")
+        .arg(path)
+        .arg("HEAD");
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Create a worktree at the given path with an existing branch checked out. Fails with the git
+/// stderr, e.g. when the branch is already checked out in another worktree.
+pub fn git_worktree_add_branch(
+    path: &String,
+    branch: &String,
+    cwd: Option<String>,
+) -> Result<bool, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("worktree").arg("add").arg(path).arg(branch);
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        return Err(GitError::Command(strip_trailing_newline(&stderr)));
+    }
+
+    Ok(true)
+}
+
+/// List the worktrees of the repository (`git worktree list --porcelain`), the main one first.
+pub fn git_worktree_list(cwd: Option<String>) -> Result<Vec<Worktree>, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("worktree").arg("list").arg("--porcelain");
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        return Err(GitError::Command(strip_trailing_newline(&stderr)));
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    // Each worktree is a block of `<attribute> <value>` lines, separated by an empty line
+    let worktrees = output
+        .split("\n\n")
+        .filter_map(|block| {
+            let attribute = |name: &str| {
+                block.lines().find_map(|line| {
+                    line.strip_prefix(name)
+                        .and_then(|value| value.strip_prefix(' '))
+                        .map(|value| value.to_string())
+                })
+            };
+
+            Some(Worktree {
+                path: attribute("worktree")?,
+                branch: attribute("branch")
+                    .map(|branch| branch.trim_start_matches("refs/heads/").to_string()),
+                sha: attribute("HEAD").unwrap_or_default(),
+            })
+        })
+        .collect::<Vec<Worktree>>();
+
+    Ok(worktrees)
+}
+
+/// Remove a worktree, discarding any uncommitted change in it.
+pub fn git_worktree_remove(path: &String, cwd: Option<String>) -> Result<bool, std::io::Error> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("worktree")
+        .arg("remove")
+        .arg("--force")
+        .arg(path);
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+// Commit all changes in the monorepo, optionally signed (see `git_signing_args`)
+pub fn git_commit(
+    mut message: String,
+    body: Option<String>,
+    footer: Option<String>,
+    sign: Option<bool>,
+    signing_key: Option<String>,
+    cwd: Option<String>,
+) -> Result<bool, std::io::Error> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    if body.is_some() {
+        message.push_str("\n\n");
+        message.push_str(body.unwrap().as_str());
+    }
+
+    if footer.is_some() {
+        message.push_str("\n\n");
+        message.push_str(footer.unwrap().as_str());
+    }
+
+    // Unique per commit, concurrent commits (e.g. in other repositories) share the temp dir
+    let rand_string: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+
+    let temp_dir = temp_dir();
+    let temp_file_path = temp_dir.join(format!("commit_message-{}.txt", rand_string));
+
+    let mut file = File::create(&temp_file_path).unwrap();
+    file.write_all(message.as_bytes()).unwrap();
+
+    let file_path = temp_file_path.as_path();
+
+    let mut command = Command::new("git");
+    command
+        .arg("commit")
+        .arg("-F")
+        .arg(&file_path.to_str().unwrap())
+        .args(git_signing_args("commit", sign, signing_key))
+        .arg("--no-verify");
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    remove_file(file_path).expect("Commit file not deleted");
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Stages and commits exactly the given paths. Every path must exist and resolve (symlinks
+/// included) inside the repository. If the staged set contains anything else the paths are
+/// unstaged again and nothing is committed.
+pub fn commit_paths(
+    paths: Vec<String>,
+    message: String,
+    cwd: Option<String>,
+) -> Result<CommitInfo, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+    let canonical_root = canonicalize(root)?;
+
+    let mut relative_paths = vec![];
+
+    for path in paths.iter() {
+        let full_path = Path::new(root).join(path);
+
+        if !full_path.exists() {
+            return Err(WorkspaceError::FileNotFound(path.to_string()));
+        }
+
+        let canonical_path = canonicalize(&full_path)?;
+
+        match to_workspace_relative(&canonical_path, &canonical_root) {
+            Some(relative_path) if !relative_path.is_empty() => relative_paths.push(relative_path),
+            _ => return Err(WorkspaceError::PathOutsideRepository(path.to_string())),
+        }
+    }
+
+    let run_git = |args: Vec<&str>| -> Result<String, WorkspaceError> {
+        let mut command = Command::new("git");
+        command.current_dir(root).args(args);
+
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+
+        let output = command.execute_output()?;
+
+        match output.status.success() {
+            true => Ok(String::from_utf8(output.stdout).unwrap()),
+            false => Err(WorkspaceError::Git(strip_trailing_newline(
+                &String::from_utf8(output.stderr).unwrap(),
+            ))),
+        }
+    };
+
+    let path_args = relative_paths.iter().map(|path| path.as_str());
+
+    run_git([vec!["add", "--"], path_args.clone().collect()].concat())?;
+
+    let mut files = run_git(vec!["diff", "--cached", "--name-only"])?
+        .lines()
+        .filter(|file| !file.trim().is_empty())
+        .map(|file| file.to_string())
+        .collect::<Vec<String>>();
+    files.sort();
+
+    let unexpected = files
+        .iter()
+        .filter(|file| {
+            !relative_paths
+                .iter()
+                .any(|path| is_within(Path::new(path), Path::new(file)))
+        })
+        .map(|file| file.to_string())
+        .collect::<Vec<String>>();
+
+    if unexpected.len() != 0 {
+        run_git([vec!["reset", "-q", "--"], path_args.collect()].concat())?;
+
+        return Err(WorkspaceError::UnexpectedStagedFiles(unexpected));
+    }
+
+    let committed = git_commit(message, None, None, None, None, Some(root.to_string()))?;
+
+    if !committed {
+        return Err(WorkspaceError::Git(format!(
+            "unable to commit {}",
+            relative_paths.join(", ")
+        )));
+    }
+
+    let sha = strip_trailing_newline(&run_git(vec!["rev-parse", "HEAD"])?);
+
+    Ok(CommitInfo { sha, files })
+}
+
+/// Remove untracked files and directories from the working tree.
+/// When force is true, ignored files are also removed.
+pub fn git_clean(cwd: Option<String>, force: bool) -> Result<bool, std::io::Error> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("clean").arg("-fd");
+
+    if force {
+        command.arg("-x");
+    }
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Reset the working tree and index to a sha, defaults to HEAD
+pub fn git_reset_hard(sha: Option<String>, cwd: Option<String>) -> Result<bool, std::io::Error> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("reset")
+        .arg("--hard")
+        .arg(sha.unwrap_or(String::from("HEAD")));
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Cherry pick a commit onto the current branch, e.g. to backport a release to a maintenance
+/// branch. Conflicts fail with the conflicting files and leave the cherry pick in progress,
+/// to be resolved or aborted (`git cherry-pick --abort`) by the caller.
+pub fn git_cherry_pick(sha: &String, cwd: Option<String>) -> Result<bool, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("cherry-pick").arg(sha);
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if output.status.success() {
+        return Ok(true);
+    }
+
+    let mut unmerged = Command::new("git");
+    unmerged
+        .arg("diff")
+        .arg("--name-only")
+        .arg("--diff-filter=U")
+        .current_dir(&current_working_dir);
+
+    unmerged.stdout(Stdio::piped());
+    unmerged.stderr(Stdio::piped());
+
+    let unmerged_output = unmerged.execute_output().unwrap();
+    let conflicts = String::from_utf8(unmerged_output.stdout)
+        .unwrap()
+        .lines()
+        .map(|file| file.to_string())
+        .filter(|file| !file.is_empty())
+        .collect::<Vec<String>>();
+
+    match conflicts.is_empty() {
+        true => {
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            Err(GitError::Command(strip_trailing_newline(&stderr)))
+        }
+        false => Err(GitError::Conflict(conflicts)),
+    }
+}
+
+/// Given a specific git sha, finds all files that have been modified
+/// since the sha and returns the absolute filepaths.
+pub fn git_all_files_changed_since_sha(sha: String, cwd: Option<String>) -> Vec<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    // Tags (e.g. `@scope/package-a@1.0.0`) are resolved to their commit, so the diff never
+    // takes the ref as a path. Anything else (e.g. ranges) is given to git as is.
+    let refer =
+        git_resolve_commit(sha.to_string(), Some(current_working_dir.to_string())).unwrap_or(sha);
+
+    let mut command = Command::new("git");
+    command
+        .arg("--no-pager")
+        .arg("diff")
+        .arg("--name-only")
+        .arg(refer)
+        .arg("--");
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+    let files = output
+        .split("\n")
+        .map(|item| item.to_string())
+        .collect::<Vec<String>>();
+
+    normalize_changed_files(files, &current_working_dir)
+        .into_iter()
+        .filter(|item| Path::new(item).exists())
+        .collect::<Vec<String>>()
+}
+
+/// Get the files changed by a single commit as absolute filepaths, deleted files included.
+pub fn git_files_changed_in_commit(sha: String, cwd: Option<String>) -> Vec<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("--no-pager")
+        .arg("show")
+        .arg("--name-only")
+        .arg("--format=")
+        .arg(&sha)
+        .arg("--");
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+    let files = output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>();
+
+    normalize_changed_files(files, &current_working_dir)
+}
+
+/// Normalizes a list of changed files: separators are converted to `/`,
+/// relative paths are joined to the root (absolute paths are kept), `.`
+/// segments are dropped and the result is deduped and sorted.
+pub fn normalize_changed_files(files: Vec<String>, root: &str) -> Vec<String> {
+    let root = Path::new(root);
+
+    let mut files = files
+        .iter()
+        .map(|item| item.trim().replace("\\", "/"))
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let path = Path::new(&item);
+            let path = match path.is_absolute() {
+                true => path.to_path_buf(),
+                false => root.join(path),
+            };
+
+            path.components()
+                .filter(|component| component != &Component::CurDir)
+                .collect::<PathBuf>()
+                .to_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect::<Vec<String>>();
+
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Returns commits since a particular git SHA or tag.
+/// If the "since" parameter isn't provided, all commits
+/// from the dawn of man are returned.
+/// Only the commits of the options history mode are collected. `MergesOnly` takes the merge
+/// commits of the first parent history, as merges are usually hidden from a path-limited log
+/// since their tree matches the merged branch.
+pub fn get_commits_since(
+    cwd: Option<String>,
+    since: Option<String>,
+    relative: Option<String>,
+    options: &CommitsSinceOptions,
+) -> Vec<Commit> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let range = since.map(|since| format!("{}..", since));
+    let exclude_authors = options.exclude_authors.to_owned().unwrap_or_default();
+
+    git_log_commits(
+        &current_working_dir,
+        range,
+        relative,
+        options.author.to_owned(),
+        options.history_mode,
+    )
+    .into_iter()
+    .filter(|commit| {
+        !exclude_authors.iter().any(|excluded| {
+            commit.author_name.contains(excluded) || commit.author_email.contains(excluded)
+        })
+    })
+    .collect::<Vec<Commit>>()
+}
+
+/// Returns commits reachable from "to" but not from "from" (`from..to`).
+/// If the "from" parameter isn't provided, all commits up to "to" are returned
+pub fn get_commits_between(
+    cwd: Option<String>,
+    from: Option<String>,
+    to: String,
+    relative: Option<String>,
+) -> Vec<Commit> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let range = match from {
+        Some(from) => format!("{}..{}", from, to),
+        None => to,
+    };
+
+    git_log_commits(&current_working_dir, Some(range), relative, None, None)
+}
+
+/// Count the commits since a particular git SHA or tag (all commits if none),
+/// optionally scoped to a relative path.
+pub fn get_commit_count_since(
+    cwd: Option<String>,
+    since: Option<String>,
+    relative: Option<String>,
+) -> usize {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command.arg("rev-list").arg("--count");
+
+    match since {
+        Some(since) => command.arg(format!("{}..HEAD", since)),
+        None => command.arg("HEAD"),
+    };
+
+    if let Some(relative) = relative {
+        command.arg("--");
+        command.arg(&relative);
+    }
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return 0;
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    strip_trailing_newline(&output)
+        .parse::<usize>()
+        .unwrap_or(0)
+}
+
+/// Get the lines inserted and deleted per file between a sha, branch or tag and HEAD.
+/// Binary files are reported with `None` counts.
+pub fn get_diff_stat(sha: &String, cwd: Option<String>) -> Result<Vec<FileStat>, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("--no-pager")
+        .arg("diff")
+        .arg("--numstat")
+        .arg(sha)
+        .arg("HEAD");
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return Err(GitError::UnknownRef(sha.to_string()));
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    let stats = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+
+            // Binary files have `-` instead of the line counts
+            let insertions = fields.next()?.parse::<u32>().ok();
+            let deletions = fields.next()?.parse::<u32>().ok();
+            let path = fields.next()?.to_string();
+
+            Some(FileStat {
+                path,
+                insertions,
+                deletions,
+            })
+        })
+        .collect::<Vec<FileStat>>();
+
+    Ok(stats)
+}
+
+/// Get the commit graph of HEAD since a sha, branch or tag (the whole history by default),
+/// newest first, to draw the commit DAG.
+pub fn get_commit_graph(
+    since: Option<String>,
+    cwd: Option<String>,
+) -> Result<Vec<GraphNode>, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("log")
+        .arg("--decorate=short")
+        .arg("--format=%H%x1f%P%x1f%D%x1f%s");
+
+    match since {
+        Some(since) => command.arg(format!("{}..HEAD", since)),
+        None => command.arg("HEAD"),
+    };
+
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        return Err(GitError::Command(strip_trailing_newline(&stderr)));
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    let nodes = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\u{1f}');
+
+            let sha = fields.next()?.to_string();
+            let parents = fields
+                .next()?
+                .split_whitespace()
+                .map(|parent| parent.to_string())
+                .collect::<Vec<String>>();
+            let refs = fields
+                .next()?
+                .split(", ")
+                .filter(|refer| !refer.trim().is_empty())
+                .map(|refer| refer.trim().to_string())
+                .collect::<Vec<String>>();
+            let subject = fields.next().unwrap_or_default().to_string();
+
+            Some(GraphNode {
+                sha,
+                parents,
+                refs,
+                subject,
+            })
+        })
+        .collect::<Vec<GraphNode>>();
+
+    Ok(nodes)
+}
+
+/// Runs git log for a revision range, optionally scoped to a relative path.
+fn git_log_commits(
+    cwd: &String,
+    range: Option<String>,
+    relative: Option<String>,
+    author: Option<String>,
+    history_mode: Option<HistoryMode>,
+) -> Vec<Commit> {
+    // Unit and record separators can't show up in commit text, unlike printable delimiters
+    const DELIMITER: char = '\u{1f}';
+    const BREAK_LINE: char = '\u{1e}';
+
+    let mut command = Command::new("git");
+    command
+        .arg("--no-pager")
+        .arg("log")
+        .arg("--format=%H%x1f%an%x1f%ae%x1f%ad%x1f%s%x1f%b%x1f%(trailers:only,unfold)%x1f%B%x1e")
+        .arg("--date=rfc2822");
+
+    if let Some(author) = author {
+        command.arg("--author").arg(author);
+    }
+
+    match history_mode.unwrap_or(HistoryMode::AllCommits) {
+        HistoryMode::AllCommits => {}
+        HistoryMode::FirstParentOnly => {
+            command.arg("--first-parent");
+        }
+        HistoryMode::MergesOnly => {
+            command.arg("--first-parent").arg("--merges");
+        }
+    }
+
+    if let Some(range) = range {
+        command.arg(range);
+    }
+
+    if let Some(relative) = relative {
+        command.arg("--");
+        command.arg(&relative);
+    }
+
+    command.current_dir(cwd);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    output
+        .split(BREAK_LINE)
+        .filter(|item| !item.trim().is_empty())
+        .filter_map(|item| {
+            let item_trimmed = item.trim();
+            let items = item_trimmed.splitn(8, DELIMITER).collect::<Vec<&str>>();
+
+            // Malformed records are skipped instead of failing the whole log
+            match items.as_slice() {
+                [hash, author_name, author_email, author_date, subject, body, trailers, message] => {
+                    Some(Commit {
+                        hash: hash.to_string(),
+                        author_name: author_name.to_string(),
+                        author_email: author_email.to_string(),
+                        author_date: author_date.to_string(),
+                        message: message.to_string(),
+                        subject: subject.to_string(),
+                        body: body.trim_end().to_string(),
+                        trailers: parse_trailers(trailers),
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect::<Vec<Commit>>()
+}
+
+/// Parses the `Key: value` lines of `%(trailers:only,unfold)`
+fn parse_trailers(trailers: &str) -> Vec<CommitTrailer> {
+    trailers
+        .lines()
+        .filter_map(|line| match line.split_once(':') {
+            Some((key, value)) if !key.trim().is_empty() => Some(CommitTrailer {
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+            }),
+            _ => None,
+        })
+        .collect::<Vec<CommitTrailer>>()
+}
+
+/// Grabs the full list of all tags available on upstream or local, within the network command
+/// limits (see `CommandLimits::network`). Fails with `GitError::Timeout` or
+/// `GitError::OutputTooLarge` when the limits are breached.
+pub fn get_remote_or_local_tags(
+    cwd: Option<String>,
+    local: Option<bool>,
+) -> Result<Vec<RemoteTags>, GitError> {
+    let timeout = CommandLimits::network(&cwd).timeout;
+
+    get_remote_or_local_tags_with_timeout(cwd, local, timeout)
+}
+
+/// Grabs the full list of all tags available on upstream or local, failing with
+/// `GitError::Timeout` when listing the upstream tags (`ls-remote`) runs longer than the timeout.
+pub fn get_remote_or_local_tags_with_timeout(
+    cwd: Option<String>,
+    local: Option<bool>,
+    timeout: Option<Duration>,
+) -> Result<Vec<RemoteTags>, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+
+    match local {
+        Some(true) => command.arg("show-ref").arg("--tags"),
+        Some(false) => command.arg("ls-remote").arg("--tags").arg("origin"),
+        None => command.arg("ls-remote").arg("--tags").arg("origin"),
+    };
+
+    command.current_dir(&current_working_dir);
+
+    let output = execute_with_limits(
+        &mut command,
+        &CommandLimits {
+            timeout,
+            ..CommandLimits::network(&Some(current_working_dir.to_string()))
+        },
+    )?;
+
+    if !output.status.success() {
+        return Ok(vec![]);
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    #[cfg(windows)]
+    const LINE_ENDING: &'static str = "\r\n";
+    #[cfg(not(windows))]
+    const LINE_ENDING: &'static str = "\n";
+
+    let tags = output
+        .trim()
+        .split(LINE_ENDING)
+        .filter(|tags| !tags.trim().is_empty())
+        .map(|tags| {
+            let hash_tags = Regex::new(r"\s+")
+                .unwrap()
+                .split(tags)
+                .collect::<Vec<&str>>();
+
+            RemoteTags {
+                hash: hash_tags.get(0).unwrap().to_string(),
+                tag: hash_tags.get(1).unwrap().to_string(),
+            }
+        })
+        .collect::<Vec<RemoteTags>>();
+
+    Ok(tags)
+}
+
+/// Deduplicates tags by name, keeping the first occurrence with a resolved hash.
+/// Tags are compared without the `refs/tags/` prefix and keep their original order.
+pub fn dedupe_tags(tags: Vec<RemoteTags>) -> Vec<RemoteTags> {
+    let mut unique_tags: Vec<RemoteTags> = vec![];
+
+    for item in tags {
+        let name = item.tag.replace("refs/tags/", "");
+
+        match unique_tags
+            .iter_mut()
+            .find(|unique| unique.tag.replace("refs/tags/", "") == name)
+        {
+            Some(unique) => {
+                if unique.hash.trim().is_empty() && !item.hash.trim().is_empty() {
+                    *unique = item;
+                }
+            }
+            None => unique_tags.push(item),
+        }
+    }
+
+    unique_tags
+}
+
+/// Get all the tags of a package, remote and local, deduplicated by tag name
+pub fn get_package_tags(
+    package_info: &PackageInfo,
+    cwd: Option<String>,
+) -> Result<Vec<RemoteTags>, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut remote_tags =
+        get_remote_or_local_tags(Some(current_working_dir.to_string()), Some(false))?;
+    let mut local_tags =
+        get_remote_or_local_tags(Some(current_working_dir.to_string()), Some(true))?;
+
+    remote_tags.append(&mut local_tags);
+
+    let tags = dedupe_tags(remote_tags)
+        .into_iter()
+        .filter(|item| {
+            let tag = item.tag.replace("refs/tags/", "");
+
+            match split_publish_tag(&tag) {
+                Some((name, _)) => name == package_info.name,
+                None => false,
+            }
+        })
+        .collect::<Vec<RemoteTags>>();
+
+    Ok(tags)
+}
+
+/// Given an input of the "main" branch name,
+/// returns all the files that have changed since the current branch was created
+pub fn get_all_files_changed_since_branch(
+    package_info: &Vec<PackageInfo>,
+    branch: &String,
+    cwd: Option<String>,
+) -> Vec<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut all_files = vec![];
+
+    package_info.iter().for_each(|item| {
+        let files = git_all_files_changed_since_sha(
+            branch.to_string(),
+            Some(current_working_dir.to_string()),
+        );
+
+        let pkg_files = files
+            .iter()
+            .filter(|file| is_within(Path::new(&item.package_path), Path::new(file)))
+            .collect::<Vec<&String>>();
+
+        all_files.append(
+            &mut pkg_files
+                .iter()
+                .map(|file| file.to_string())
+                .collect::<Vec<String>>(),
+        );
+    });
+
+    all_files
+}
+
+/// Get the files changed in a package since a particular git SHA, branch or tag.
+/// Path filtering is done by git using the package relative path.
+pub fn get_changed_files_for_package(
+    package_info: &PackageInfo,
+    since: String,
+    cwd: Option<String>,
+) -> Vec<String> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("--no-pager")
+        .arg("diff")
+        .arg("--name-only")
+        .arg(format!("{}..HEAD", since))
+        .arg("--")
+        .arg(&package_info.package_relative_path);
+    command.current_dir(&current_working_dir);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+    let root = Path::new(&current_working_dir);
+
+    output
+        .split("\n")
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| root.join(item))
+        .filter(|item| item.exists())
+        .map(|item| item.to_str().unwrap().to_string())
+        .collect::<Vec<String>>()
+}
+
+/// Builds the publish tag of a package version with the tag format, `DEFAULT_TAG_FORMAT` when
+/// none is given.
+pub fn format_publish_tag(tag_format: &Option<String>, name: &str, version: &str) -> String {
+    tag_format
+        .as_deref()
+        .unwrap_or(DEFAULT_TAG_FORMAT)
+        .replace("{name}", name)
+        .replace("{version}", version)
+}
+
+/// Splits a tag format into the literals around its placeholders: before `{name}`, between
+/// `{name}` and `{version}` (never empty) and after `{version}`.
+fn split_tag_format(tag_format: &str) -> Option<(&str, &str, &str)> {
+    let (prefix, rest) = tag_format.split_once("{name}")?;
+    let (separator, suffix) = rest.split_once("{version}")?;
+
+    match separator.is_empty() || suffix.contains("{name}") || suffix.contains("{version}") {
+        true => None,
+        false => Some((prefix, separator, suffix)),
+    }
+}
+
+/// Checks that a tag format has a `{name}` then a `{version}` placeholder, apart from each other.
+pub fn is_valid_tag_format(tag_format: &str) -> bool {
+    split_tag_format(tag_format).is_some()
+}
+
+/// Rewrites the tags following a custom tag format (see `format_publish_tag`) to the default
+/// `<name>@<version>` form, so they're read as the publish tags of their package. The other
+/// tags are left as they are.
+pub fn normalize_publish_tags(
+    tags: Vec<RemoteTags>,
+    tag_format: &Option<String>,
+) -> Vec<RemoteTags> {
+    let (prefix, separator, suffix) = match tag_format.as_deref().and_then(split_tag_format) {
+        Some(parts) if tag_format.as_deref() != Some(DEFAULT_TAG_FORMAT) => parts,
+        _ => return tags,
+    };
+
+    tags.into_iter()
+        .map(|item| {
+            let tag = item.tag.replace("refs/tags/", "");
+            let (tag, peeled) = match tag.strip_suffix("^{}") {
+                Some(tag) => (tag, "^{}"),
+                None => (tag.as_str(), ""),
+            };
+
+            let publish_tag = tag
+                .strip_prefix(prefix)
+                .and_then(|tag| tag.strip_suffix(suffix))
+                .and_then(|tag| tag.rsplit_once(separator))
+                .filter(|(name, version)| !name.is_empty() && !version.is_empty());
+
+            match publish_tag {
+                Some((name, version)) => RemoteTags {
+                    hash: item.hash.to_string(),
+                    tag: format!("refs/tags/{}@{}{}", name, version, peeled),
+                },
+                None => item,
+            }
+        })
+        .collect::<Vec<RemoteTags>>()
+}
+
+/// Splits a publish tag (`<name>@<version>`) into its name and version components.
+/// The peeled suffix (`^{}`) of annotated tags is ignored.
+pub(crate) fn split_publish_tag(tag: &str) -> Option<(&str, &str)> {
+    tag.trim_end_matches("^{}")
+        .rsplit_once('@')
+        .filter(|(name, version)| !name.is_empty() && !version.is_empty())
+}
+
+/// Get the publish tags of the project, upstream ones first and then the local ones.
+pub fn get_publish_tags(cwd: Option<String>) -> Result<Vec<RemoteTags>, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let mut tags = get_remote_or_local_tags(Some(current_working_dir.to_string()), Some(false))?;
+    tags.append(&mut get_remote_or_local_tags(
+        Some(current_working_dir.to_string()),
+        Some(true),
+    )?);
+
+    Ok(tags)
+}
+
+/// Get the highest version among the publish tags (local and upstream) of a package.
+/// Tags whose version isn't valid semver are ignored.
+pub fn get_latest_publish_tag_version(
+    package_name: &String,
+    cwd: Option<String>,
+) -> Result<Option<String>, GitError> {
+    Ok(get_latest_publish_tag_version_in(
+        &get_publish_tags(cwd)?,
+        package_name,
+    ))
+}
+
+/// Get the highest version of a package among the given publish tags (see `get_publish_tags`),
+/// so several packages can be looked up without listing the tags again.
+pub fn get_latest_publish_tag_version_in(
+    tags: &[RemoteTags],
+    package_name: &String,
+) -> Option<String> {
+    tags.iter()
+        .filter_map(|item| {
+            let tag = item.tag.replace("refs/tags/", "");
+            let (name, version) = split_publish_tag(&tag)?;
+
+            match name == package_name {
+                true => semver::Version::parse(version).ok(),
+                false => None,
+            }
+        })
+        .max()
+        .map(|version| version.to_string())
+}
+
+/// Get the highest version among the publish tags (local and upstream) of a package that is
+/// lower than the given version, the previous release of that version.
+/// Tags whose version isn't valid semver are ignored, tags are read with the tag format
+/// (see `format_publish_tag`) and filtered with the prerelease policy.
+pub fn get_previous_publish_tag_version(
+    package_name: &String,
+    version: &String,
+    cwd: Option<String>,
+    tag_format: &Option<String>,
+    prerelease_policy: Option<PrereleasePolicy>,
+) -> Result<Option<String>, GitError> {
+    Ok(get_previous_publish_tag_version_in(
+        &normalize_publish_tags(get_publish_tags(cwd)?, tag_format),
+        package_name,
+        version,
+        prerelease_policy,
+    ))
+}
+
+/// Get the previous release of a version the same way as `get_previous_publish_tag_version`,
+/// among the given publish tags.
+pub fn get_previous_publish_tag_version_in(
+    tags: &[RemoteTags],
+    package_name: &String,
+    version: &String,
+    prerelease_policy: Option<PrereleasePolicy>,
+) -> Option<String> {
+    let version = semver::Version::parse(version).ok()?;
+
+    filter_prerelease_tags(tags.to_vec(), package_name, prerelease_policy)
+        .iter()
+        .filter_map(|item| {
+            let tag = item.tag.replace("refs/tags/", "");
+            let (name, tag_version) = split_publish_tag(&tag)?;
+
+            match name == package_name {
+                true => semver::Version::parse(tag_version).ok(),
+                false => None,
+            }
+        })
+        .filter(|tag_version| tag_version < &version)
+        .max()
+        .map(|tag_version| tag_version.to_string())
+}
+
+/// Get the next prerelease number of a version on a prerelease channel, one more than the
+/// highest `<version>-<prerelease_id>.<n>` publish tag of the package (local and upstream).
+/// Starts at 1 when the version has no such tag. Tags are read with the tag format (see
+/// `format_publish_tag`).
+pub fn get_next_prerelease_number(
+    package_name: &String,
+    version: &String,
+    prerelease_id: &String,
+    cwd: Option<String>,
+    tag_format: &Option<String>,
+) -> Result<u64, GitError> {
+    let prefix = format!("{}-{}.", version, prerelease_id);
+
+    let number = normalize_publish_tags(get_publish_tags(cwd)?, tag_format)
+        .iter()
+        .filter_map(|item| {
+            let tag = item.tag.replace("refs/tags/", "");
+            let (name, tag_version) = split_publish_tag(&tag)?;
+
+            match name == package_name {
+                true => tag_version.strip_prefix(&prefix)?.parse::<u64>().ok(),
+                false => None,
+            }
+        })
+        .max()
+        .map_or(1, |number| number + 1);
+
+    Ok(number)
+}
+
+/// Grabs the last known publish tag info for a package, of the default tag format, see
+/// `get_last_known_publish_tag_info_for_package_with_policy` for another format or policy
+pub fn get_last_known_publish_tag_info_for_package(
+    package_info: &PackageInfo,
+    cwd: Option<String>,
+) -> Result<Option<PublishTagInfo>, GitError> {
+    get_last_known_publish_tag_info_for_package_with_policy(package_info, cwd, None, &None)
+}
+
+/// Grabs the last known publish tag info for a package, only considering the tags allowed by
+/// the prerelease policy (default `IncludePrerelease`). Tags are read with the tag format (see
+/// `format_publish_tag`), the returned tag is in the `<name>@<version>` form.
+pub fn get_last_known_publish_tag_info_for_package_with_policy(
+    package_info: &PackageInfo,
+    cwd: Option<String>,
+    prerelease_policy: Option<PrereleasePolicy>,
+    tag_format: &Option<String>,
+) -> Result<Option<PublishTagInfo>, GitError> {
+    let current_working_dir = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let tags = normalize_publish_tags(
+        get_publish_tags(Some(current_working_dir.to_string()))?,
+        tag_format,
+    );
+
+    Ok(get_last_known_publish_tag_info_in(
+        &tags,
+        package_info,
+        prerelease_policy,
+    ))
+}
+
+/// Drops the prerelease tags of a package the prerelease policy (default `IncludePrerelease`)
+/// doesn't allow. Tags of other packages are left as they are.
+fn filter_prerelease_tags(
+    mut tags: Vec<RemoteTags>,
+    package_name: &str,
+    prerelease_policy: Option<PrereleasePolicy>,
+) -> Vec<RemoteTags> {
+    let is_package_tag = |item: &RemoteTags, prerelease: bool| {
+        let tag = item.tag.replace("refs/tags/", "");
+
+        match split_publish_tag(&tag) {
+            Some((name, version)) => {
+                name == package_name
+                    && version.split('+').next().unwrap_or(version).contains('-') == prerelease
+            }
+            None => false,
+        }
+    };
+    let skip_prerelease = match prerelease_policy.unwrap_or(PrereleasePolicy::IncludePrerelease) {
+        PrereleasePolicy::IncludePrerelease => false,
+        PrereleasePolicy::StableOnly => true,
+        PrereleasePolicy::PreferStable => tags.iter().any(|item| is_package_tag(item, false)),
+    };
+
+    if skip_prerelease {
+        tags.retain(|item| !is_package_tag(item, true));
+    }
+
+    tags
+}
+
+/// Grabs the last known publish tag info for a package among the given publish tags (see
+/// `get_publish_tags`), only considering the tags allowed by the prerelease policy. Tags must
+/// be in the `<name>@<version>` form (see `normalize_publish_tags`).
+pub fn get_last_known_publish_tag_info_in(
+    tags: &[RemoteTags],
+    package_info: &PackageInfo,
+    prerelease_policy: Option<PrereleasePolicy>,
+) -> Option<PublishTagInfo> {
+    let mut remote_tags = filter_prerelease_tags(
+        dedupe_tags(tags.to_vec()),
+        &package_info.name,
+        prerelease_policy,
+    );
+
+    let mut options = CollatorOptions::new();
+    options.strength = Some(Strength::Secondary);
+    options.numeric = Some(Numeric::On);
+
+    let collator = Collator::try_new(&Default::default(), options).unwrap();
 
     remote_tags.sort_by(|a, b| {
         let tag_a = a.tag.replace("refs/tags/", "");
         let tag_b = b.tag.replace("refs/tags/", "");
 
-        collator.compare(&tag_b, &tag_a)
-    });
+        collator.compare(&tag_b, &tag_a)
+    });
+
+    let mut match_tag = remote_tags.iter().find(|item| {
+        let tag = item.tag.replace("refs/tags/", "");
+
+        match split_publish_tag(&tag) {
+            Some((name, version)) => name == package_info.name && version == package_info.version,
+            None => false,
+        }
+    });
+
+    // Without a tag of the current version, the highest tag of the package wins. Tags whose
+    // version isn't valid semver are skipped
+    if match_tag.is_none() {
+        let highest_version = get_latest_publish_tag_version_in(&remote_tags, &package_info.name);
+
+        match_tag = remote_tags.iter().find(|item| {
+            let tag = item.tag.replace("refs/tags/", "");
+
+            match split_publish_tag(&tag) {
+                Some((name, version)) => {
+                    name == package_info.name
+                        && semver::Version::parse(version)
+                            .ok()
+                            .map(|version| version.to_string())
+                            == highest_version
+                }
+                None => false,
+            }
+        });
+    }
+
+    if match_tag.is_some() {
+        let hash = &match_tag.unwrap().hash;
+        let tag = &match_tag.unwrap().tag;
+        let package = &package_info.name;
+
+        return Some(PublishTagInfo {
+            hash: hash.to_string(),
+            tag: tag.to_string(),
+            package: package.to_string(),
+        });
+    }
+
+    None
+}
+
+/// Grabs the last known publish tag info for all packages in the monorepo, the tags are listed
+/// once for all of them
+pub fn get_last_known_publish_tag_info_for_all_packages(
+    package_info: &Vec<PackageInfo>,
+    cwd: Option<String>,
+) -> Result<Vec<Option<PublishTagInfo>>, GitError> {
+    let root = match cwd {
+        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let timeout = CommandLimits::network(&Some(root.to_string())).timeout;
+    git_fetch_all_with_timeout(Some(root.to_string()), Some(true), timeout)?;
+
+    let ref tags = get_publish_tags(Some(root.to_string()))?;
+
+    let tag_infos = package_info
+        .iter()
+        .map(|item| get_last_known_publish_tag_info_in(tags, &item, None))
+        .filter(|item| item.is_some())
+        .collect::<Vec<Option<PublishTagInfo>>>();
+
+    Ok(tag_infos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        manager::PackageManager,
+        packages::{get_changed_packages, get_packages, ChangedPackagesOptions},
+        paths::get_project_root_path,
+        utils::{clone_test_monorepo, create_test_monorepo, run_git},
+    };
+    use std::fs::{remove_dir_all, File};
+
+    #[test]
+    fn test_git_fetch_all() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let result = git_fetch_all(project_root, None)?;
+        assert_eq!(result, false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_fetch() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref clone_dir = clone_test_monorepo(monorepo_dir)?;
+        let ref clone = clone_dir.display().to_string();
+
+        run_git(clone_dir, &["remote", "rename", "origin", "upstream"])?;
+
+        assert_eq!(
+            git_fetch(
+                &String::from("origin"),
+                false,
+                None,
+                Some(clone.to_string())
+            )?,
+            false
+        );
+        assert_eq!(clone_dir.join(".git/shallow").exists(), false);
+
+        // A fetch with depth turns the clone into a shallow one
+        let fetch = git_fetch(
+            &String::from("upstream"),
+            true,
+            Some(1),
+            Some(clone.to_string()),
+        )?;
+
+        assert_eq!(fetch, true);
+        assert_eq!(clone_dir.join(".git/shallow").exists(), true);
+        remove_dir_all(&clone_dir)?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_execute_with_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref clone_dir = clone_test_monorepo(monorepo_dir)?;
+
+        let started = Instant::now();
+        let mut sleep = Command::new("sleep");
+        sleep.arg("5");
+
+        let result = git_execute_with_timeout(&mut sleep, Some(Duration::from_millis(100)));
+
+        assert_eq!(
+            matches!(result, Err(GitError::Timeout(ref command)) if command == "sleep 5"),
+            true
+        );
+        assert_eq!(started.elapsed() < Duration::from_secs(5), true);
+
+        let fetch = git_fetch_all_with_timeout(
+            Some(clone_dir.display().to_string()),
+            Some(true),
+            Some(Duration::from_secs(30)),
+        )?;
+
+        assert_eq!(fetch, true);
+        remove_dir_all(&clone_dir)?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_with_limits() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref pid_path = monorepo_dir.join("command.pid");
+
+        // The shell execs the command, so the recorded pid is the one of the killed child
+        let command = |script: &str| {
+            let mut command = Command::new("sh");
+            command
+                .arg("-c")
+                .arg(format!("echo $$ > {}; exec {}", pid_path.display(), script));
+            command
+        };
+        let is_reaped = || -> Result<bool, std::io::Error> {
+            let pid = std::fs::read_to_string(pid_path)?;
+            let alive = Command::new("kill")
+                .arg("-0")
+                .arg(pid.trim())
+                .stderr(Stdio::piped())
+                .status()?;
+
+            Ok(!alive.success())
+        };
+
+        let started = Instant::now();
+        let result = execute_with_limits(
+            &mut command("sleep 5"),
+            &CommandLimits {
+                timeout: Some(Duration::from_millis(100)),
+                max_output_bytes: None,
+            },
+        );
+
+        assert_eq!(matches!(result, Err(GitError::Timeout(_))), true);
+        assert_eq!(started.elapsed() < Duration::from_secs(5), true);
+        assert_eq!(is_reaped()?, true);
+
+        let result = execute_with_limits(
+            &mut command("yes"),
+            &CommandLimits {
+                timeout: Some(Duration::from_secs(10)),
+                max_output_bytes: Some(1024),
+            },
+        );
+
+        assert_eq!(
+            matches!(result, Err(GitError::OutputTooLarge(ref command)) if command.starts_with("sh -c") && command.contains("exec yes")),
+            true
+        );
+        assert_eq!(is_reaped()?, true);
+
+        let output = execute_with_limits(
+            &mut command("echo hello"),
+            &CommandLimits {
+                timeout: Some(Duration::from_secs(10)),
+                max_output_bytes: Some(1024),
+            },
+        )?;
+
+        assert_eq!(String::from_utf8(output.stdout)?, "hello\n");
+
+        let ref root = Some(monorepo_dir.display().to_string());
+
+        assert_eq!(
+            CommandLimits::network(root).timeout,
+            Some(DEFAULT_NETWORK_TIMEOUT)
+        );
+        assert_eq!(
+            CommandLimits::local(root).timeout,
+            Some(DEFAULT_COMMAND_TIMEOUT)
+        );
+
+        std::fs::write(
+            monorepo_dir.join(crate::config::RELEASE_CONFIG_FILE),
+            r#"{ "network_timeout_ms": 120000, "command_timeout_ms": 5000, "max_output_bytes": 1024 }"#,
+        )?;
+
+        assert_eq!(
+            CommandLimits::network(root),
+            CommandLimits {
+                timeout: Some(Duration::from_secs(120)),
+                max_output_bytes: Some(1024),
+            }
+        );
+        assert_eq!(
+            CommandLimits::local(root),
+            CommandLimits {
+                timeout: Some(Duration::from_secs(5)),
+                max_output_bytes: Some(1024),
+            }
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_diverged_commit() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let result = get_diverged_commit(String::from("@scope/package-a@1.0.0"), project_root);
+
+        assert!(result.is_some());
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_divergence() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let commit_file = |file: &str| -> Result<(), std::io::Error> {
+            File::create(monorepo_dir.join(file))?;
+
+            let add = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("add")
+                .arg(".")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git add problem");
+
+            add.wait_with_output()?;
+
+            let commit = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("commit")
+                .arg("-m")
+                .arg(format!("chore: add {}", file))
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git commit problem");
+
+            commit.wait_with_output()?;
+            Ok(())
+        };
+
+        let checkout = |args: &[&str]| -> Result<(), std::io::Error> {
+            let checkout = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("checkout")
+                .args(args)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .expect("Git checkout problem");
+
+            checkout.wait_with_output()?;
+            Ok(())
+        };
+
+        let base = git_current_sha(project_root.to_owned());
+
+        checkout(&["-b", "feat/divergence"])?;
+        commit_file("feature.txt")?;
+        checkout(&["main"])?;
+        commit_file("main-1.txt")?;
+        commit_file("main-2.txt")?;
+        checkout(&["feat/divergence"])?;
+
+        let divergence = get_divergence(String::from("main"), project_root.to_owned()).unwrap();
+
+        assert_eq!(divergence.ahead, 1);
+        assert_eq!(divergence.behind, 2);
+        assert_eq!(divergence.merge_base.starts_with(&base), true);
+
+        let unknown = get_divergence(String::from("unknown-branch"), project_root);
+
+        assert_eq!(matches!(unknown, Err(GitError::UnknownRef(_))), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_repository_url() {
+        let urls = [
+            "git@github.com:websublime/workspace-node-tools.git",
+            "git+ssh://git@github.com/websublime/workspace-node-tools.git",
+            "ssh://git@github.com:22/websublime/workspace-node-tools.git",
+            "git+https://github.com/websublime/workspace-node-tools.git",
+            "https://github.com/websublime/workspace-node-tools/",
+        ];
+
+        for url in urls {
+            assert_eq!(
+                normalize_repository_url(url),
+                "https://github.com/websublime/workspace-node-tools"
+            );
+        }
+    }
+
+    #[test]
+    fn test_git_remote_url() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        assert_eq!(git_remote_url(None, project_root.to_owned()), None);
+
+        let add_remote = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("remote")
+            .arg("add")
+            .arg("origin")
+            .arg("git@github.com:websublime/workspace-node-tools.git")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git remote problem");
+
+        add_remote.wait_with_output()?;
+
+        let result = git_remote_url(None, project_root);
+
+        assert_eq!(
+            result,
+            Some(String::from(
+                "https://github.com/websublime/workspace-node-tools"
+            ))
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_current_sha() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let result = git_current_sha(project_root);
+        assert_eq!(result.is_empty(), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_head_sha() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let result = git_head_sha(project_root.to_owned());
+
+        assert_eq!(result.short.is_empty(), false);
+        assert_eq!(result.full.len(), 40);
+        assert_eq!(result.full.starts_with(&result.short), true);
+        assert_eq!(result.short, git_current_sha(project_root));
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_first_sha() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let commit_file = |file: &str| -> Result<String, std::io::Error> {
+            File::create(monorepo_dir.join(file))?;
+            run_git(&monorepo_dir, &["add", "."])?;
+            run_git(
+                &monorepo_dir,
+                &["commit", "-m", &format!("chore: add {}", file)],
+            )?;
+
+            Ok(git_head_sha(project_root.to_owned()).short)
+        };
+
+        assert_eq!(
+            matches!(
+                git_first_sha(project_root.to_owned(), None),
+                Err(GitError::Command(_))
+            ),
+            true
+        );
+
+        run_git(&monorepo_dir, &["checkout", "-b", "feat/first"])?;
+        let first = commit_file("first.js")?;
+        commit_file("second.js")?;
+        commit_file("third.js")?;
+
+        let result = git_first_sha(project_root.to_owned(), Some(String::from("main")))?;
+
+        assert_eq!(result, first);
+        assert_eq!(
+            matches!(
+                git_first_sha(project_root.to_owned(), Some(String::from("unknown"))),
+                Err(GitError::UnknownRef(_))
+            ),
+            true
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_previous_sha() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let result = git_previous_sha(project_root);
+        assert_eq!(result.is_empty(), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_workdir_unclean() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let js_path = monorepo_dir.join("packages/package-a/index.js");
+
+        let mut js_file = File::create(&js_path)?;
+        js_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+
+        let result = git_workdir_unclean(project_root);
+        assert_eq!(result, true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_status() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        assert_eq!(git_status(project_root.to_owned())?, vec![]);
+        assert_eq!(git_workdir_unclean(project_root.to_owned()), false);
+
+        let mut js_file = File::create(monorepo_dir.join("packages/package-a/index.js"))?;
+        js_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+
+        let mut package_json = File::options()
+            .append(true)
+            .open(monorepo_dir.join("packages/package-b/package.json"))?;
+        package_json.write_all(b"\n")?;
+
+        let git = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .args([
+                "mv",
+                "packages/package-c/package.json",
+                "packages/package-c/manifest.json",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Git mv problem");
+        git.wait_with_output()?;
+
+        let entries = git_status(project_root.to_owned())?;
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries.contains(&StatusEntry {
+                path: String::from("packages/package-a/index.js"),
+                original_path: None,
+                status: FileStatus::Untracked,
+                staged: false,
+            }),
+            true
+        );
+        assert_eq!(
+            entries.contains(&StatusEntry {
+                path: String::from("packages/package-b/package.json"),
+                original_path: None,
+                status: FileStatus::Modified,
+                staged: false,
+            }),
+            true
+        );
+        assert_eq!(
+            entries.contains(&StatusEntry {
+                path: String::from("packages/package-c/manifest.json"),
+                original_path: Some(String::from("packages/package-c/package.json")),
+                status: FileStatus::Renamed,
+                staged: true,
+            }),
+            true
+        );
+        assert_eq!(git_workdir_unclean(project_root), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_workdir_unclean() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let ref root = project_root.unwrap().to_string();
+        let js_path = monorepo_dir.join("packages/package-a/index.js");
+
+        let mut js_file = File::create(&js_path)?;
+        js_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+
+        let packages = get_packages(Some(root.to_string()))?;
+
+        for package in packages {
+            let unclean = package_workdir_unclean(&package, Some(root.to_string()));
+
+            assert_eq!(unclean, package.name == "@scope/package-a");
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_tag_tree_clean() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let ref root = project_root.unwrap();
+
+        let tag = String::from("@scope/package-a@1.0.0");
+
+        assert_eq!(
+            verify_tag_tree_clean(tag.to_string(), Some(root.to_string())),
+            true
+        );
+        assert_eq!(
+            verify_tag_tree_clean(String::from("unknown@9.9.9"), Some(root.to_string())),
+            false
+        );
+
+        let mut package_json = File::options()
+            .append(true)
+            .open(monorepo_dir.join("packages/package-a/package.json"))?;
+        package_json.write_all(b"\n")?;
+
+        assert_eq!(verify_tag_tree_clean(tag, Some(root.to_string())), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_clean() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let js_path = monorepo_dir.join("packages/package-a/index.js");
+        let dist_path = monorepo_dir.join("packages/package-a/dist");
+
+        std::fs::create_dir(&dist_path)?;
+        let mut js_file = File::create(&js_path)?;
+        js_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+        File::create(dist_path.join("index.js"))?;
+
+        let result = git_clean(project_root.to_owned(), false)?;
+
+        assert_eq!(result, true);
+        assert_eq!(js_path.exists(), false);
+        assert_eq!(dist_path.exists(), false);
+        assert_eq!(git_workdir_unclean(project_root), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_reset_hard() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let package_json_path = monorepo_dir.join("packages/package-a/package.json");
+
+        let mut package_json_file = File::create(&package_json_path)?;
+        package_json_file.write_all(r#"{}"#.as_bytes())?;
+
+        let result = git_reset_hard(None, project_root.to_owned())?;
+
+        assert_eq!(result, true);
+        assert_eq!(git_workdir_unclean(project_root), false);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_cherry_pick() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let write_file = |file: &str, content: &str| -> Result<(), std::io::Error> {
+            let mut js_file = File::create(monorepo_dir.join(file))?;
+            js_file.write_all(content.as_bytes())
+        };
+
+        run_git(&monorepo_dir, &["checkout", "-b", "release/1.x"])?;
+        run_git(&monorepo_dir, &["checkout", "-"])?;
+
+        write_file("packages/package-a/fix.js", "export const fix = true;")?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "fix: backported fix"])?;
+        let fix_sha = git_current_sha(project_root.to_owned());
+
+        write_file("packages/package-a/index.js", "export const main = true;")?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "feat: main only"])?;
+        let main_sha = git_current_sha(project_root.to_owned());
+
+        run_git(&monorepo_dir, &["checkout", "release/1.x"])?;
+
+        let result = git_cherry_pick(&fix_sha, project_root.to_owned())?;
+
+        assert_eq!(result, true);
+        assert_eq!(
+            monorepo_dir.join("packages/package-a/fix.js").exists(),
+            true
+        );
+        assert_eq!(
+            monorepo_dir.join("packages/package-a/index.js").exists(),
+            false
+        );
+
+        write_file(
+            "packages/package-a/index.js",
+            "export const release = true;",
+        )?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "feat: release only"])?;
+
+        let conflict = git_cherry_pick(&main_sha, project_root.to_owned());
+
+        assert_eq!(
+            matches!(conflict, Err(GitError::Conflict(ref files)) if files == &vec![String::from("packages/package-a/index.js")]),
+            true
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_is_head_pushed() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let ref remote_dir = monorepo_dir.join("remote.git");
+
+        assert_eq!(git_is_head_pushed(None, project_root.to_owned()), false);
+
+        let init = Command::new("git")
+            .arg("init")
+            .arg("--bare")
+            .arg(remote_dir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Git init problem");
+
+        init.wait_with_output()?;
+
+        let add_remote = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("remote")
+            .arg("add")
+            .arg("origin")
+            .arg(remote_dir)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git remote problem");
+
+        add_remote.wait_with_output()?;
+
+        assert_eq!(git_is_head_pushed(None, project_root.to_owned()), false);
+
+        let push = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("push")
+            .arg("origin")
+            .arg("main")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Git push problem");
+
+        push.wait_with_output()?;
+
+        assert_eq!(git_is_head_pushed(None, project_root.to_owned()), true);
+        assert_eq!(
+            git_is_head_pushed(Some(String::from("upstream")), project_root),
+            false
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_branch_from_commit() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let commit = git_current_sha(Some(project_root.as_ref().unwrap().to_string()));
+        let result = git_branch_from_commit(commit, project_root);
+        assert_eq!(result.is_some(), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+
+        let remote_branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("branch")
+            .arg("feat/remote-only")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Git branch problem");
+
+        remote_branch.wait_with_output()?;
+
+        let ref clone_dir = clone_test_monorepo(monorepo_dir)?;
+        let ref clone_root = clone_dir.display().to_string();
+
+        let local_branch = Command::new("git")
+            .current_dir(&clone_dir)
+            .arg("branch")
+            .arg("feat/local")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Git branch problem");
+
+        local_branch.wait_with_output()?;
+
+        let names = |branches: &Vec<BranchInfo>| {
+            let mut names = branches
+                .iter()
+                .map(|branch| branch.name.to_string())
+                .collect::<Vec<String>>();
+            names.sort();
+            names
+        };
+
+        let local = get_branches(Some(clone_root.to_string()), BranchScope::Local);
+        let current = local
+            .iter()
+            .filter(|branch| branch.is_current)
+            .collect::<Vec<&BranchInfo>>();
+
+        assert_eq!(names(&local), vec!["feat/local", "main"]);
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].name, String::from("main"));
+        assert_eq!(current[0].upstream, Some(String::from("origin/main")));
+        assert_eq!(
+            current[0]
+                .head_sha
+                .starts_with(&git_current_sha(Some(clone_root.to_string()))),
+            true
+        );
+
+        let remote = get_branches(Some(clone_root.to_string()), BranchScope::Remote);
+
+        assert_eq!(names(&remote), vec!["feat/remote-only", "main"]);
+        assert_eq!(
+            get_branches(Some(clone_root.to_string()), BranchScope::All).len(),
+            4
+        );
+        assert_eq!(
+            branch_exists(
+                String::from("feat/remote-only"),
+                BranchScope::Remote,
+                Some(clone_root.to_string())
+            ),
+            true
+        );
+        assert_eq!(
+            branch_exists(
+                String::from("feat/remote-only"),
+                BranchScope::Local,
+                Some(clone_root.to_string())
+            ),
+            false
+        );
+        remove_dir_all(&clone_dir)?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_commits_since() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-    let package_tag = format!("{}@{}", package_info.name, package_info.version);
+        let result = get_commits_since(
+            project_root,
+            Some(String::from("main")),
+            Some(String::from("packages/package-a")),
+            &CommitsSinceOptions::default(),
+        );
+        let count = result.len();
 
-    let mut match_tag = remote_tags.iter().find(|item| {
-        let tag = item.tag.replace("refs/tags/", "");
-        let matches: Vec<&str> = tag.matches(&package_tag).collect();
+        assert_eq!(count, 0);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
 
-        if matches.len() > 0 {
-            return true;
-        } else {
-            return false;
-        }
-    });
+    #[test]
+    fn test_get_commits_since_with_delimiters() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-    if match_tag.is_none() {
-        let mut highest_tag = None;
+        let message = "feat: split on #=# and #+# markers\n\nbody with #=# too";
 
-        remote_tags.iter().for_each(|item| {
-            let tag = &item.tag.replace("refs/tags/", "");
+        File::create(monorepo_dir.join("packages/package-a/markers.js"))?;
 
-            if tag.contains(&package_info.name) {
-                if highest_tag.is_none() {
-                    highest_tag = Some(String::from(tag));
-                }
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
 
-                let high_tag = highest_tag.as_ref().unwrap();
-                let current_tag_meta = package_scope_name_version(tag).unwrap();
-                let highest_tag_meta = package_scope_name_version(high_tag).unwrap();
+        add.wait_with_output()?;
 
-                let current_version = Version::from(&current_tag_meta.version).unwrap();
-                let highest_version = Version::from(&highest_tag_meta.version).unwrap();
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg(message)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
 
-                if current_version.compare_to(&highest_version, Cmp::Gt) {
-                    highest_tag = Some(String::from(tag));
-                }
-            }
-        });
+        commit.wait_with_output()?;
+
+        let result = get_commits_since(
+            project_root,
+            Some(String::from("HEAD~1")),
+            Some(String::from("packages/package-a")),
+            &CommitsSinceOptions::default(),
+        );
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].message, message);
+        assert_eq!(result[0].hash.len(), 40);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_commits_between_tags() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let ref root = project_root.unwrap().to_string();
 
-        if highest_tag.is_some() {
-            let highest_tag = highest_tag.unwrap();
-            let highest_tag_meta = package_scope_name_version(&highest_tag).unwrap();
+        run_git(&monorepo_dir, &["tag", "range-start"])?;
 
-            match_tag = remote_tags.iter().find(|item| {
-                let tag = item.tag.replace("refs/tags/", "");
-                let matches: Vec<&str> = tag.matches(&highest_tag_meta.full).collect();
+        for (file, message) in [
+            ("first.js", "feat: first in range"),
+            ("second.js", "fix: second in range"),
+            ("after.js", "feat: after the range"),
+        ] {
+            File::create(monorepo_dir.join("packages/package-a").join(file))?;
 
-                if matches.len() > 0 {
-                    return true;
-                } else {
-                    return false;
-                }
-            });
+            run_git(&monorepo_dir, &["add", "."])?;
+            run_git(&monorepo_dir, &["commit", "-m", message])?;
+
+            if file == "second.js" {
+                run_git(&monorepo_dir, &["tag", "range-end"])?;
+            }
         }
+
+        let subjects = |relative: Option<String>| {
+            get_commits_between(
+                Some(root.to_string()),
+                Some(String::from("range-start")),
+                String::from("range-end"),
+                relative,
+            )
+            .iter()
+            .map(|commit| commit.subject.to_string())
+            .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            subjects(None),
+            vec!["fix: second in range", "feat: first in range"]
+        );
+        assert_eq!(subjects(Some(String::from("packages/package-b"))).len(), 0);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
     }
 
-    if match_tag.is_some() {
-        let hash = &match_tag.unwrap().hash;
-        let tag = &match_tag.unwrap().tag;
-        let package = &package_info.name;
+    #[test]
+    fn test_get_commits_since_with_trailers() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let ref root = project_root.unwrap().to_string();
+
+        let markers_message = "fix: keep #=# and #+# markers\n\nbody with #=# and #+# too";
+        let trailers_message = "feat: route deploys\n\nDeploys are routed by the commit trailers.\n\nDeploy: staging\nRefs: #42";
+
+        for (file, message) in [
+            ("markers.js", markers_message),
+            ("deploy.js", trailers_message),
+        ] {
+            File::create(monorepo_dir.join("packages/package-a").join(file))?;
+
+            git_add_all(root)?;
+            git_commit(
+                message.to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some(root.to_string()),
+            )?;
+        }
 
-        return Some(PublishTagInfo {
-            hash: hash.to_string(),
-            tag: tag.to_string(),
-            package: package.to_string(),
-        });
+        let result = get_commits_since(
+            Some(root.to_string()),
+            Some(String::from("HEAD~2")),
+            None,
+            &CommitsSinceOptions::default(),
+        );
+
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(result[0].message, trailers_message);
+        assert_eq!(result[0].subject, "feat: route deploys");
+        assert_eq!(
+            result[0].body,
+            "Deploys are routed by the commit trailers.\n\nDeploy: staging\nRefs: #42"
+        );
+        assert_eq!(
+            result[0].trailers,
+            vec![
+                CommitTrailer {
+                    key: String::from("Deploy"),
+                    value: String::from("staging"),
+                },
+                CommitTrailer {
+                    key: String::from("Refs"),
+                    value: String::from("#42"),
+                },
+            ]
+        );
+
+        assert_eq!(result[1].message, markers_message);
+        assert_eq!(result[1].subject, "fix: keep #=# and #+# markers");
+        assert_eq!(result[1].body, "body with #=# and #+# too");
+        assert_eq!(result[1].trailers, vec![]);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
     }
 
-    None
-}
+    #[test]
+    fn test_get_commits_since_author_filters() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let ref root = project_root.unwrap().to_string();
+
+        for (file, author) in [
+            ("bump.js", "dependabot[bot] <support@github.com>"),
+            ("feature.js", "Jane Doe <jane.doe@domain.com>"),
+        ] {
+            File::create(monorepo_dir.join("packages/package-a").join(file))?;
+
+            let add = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("add")
+                .arg(".")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git add problem");
+
+            add.wait_with_output()?;
+
+            let commit = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("commit")
+                .arg("-m")
+                .arg(format!("feat: add {}", file))
+                .arg("--author")
+                .arg(author)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git commit problem");
+
+            commit.wait_with_output()?;
+        }
 
-/// Grabs the last known publish tag info for all packages in the monorepo
-pub fn get_last_known_publish_tag_info_for_all_packages(
-    package_info: &Vec<PackageInfo>,
-    cwd: Option<String>,
-) -> Vec<Option<PublishTagInfo>> {
-    let root = match cwd {
-        Some(dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
-        None => get_project_root_path(None).unwrap(),
-    };
+        let all_commits = get_commits_since(
+            Some(root.to_string()),
+            Some(String::from("HEAD~2")),
+            None,
+            &CommitsSinceOptions::default(),
+        );
 
-    git_fetch_all(Some(root.to_string()), Some(true)).expect("Fetch all tags");
+        assert_eq!(all_commits.len(), 2);
 
-    package_info
-        .iter()
-        .map(|item| get_last_known_publish_tag_info_for_package(&item, Some(root.to_string())))
-        .filter(|item| item.is_some())
-        .collect::<Vec<Option<PublishTagInfo>>>()
-}
+        let human_commits = get_commits_since(
+            Some(root.to_string()),
+            Some(String::from("HEAD~2")),
+            None,
+            &CommitsSinceOptions {
+                exclude_authors: Some(vec![String::from("dependabot")]),
+                ..Default::default()
+            },
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{
-        manager::PackageManager, paths::get_project_root_path, utils::create_test_monorepo,
-    };
-    use std::fs::{remove_dir_all, File};
+        assert_eq!(human_commits.len(), 1);
+        assert_eq!(human_commits[0].author_name, "Jane Doe");
+
+        let bot_commits = get_commits_since(
+            Some(root.to_string()),
+            Some(String::from("HEAD~2")),
+            None,
+            &CommitsSinceOptions {
+                author: Some(String::from("dependabot")),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(bot_commits.len(), 1);
+        assert_eq!(bot_commits[0].author_name, "dependabot[bot]");
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
 
     #[test]
-    fn test_git_fetch_all() -> Result<(), std::io::Error> {
+    fn test_get_diff_stat() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let result = git_fetch_all(project_root, None)?;
-        assert_eq!(result, false);
+        std::fs::write(
+            monorepo_dir.join("packages/package-a/index.js"),
+            "export const a = 1;\nexport const b = 2;\n",
+        )?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "feat: add index"])?;
+
+        let base = git_head_sha(project_root.to_owned()).full;
+
+        std::fs::write(
+            monorepo_dir.join("packages/package-a/index.js"),
+            "export const a = 1;\nexport const c = 3;\nexport const d = 4;\n",
+        )?;
+        std::fs::write(
+            monorepo_dir.join("packages/package-b/logo.png"),
+            [0u8, 159, 146, 150, 0, 1],
+        )?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "feat: update index"])?;
+
+        let stats = get_diff_stat(&base, project_root.to_owned())?;
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats.contains(&FileStat {
+                path: String::from("packages/package-a/index.js"),
+                insertions: Some(2),
+                deletions: Some(1),
+            }),
+            true
+        );
+        assert_eq!(
+            stats.contains(&FileStat {
+                path: String::from("packages/package-b/logo.png"),
+                insertions: None,
+                deletions: None,
+            }),
+            true
+        );
+        assert_eq!(
+            matches!(
+                get_diff_stat(&String::from("unknown"), project_root.to_owned()),
+                Err(GitError::UnknownRef(_))
+            ),
+            true
+        );
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_get_diverged_commit() -> Result<(), std::io::Error> {
+    fn test_git_worktree_list() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let result = get_diverged_commit(String::from("@scope/package-a@1.0.0"), project_root);
+        let ref worktree_dir = monorepo_dir.with_file_name(format!(
+            "{}-worktree",
+            monorepo_dir.file_name().unwrap().to_str().unwrap()
+        ));
+        let ref worktree_path = worktree_dir.display().to_string();
+
+        let branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .args(["branch", "feat/worktree"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Git branch problem");
+        branch.wait_with_output()?;
+
+        let added = git_worktree_add_branch(
+            worktree_path,
+            &String::from("feat/worktree"),
+            project_root.to_owned(),
+        )?;
+        let worktrees = git_worktree_list(project_root.to_owned())?;
+        let head = git_head_sha(project_root.to_owned()).full;
+
+        assert_eq!(added, true);
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(worktrees[0].path, project_root.to_owned().unwrap());
+        assert_eq!(worktrees[0].branch, Some(String::from("main")));
+        assert_eq!(
+            worktrees[1].path,
+            std::fs::canonicalize(worktree_dir)?.display().to_string()
+        );
+        assert_eq!(worktrees[1].branch, Some(String::from("feat/worktree")));
+        assert_eq!(worktrees[1].sha, head);
+
+        // The branch is already checked out in the worktree
+        assert_eq!(
+            matches!(
+                git_worktree_add_branch(
+                    &format!("{}-again", worktree_path),
+                    &String::from("feat/worktree"),
+                    project_root.to_owned(),
+                ),
+                Err(GitError::Command(_))
+            ),
+            true
+        );
 
-        assert!(result.is_some());
+        git_worktree_remove(worktree_path, project_root.to_owned())?;
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_git_current_sha() -> Result<(), std::io::Error> {
+    fn test_get_commit_graph() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let result = git_current_sha(project_root);
-        assert_eq!(result.is_empty(), false);
+        let commit_file = |file: &str| -> Result<String, std::io::Error> {
+            File::create(monorepo_dir.join(file))?;
+            run_git(&monorepo_dir, &["add", "."])?;
+            run_git(
+                &monorepo_dir,
+                &["commit", "-m", &format!("chore: add {}", file)],
+            )?;
+
+            Ok(git_head_sha(project_root.to_owned()).full)
+        };
+
+        let base = git_head_sha(project_root.to_owned()).full;
+
+        run_git(&monorepo_dir, &["checkout", "-b", "feat/graph"])?;
+        let feature = commit_file("feature.js")?;
+        run_git(&monorepo_dir, &["checkout", "-"])?;
+        let main = commit_file("main.js")?;
+        run_git(
+            &monorepo_dir,
+            &[
+                "merge",
+                "--no-ff",
+                "-m",
+                "chore: merge feat/graph",
+                "feat/graph",
+            ],
+        )?;
+
+        let graph = get_commit_graph(Some(base.to_string()), project_root.to_owned())?;
+        let merge = graph.first().unwrap();
+        let feature_node = graph.iter().find(|node| node.sha == feature).unwrap();
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(merge.subject, String::from("chore: merge feat/graph"));
+        assert_eq!(merge.parents, vec![main, feature.to_string()]);
+        assert_eq!(
+            merge.refs.iter().any(|refer| refer.starts_with("HEAD -> ")),
+            true
+        );
+        assert_eq!(feature_node.parents, vec![base]);
+        assert_eq!(feature_node.refs, vec![String::from("feat/graph")]);
+        assert_eq!(
+            get_commit_graph(Some(String::from("unknown")), project_root).is_err(),
+            true
+        );
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_git_previous_sha() -> Result<(), std::io::Error> {
+    fn test_git_signing_args() {
+        let key = Some(String::from("3AA5C34371567BD2"));
+
+        assert_eq!(git_signing_args("commit", None, None), Vec::<String>::new());
+        assert_eq!(git_signing_args("commit", Some(true), None), vec!["-S"]);
+        assert_eq!(
+            git_signing_args("commit", Some(true), key.to_owned()),
+            vec!["-S3AA5C34371567BD2"]
+        );
+        assert_eq!(
+            git_signing_args("commit", None, key.to_owned()),
+            vec!["-S3AA5C34371567BD2"]
+        );
+        assert_eq!(
+            git_signing_args("tag", Some(false), None),
+            Vec::<String>::new()
+        );
+        assert_eq!(git_signing_args("tag", Some(true), None), vec!["-s"]);
+        assert_eq!(
+            git_signing_args("tag", Some(true), key.to_owned()),
+            vec!["--local-user", "3AA5C34371567BD2"]
+        );
+    }
+
+    #[test]
+    fn test_get_local_tags() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let result = git_previous_sha(project_root);
-        assert_eq!(result.is_empty(), true);
+        let result = get_remote_or_local_tags(project_root.to_owned(), Some(true))?;
+        let count = result.len();
+
+        assert_eq!(count, 3);
+
+        // Tags cut by the output limit are an error, never an empty list
+        std::fs::write(
+            monorepo_dir.join(crate::config::RELEASE_CONFIG_FILE),
+            r#"{ "max_output_bytes": 16 }"#,
+        )?;
+
+        assert_eq!(
+            matches!(
+                get_remote_or_local_tags(project_root, Some(true)),
+                Err(GitError::OutputTooLarge(_))
+            ),
+            true
+        );
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_git_workdir_unclean() -> Result<(), std::io::Error> {
+    fn test_git_all_files_changed_since_sha() -> Result<(), std::io::Error> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
-        let js_path = monorepo_dir.join("packages/package-a/index.js");
 
-        let mut js_file = File::create(&js_path)?;
-        js_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+        let result = git_all_files_changed_since_sha(String::from("main"), project_root);
+        let count = result.len();
 
-        let result = git_workdir_unclean(project_root);
-        assert_eq!(result, true);
+        assert_eq!(count, 0);
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_git_branch_from_commit() -> Result<(), std::io::Error> {
+    fn test_normalize_changed_files() {
+        let files = vec![
+            String::from("packages/package-b/index.mjs"),
+            String::from("packages\\package-a\\index.mjs"),
+            String::from("./packages/package-a/index.mjs"),
+            String::from("/tmp/monorepo/packages/package-b/index.mjs"),
+            String::from(""),
+            String::from("packages/package-a/package.json"),
+        ];
+
+        assert_eq!(
+            normalize_changed_files(files, "/tmp/monorepo"),
+            vec![
+                String::from("/tmp/monorepo/packages/package-a/index.mjs"),
+                String::from("/tmp/monorepo/packages/package-a/package.json"),
+                String::from("/tmp/monorepo/packages/package-b/index.mjs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedupe_tags() {
+        let remote_tags = vec![
+            RemoteTags {
+                hash: String::from(""),
+                tag: String::from("refs/tags/@scope/package-a@1.1.0"),
+            },
+            RemoteTags {
+                hash: String::from("ddd1fa69be3e6c6a8b2f18af8f8f5607106188db"),
+                tag: String::from("refs/tags/@scope/package-a@1.0.0"),
+            },
+        ];
+        let local_tags = vec![
+            RemoteTags {
+                hash: String::from("c5353e1f3c9385c35f64e838a0a09dc4bb8f7b07"),
+                tag: String::from("refs/tags/@scope/package-a@1.1.0"),
+            },
+            RemoteTags {
+                hash: String::from("ddd1fa69be3e6c6a8b2f18af8f8f5607106188db"),
+                tag: String::from("@scope/package-a@1.0.0"),
+            },
+        ];
+
+        let tags = dedupe_tags([remote_tags, local_tags].concat());
+
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].tag, "refs/tags/@scope/package-a@1.1.0");
+        assert_eq!(tags[0].hash, "c5353e1f3c9385c35f64e838a0a09dc4bb8f7b07");
+        assert_eq!(tags[1].tag, "refs/tags/@scope/package-a@1.0.0");
+    }
+
+    #[test]
+    fn test_get_package_tags() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let commit = git_current_sha(Some(project_root.as_ref().unwrap().to_string()));
-        let result = git_branch_from_commit(commit, project_root);
-        assert_eq!(result.is_some(), true);
+        let ref root = project_root.unwrap().to_string();
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let package_a = packages
+            .iter()
+            .find(|package| package.name == "@scope/package-a")
+            .unwrap();
+
+        let tags = get_package_tags(package_a, Some(root.to_string()))?;
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].tag, "refs/tags/@scope/package-a@1.0.0");
+
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_get_commits_since() -> Result<(), std::io::Error> {
+    fn test_get_last_known_publish_tag_info_for_similar_packages(
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let result = get_commits_since(
+        for tag in ["pkg@1.0.0", "pkg@next", "pkg-core@1.0.0", "pkg-core@3.0.0"] {
+            let tag = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("tag")
+                .arg(tag)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git tag problem");
+
+            tag.wait_with_output()?;
+        }
+
+        let packages = get_packages(project_root.to_owned())?;
+        let mut pkg = packages.first().unwrap().to_owned();
+        let mut pkg_core = pkg.to_owned();
+
+        pkg.name = String::from("pkg");
+        pkg.version = String::from("1.0.0");
+        pkg_core.name = String::from("pkg-core");
+        pkg_core.version = String::from("1.0.0");
+
+        let pkg_tag = get_last_known_publish_tag_info_for_package(&pkg, project_root.to_owned())?;
+        let pkg_core_tag =
+            get_last_known_publish_tag_info_for_package(&pkg_core, project_root.to_owned())?;
+
+        assert_eq!(pkg_tag.unwrap().tag, String::from("refs/tags/pkg@1.0.0"));
+        assert_eq!(
+            pkg_core_tag.unwrap().tag,
+            String::from("refs/tags/pkg-core@1.0.0")
+        );
+
+        pkg.version = String::from("2.0.0");
+
+        // The fallback skips the tags that aren't valid semver
+        let pkg_fallback_tag =
+            get_last_known_publish_tag_info_for_package(&pkg, project_root.to_owned())?;
+
+        assert_eq!(
+            pkg_fallback_tag.unwrap().tag,
+            String::from("refs/tags/pkg@1.0.0")
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_changed_files_for_package() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        std::fs::create_dir(monorepo_dir.join("packages/package-a-extra"))?;
+        File::create(monorepo_dir.join("packages/package-a/index.js"))?;
+        File::create(monorepo_dir.join("packages/package-a-extra/index.js"))?;
+        File::create(monorepo_dir.join("packages/package-b/index.js"))?;
+
+        git_add_all(project_root.as_ref().unwrap())?;
+        git_commit(
+            String::from("feat: add index files"),
+            None,
+            None,
+            None,
+            None,
+            project_root.to_owned(),
+        )?;
+
+        let packages = get_packages(project_root.to_owned())?;
+        let package = packages
+            .iter()
+            .find(|package| package.name == "@scope/package-a")
+            .unwrap();
+
+        let result = get_changed_files_for_package(
+            package,
+            String::from("@scope/package-a@1.0.0"),
             project_root,
-            Some(String::from("main")),
-            Some(String::from("packages/package-a")),
         );
-        let count = result.len();
 
-        assert_eq!(count, 0);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ends_with("packages/package-a/index.js"), true);
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_get_local_tags() -> Result<(), std::io::Error> {
+    fn test_commit_paths() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let result = get_remote_or_local_tags(project_root, Some(true));
-        let count = result.len();
+        let ref root = project_root.unwrap().to_string();
+
+        File::create(monorepo_dir.join("packages/package-a/CHANGELOG.md"))?;
+        File::create(monorepo_dir.join("packages/package-b/CHANGELOG.md"))?;
+        File::create(monorepo_dir.join("unrelated.txt"))?;
+
+        let commit_info = commit_paths(
+            vec![
+                String::from("packages/package-a/CHANGELOG.md"),
+                monorepo_dir
+                    .join("packages/package-b/CHANGELOG.md")
+                    .display()
+                    .to_string(),
+            ],
+            String::from("chore: release changelogs"),
+            Some(root.to_string()),
+        )?;
+
+        let head = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("rev-parse")
+            .arg("HEAD")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git rev-parse problem");
+        let head = String::from_utf8(head.wait_with_output()?.stdout)?;
+
+        assert_eq!(commit_info.sha, head.trim());
+        assert_eq!(
+            commit_info.files,
+            vec![
+                String::from("packages/package-a/CHANGELOG.md"),
+                String::from("packages/package-b/CHANGELOG.md"),
+            ]
+        );
+        assert_eq!(git_workdir_unclean(Some(root.to_string())), true);
 
-        assert_eq!(count, 3);
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
     #[test]
-    fn test_git_all_files_changed_since_sha() -> Result<(), std::io::Error> {
+    #[cfg(unix)]
+    fn test_commit_paths_outside_repository() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let result = git_all_files_changed_since_sha(String::from("main"), project_root);
-        let count = result.len();
+        let ref root = project_root.unwrap().to_string();
+        let ref outside_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let outside_file = outside_dir.join("package.json");
 
-        assert_eq!(count, 0);
+        std::os::unix::fs::symlink(&outside_file, monorepo_dir.join("linked.json"))?;
+
+        let sha_before = git_current_sha(Some(root.to_string()));
+
+        for path in [
+            outside_file.display().to_string(),
+            String::from("linked.json"),
+        ] {
+            let result = commit_paths(
+                vec![path],
+                String::from("chore: escape"),
+                Some(root.to_string()),
+            );
+
+            assert_eq!(
+                matches!(result, Err(WorkspaceError::PathOutsideRepository(_))),
+                true
+            );
+        }
+
+        assert_eq!(git_current_sha(Some(root.to_string())), sha_before);
+
+        remove_dir_all(&monorepo_dir)?;
+        remove_dir_all(&outside_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_clone() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref clone_dir = clone_test_monorepo(monorepo_dir)?;
+        let ref clone_root = clone_dir.display().to_string();
+        let ref url = format!("file://{}", monorepo_dir.display());
+
+        let remotes = git_get_remotes(Some(clone_root.to_string()));
+
+        assert_eq!(remotes, vec![(String::from("origin"), url.to_string())]);
+
+        git_add_remote(&String::from("upstream"), url, Some(clone_root.to_string()))?;
+        let duplicated =
+            git_add_remote(&String::from("upstream"), url, Some(clone_root.to_string()));
+
+        assert_eq!(git_get_remotes(Some(clone_root.to_string())).len(), 2);
+        assert_eq!(
+            duplicated
+                .unwrap_err()
+                .to_string()
+                .contains("already exists"),
+            true
+        );
+
+        git_config(
+            &String::from("Sublime Machine"),
+            &String::from("machine@websublime.dev"),
+            clone_root,
+        )?;
+
+        let branch = Command::new("git")
+            .current_dir(&clone_dir)
+            .arg("checkout")
+            .arg("-b")
+            .arg("feat/clone")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git checkout problem");
+
+        branch.wait_with_output()?;
+
+        let mut js_file = File::create(clone_dir.join("packages/package-a/index.js"))?;
+        js_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+
+        git_add_all(clone_root)?;
+        git_commit(
+            String::from("feat: message to the world"),
+            None,
+            None,
+            None,
+            None,
+            Some(clone_root.to_string()),
+        )?;
+
+        let packages = get_changed_packages(
+            Some(String::from("main")),
+            Some(clone_root.to_string()),
+            &ChangedPackagesOptions::default(),
+        )?;
+
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, String::from("@scope/package-a"));
+        remove_dir_all(&clone_dir)?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_clone_unknown_branch() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let ref clone_dir = monorepo_dir.with_file_name(format!(
+            "{}-clone",
+            monorepo_dir.file_name().unwrap().to_str().unwrap()
+        ));
+
+        let result = git_clone(
+            &format!("file://{}", monorepo_dir.display()),
+            clone_dir,
+            &CloneOptions {
+                depth: Some(1),
+                branch: Some(String::from("unknown")),
+                single_branch: Some(true),
+                filter: None,
+            },
+        );
+
+        match result {
+            Err(GitError::Command(stderr)) => assert_eq!(stderr.contains("unknown"), true),
+            _ => panic!("Clone of an unknown branch should fail"),
+        }
+
+        assert_eq!(clone_dir.exists(), false);
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
+
+    #[test]
+    fn test_publish_tag_format() -> Result<(), Box<dyn std::error::Error>> {
+        let ref tag_format = Some(String::from("{name}/v{version}"));
+
+        assert_eq!(
+            format_publish_tag(&None, "@scope/package-a", "1.1.0"),
+            String::from("@scope/package-a@1.1.0")
+        );
+        assert_eq!(
+            format_publish_tag(tag_format, "@scope/package-a", "1.1.0"),
+            String::from("@scope/package-a/v1.1.0")
+        );
+        assert_eq!(is_valid_tag_format("{name}/v{version}"), true);
+        assert_eq!(is_valid_tag_format("v{version}"), false);
+        assert_eq!(is_valid_tag_format("{name}{version}"), false);
+
+        let tags = normalize_publish_tags(
+            vec![
+                RemoteTags {
+                    hash: String::from("a1b2c3"),
+                    tag: String::from("refs/tags/@scope/package-a/v1.1.0^{}"),
+                },
+                RemoteTags {
+                    hash: String::from("d4e5f6"),
+                    tag: String::from("refs/tags/@scope/package-b@1.0.0"),
+                },
+            ],
+            tag_format,
+        );
+
+        assert_eq!(
+            tags[0].tag,
+            String::from("refs/tags/@scope/package-a@1.1.0^{}")
+        );
+        assert_eq!(
+            tags[1].tag,
+            String::from("refs/tags/@scope/package-b@1.0.0")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_json_format() -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::json!({
+            "hash": "0f7412d",
+            "author_name": "Sublime Machine",
+            "author_email": "machine@websublime.dev",
+            "author_date": "Thu, 1 Jan 2026 10:00:00 +0000",
+            "message": "feat: route deploys\n\nDeploy: staging",
+            "subject": "feat: route deploys",
+            "body": "Deploy: staging",
+            "trailers": [{ "key": "Deploy", "value": "staging" }]
+        });
+
+        let commit = serde_json::from_value::<Commit>(json.to_owned())?;
+
+        assert_eq!(commit.trailers[0].key, String::from("Deploy"));
+        assert_eq!(serde_json::to_value(&commit)?, json);
+        Ok(())
+    }
 }
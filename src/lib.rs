@@ -3,6 +3,10 @@
 //!
 //! This crate provides a set of tools to work with node workspaces.
 //! It allows you to retrieve information about the workspace, and to interact with the workspace.
+//!
+//! Structs serialize to json with their Rust field names (snake_case), with or without the
+//! `napi` feature, so persisted json is readable by both builds. The camelCase of the napi
+//! objects only applies to the JavaScript values.
 #[allow(unused_imports)]
 #[macro_use]
 #[cfg(feature = "napi-derive")]
@@ -24,4 +28,12 @@ pub mod bumps;
 
 pub mod changes;
 
+pub mod versions;
+
+pub mod config;
+
 pub mod dependency;
+
+pub mod reports;
+
+pub mod errors;
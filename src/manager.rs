@@ -2,9 +2,9 @@
 //!
 //! This package ables to detect which package manager is being used in the monorepo.
 #![allow(clippy::all)]
-use std::{
-    collections::HashMap, fmt::Display, fmt::Formatter, fmt::Result as FmtResult, path::Path,
-};
+use std::{fmt::Display, fmt::Formatter, fmt::Result as FmtResult, path::Path};
+
+use super::git::GitError;
 
 #[cfg(feature = "napi")]
 #[napi(string_enum)]
@@ -39,36 +39,90 @@ impl Display for PackageManager {
     }
 }
 
+#[derive(Debug)]
+/// An error that represents a failed package manager command, e.g. `pnpm list`
+pub enum PackageManagerError {
+    Command(String),
+    Timeout(String),
+    OutputTooLarge(String),
+}
+
+impl Display for PackageManagerError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            PackageManagerError::Command(stderr) => {
+                write!(f, "Package manager command failed: {}", stderr)
+            }
+            PackageManagerError::Timeout(command) => {
+                write!(f, "Package manager command timed out: {}", command)
+            }
+            PackageManagerError::OutputTooLarge(command) => {
+                write!(f, "Package manager command output too large: {}", command)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackageManagerError {}
+
+impl From<GitError> for PackageManagerError {
+    fn from(error: GitError) -> Self {
+        match error {
+            GitError::Timeout(command) => PackageManagerError::Timeout(command),
+            GitError::OutputTooLarge(command) => PackageManagerError::OutputTooLarge(command),
+            GitError::Command(stderr) => PackageManagerError::Command(stderr),
+            error => PackageManagerError::Command(error.to_string()),
+        }
+    }
+}
+
+/// Lock files of every package manager, in detection order.
+const PACKAGE_MANAGER_FILES: [(&str, PackageManager); 6] = [
+    ("package-lock.json", PackageManager::Npm),
+    ("npm-shrinkwrap.json", PackageManager::Npm),
+    ("yarn.lock", PackageManager::Yarn),
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("bun.lockb", PackageManager::Bun),
+    ("bun.lock", PackageManager::Bun),
+];
+
 /// Detects which package manager is available in the workspace.
+/// When several lock files coexist, the first one in detection order wins
+/// (see `detect_all_package_managers` to find out about the ambiguity).
 pub fn detect_package_manager(path: &Path) -> Option<PackageManager> {
-    let package_manager_files = HashMap::from([
-        ("package-lock.json", PackageManager::Npm),
-        ("npm-shrinkwrap.json", PackageManager::Npm),
-        ("yarn.lock", PackageManager::Yarn),
-        ("pnpm-lock.yaml", PackageManager::Pnpm),
-        ("bun.lockb", PackageManager::Bun),
-    ]);
-
-    for (file, package_manager) in package_manager_files.iter() {
+    detect_all_package_managers(path).into_iter().next()
+}
+
+/// Detects every package manager with a lock file in the nearest directory (walking up from
+/// path) that has one. More than one result means leftover lock files, e.g. after a migration.
+pub fn detect_all_package_managers(path: &Path) -> Vec<PackageManager> {
+    let mut package_managers = vec![];
+
+    for (file, package_manager) in PACKAGE_MANAGER_FILES.iter() {
         let lock_file = path.join(file);
 
-        if lock_file.exists() {
-            return Some(*package_manager);
+        if lock_file.exists() && !package_managers.contains(package_manager) {
+            package_managers.push(*package_manager);
         }
     }
 
-    if let Some(parent) = path.parent() {
-        return detect_package_manager(&parent);
+    if package_managers.is_empty() {
+        if let Some(parent) = path.parent() {
+            return detect_all_package_managers(&parent);
+        }
     }
 
-    None
+    package_managers
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{paths::get_project_root_path, utils::create_test_monorepo};
-    use std::{fs::remove_dir_all, path::PathBuf};
+    use std::{
+        fs::{remove_dir_all, File},
+        path::PathBuf,
+    };
 
     #[test]
     fn package_manager_for_npm_lock() -> Result<(), std::io::Error> {
@@ -138,4 +192,26 @@ mod tests {
 
         assert_eq!(package_manager.unwrap().to_string(), String::from(""));
     }
+
+    #[test]
+    fn package_managers_for_multiple_locks() -> Result<(), std::io::Error> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        File::create(monorepo_dir.join("pnpm-lock.yaml"))?;
+
+        let ref root = PathBuf::from(project_root.unwrap());
+        let package_managers = detect_all_package_managers(root.as_path());
+
+        assert_eq!(
+            package_managers,
+            vec![PackageManager::Npm, PackageManager::Pnpm]
+        );
+        assert_eq!(
+            detect_package_manager(root.as_path()),
+            Some(PackageManager::Npm)
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
 }
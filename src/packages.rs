@@ -3,20 +3,35 @@
 //! #Packages module
 //!
 //! The `packages` module is used to get the list of packages available in the monorepo.
-use execute::Execute;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
 use wax::{CandidatePath, Glob, Pattern};
 
+use super::conventional::CHANGELOG_FILE;
 use super::dependency::Node;
-use super::git::get_all_files_changed_since_branch;
-use super::manager::{detect_package_manager, PackageManager};
-use super::paths::get_project_root_path;
+use super::git::{
+    execute_with_limits, get_all_files_changed_since_branch, git_files_changed_in_commit,
+    git_show_file, CommandLimits,
+};
+use super::manager::{detect_package_manager, PackageManager, PackageManagerError};
+use super::paths::{get_project_root_path, is_within, nearest_package_root, to_workspace_relative};
+
+#[derive(Debug, Deserialize, Serialize)]
+/// The packages cache, valid while the fingerprint of the discovery inputs is unchanged.
+struct PackagesCache {
+    pub fingerprint: BTreeMap<String, String>,
+    pub packages: Vec<PackageInfo>,
+}
 
 #[derive(Debug, Deserialize, Serialize)]
 /// A struct that represents a pnpm workspace.
@@ -27,9 +42,27 @@ struct PnpmInfo {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-/// A struct that represents a yarn workspace.
+/// A struct that represents a yarn, npm or bun workspace.
 struct PkgJson {
-    pub workspaces: Vec<String>,
+    pub workspaces: Workspaces,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+/// The `workspaces` field of the root package.json. It is either a list of globs or
+/// an object with a `packages` list (yarn nohoist and bun catalogs live in the same object).
+enum Workspaces {
+    Globs(Vec<String>),
+    Config { packages: Vec<String> },
+}
+
+impl Workspaces {
+    fn into_globs(self) -> Vec<String> {
+        match self {
+            Workspaces::Globs(globs) => globs,
+            Workspaces::Config { packages } => packages,
+        }
+    }
 }
 
 #[cfg(feature = "napi")]
@@ -101,6 +134,72 @@ pub struct DependencyInfo {
     pub version: String,
 }
 
+#[cfg(feature = "napi")]
+#[napi(string_enum)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum DependencyKind {
+    Prod,
+    Dev,
+    Peer,
+    Optional,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, Copy, PartialEq)]
+/// Enum representing the package.json section of a dependency: `dependencies`,
+/// `devDependencies`, `peerDependencies` or `optionalDependencies`.
+pub enum DependencyKind {
+    Prod,
+    Dev,
+    Peer,
+    Optional,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PackageUpdateResult {
+    pub package: String,
+    pub previous: Option<Value>,
+    pub current: Option<Value>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents a package.json field update, with the value before and after it.
+pub struct PackageUpdateResult {
+    pub package: String,
+    pub previous: Option<Value>,
+    pub current: Option<Value>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct ChangedPackagesOptions {
+    pub apply_gitignore: Option<bool>,
+    pub exclude_publish_directory: Option<bool>,
+    pub ignore_release_artifacts: Option<bool>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+/// A struct that represents the options of `get_changed_packages`, all disabled by default.
+pub struct ChangedPackagesOptions {
+    pub apply_gitignore: Option<bool>,
+    pub exclude_publish_directory: Option<bool>,
+    pub ignore_release_artifacts: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Enum representing where a package gets published. `Private` packages are never published,
+/// `Registry` comes from `publishConfig.registry` and `DefaultRegistry` is the configured one.
+pub enum PublishTarget {
+    Private,
+    Registry(String),
+    DefaultRegistry,
+}
+
 impl Node for PackageInfo {
     type DependencyType = DependencyInfo;
 
@@ -109,7 +208,8 @@ impl Node for PackageInfo {
     }
 
     fn matches(&self, dependency: &Self::DependencyType) -> bool {
-        let dependency_version = semver::VersionReq::parse(&dependency.version).unwrap();
+        let ref dependency_spec = resolve_workspace_version(&dependency.version, self);
+        let dependency_version = semver::VersionReq::parse(dependency_spec).unwrap();
         let self_version = semver::Version::parse(&self.version).unwrap();
 
         // Check that name is an exact match, and that the dependency
@@ -129,17 +229,65 @@ impl PackageInfo {
         self.changed_files.to_vec()
     }
 
+    /// Returns the `publishConfig.directory` of the package.json (the build output that gets
+    /// published, relative to the package), `None` when it isn't set.
+    pub fn get_publish_directory(&self) -> Option<String> {
+        self.pkg_json
+            .get("publishConfig")
+            .and_then(|publish_config| publish_config.get("directory"))
+            .and_then(|directory| directory.as_str())
+            .map(|directory| {
+                directory
+                    .trim()
+                    .trim_start_matches("./")
+                    .trim_end_matches('/')
+            })
+            .filter(|directory| !directory.is_empty() && *directory != ".")
+            .map(|directory| directory.to_string())
+    }
+
     /// Extends the list of changed files with the provided list.
     pub fn extend_changed_files(&mut self, files: Vec<String>) {
+        let package = self.to_owned();
+
+        self.extend_changed_files_of(files, &[package]);
+    }
+
+    /// Extends the list of changed files with the files of the provided list owned by the
+    /// package, the ones whose nearest package among `packages` is this one. Files of a
+    /// package nested in this one are left out.
+    pub fn extend_changed_files_of(&mut self, files: Vec<String>, packages: &[PackageInfo]) {
         let founded_files = files
             .iter()
-            .filter(|file| file.starts_with(&self.package_path))
+            .filter(|file| {
+                nearest_package_root(Path::new(file), packages)
+                    .map_or(false, |package| package.package_path == self.package_path)
+            })
             .map(|file| file.to_string())
             .collect::<Vec<String>>();
 
         self.changed_files.extend(founded_files);
     }
 
+    /// Returns the kinds of a dependency, the package.json sections declaring it.
+    pub fn get_dependency_kinds(&self, dependency: &String) -> Vec<DependencyKind> {
+        [
+            ("dependencies", DependencyKind::Prod),
+            ("devDependencies", DependencyKind::Dev),
+            ("peerDependencies", DependencyKind::Peer),
+            ("optionalDependencies", DependencyKind::Optional),
+        ]
+        .into_iter()
+        .filter(|(section, _)| {
+            self.pkg_json
+                .get(section)
+                .and_then(|dependencies| dependencies.as_object())
+                .map_or(false, |dependencies| dependencies.contains_key(dependency))
+        })
+        .map(|(_, kind)| kind)
+        .collect::<Vec<DependencyKind>>()
+    }
+
     pub fn push_dependency(&mut self, dependency: DependencyInfo) {
         self.dependencies.push(dependency);
     }
@@ -150,6 +298,11 @@ impl PackageInfo {
         self.pkg_json["version"] = Value::String(version.to_string());
     }
 
+    /// Updates the `publishConfig.tag` of the package, the npm dist-tag it gets published under.
+    pub fn update_publish_tag(&mut self, tag: String) {
+        self.pkg_json["publishConfig"]["tag"] = Value::String(tag);
+    }
+
     /// Updates a dependency version in the package.json file.
     pub fn update_dependency_version(&mut self, dependency: String, version: String) {
         let package_json = self.pkg_json.as_object().unwrap();
@@ -187,37 +340,41 @@ impl PackageInfo {
     }
 }
 
-/// Returns package info domain, scope and repository name.
-fn get_package_repository_info(url: &String) -> PackageRepositoryInfo {
+/// Returns package info domain, scope and repository name, None when the url isn't a
+/// `<protocol>://<domain>/<org>/<project>` url (e.g. a local path remote).
+pub(crate) fn get_package_repository_info(url: &String) -> Option<PackageRepositoryInfo> {
     let regex = Regex::new(
         r"(?m)((?<protocol>[a-z]+)://)((?<domain>[^/]*)/)(?<org>([^/]*)/)(?<project>(.*))(\.git)?",
     )
     .unwrap();
 
-    let captures = regex.captures(url).unwrap();
-    let domain = captures.name("domain").unwrap().as_str();
-    let orga = captures.name("org").unwrap().as_str();
-    let project = captures.name("project").unwrap().as_str();
+    let captures = regex.captures(url)?;
+    let domain = captures.name("domain")?.as_str();
+    let orga = captures.name("org")?.as_str();
+    let project = captures.name("project")?.as_str();
 
-    PackageRepositoryInfo {
+    Some(PackageRepositoryInfo {
         domain: domain.to_string().replace("/", ""),
         orga: orga.to_string().replace("/", ""),
         project: project.to_string().replace("/", "").replace(".git", ""),
-    }
+    })
 }
 
 /// Returns the package info of the package with the provided name.
-pub fn get_package_info(package_name: String, cwd: Option<String>) -> Option<PackageInfo> {
+pub fn get_package_info(
+    package_name: String,
+    cwd: Option<String>,
+) -> Result<Option<PackageInfo>, PackageManagerError> {
     let project_root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let packages = get_packages(Some(project_root));
+    let packages = get_packages(Some(project_root))?;
 
-    packages
+    Ok(packages
         .into_iter()
-        .find(|package| package.name == package_name)
+        .find(|package| package.name == package_name))
 }
 
 /// Get defined package manager in the monorepo
@@ -232,12 +389,231 @@ pub fn get_monorepo_package_manager(cwd: Option<String>) -> Option<PackageManage
     detect_package_manager(&path)
 }
 
-/// Get a list of packages available in the monorepo
-pub fn get_packages(cwd: Option<String>) -> Vec<PackageInfo> {
+/// Get a list of packages available in the monorepo. Fails when listing the pnpm packages
+/// fails or breaches the command limits.
+pub fn get_packages(cwd: Option<String>) -> Result<Vec<PackageInfo>, PackageManagerError> {
+    get_packages_with_cache(cwd, None)
+}
+
+/// Get a list of packages available in the monorepo, listing the pnpm packages within the
+/// given limits. Fails with `PackageManagerError::Timeout` or
+/// `PackageManagerError::OutputTooLarge` when they're breached.
+pub fn get_packages_with_limits(
+    cwd: Option<String>,
+    limits: &CommandLimits,
+) -> Result<Vec<PackageInfo>, PackageManagerError> {
+    let project_root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    discover_packages(&project_root, limits)
+}
+
+/// Get a list of packages available in the monorepo, within the local command limits of the
+/// project (see `CommandLimits::local`). With `package_cache` the packages are
+/// cached in the temp directory and reused while the root manifests (package.json and
+/// pnpm-workspace.yaml) hash, the package.json files, their parent directories and the
+/// workspace glob roots are unchanged. Corrupt or unreadable caches are ignored and the
+/// packages discovered again.
+pub fn get_packages_with_cache(
+    cwd: Option<String>,
+    package_cache: Option<bool>,
+) -> Result<Vec<PackageInfo>, PackageManagerError> {
     let project_root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
+    let ref limits = CommandLimits::local(&Some(project_root.to_string()));
+
+    if !package_cache.unwrap_or(false) {
+        return discover_packages(&project_root, limits);
+    }
+
+    let ref cache_path = get_packages_cache_path(&project_root);
+
+    let cache = std::fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<PackagesCache>(&content).ok());
+
+    if let Some(cache) = cache {
+        if cache.fingerprint == get_packages_fingerprint(&project_root, &cache.packages) {
+            return Ok(cache.packages);
+        }
+    }
+
+    let packages = discover_packages(&project_root, limits)?;
+    let cache = PackagesCache {
+        fingerprint: get_packages_fingerprint(&project_root, &packages),
+        packages,
+    };
+
+    // A cache that can't be written only means discovering again next time
+    if let Ok(content) = serde_json::to_string(&cache) {
+        let _ = std::fs::write(cache_path, content);
+    }
+
+    Ok(cache.packages)
+}
+
+/// Path of the packages cache of a project, in the temp directory so it never shows up
+/// as a change in the repository.
+fn get_packages_cache_path(project_root: &String) -> PathBuf {
+    let root_hash = format!("{:x}", Sha256::digest(project_root.as_bytes()));
+
+    std::env::temp_dir().join(format!("workspace-packages-{}.json", &root_hash[..16]))
+}
+
+/// Fingerprint of the discovery inputs: the root manifests content hash and the modification
+/// time and size of every package.json, of the directories holding the packages and of the
+/// workspace glob roots and their sub directories, which change when a package is added or
+/// removed, even the first one of a new or empty workspace directory.
+fn get_packages_fingerprint(
+    project_root: &String,
+    packages: &Vec<PackageInfo>,
+) -> BTreeMap<String, String> {
+    let path = Path::new(project_root);
+    let mut fingerprint = BTreeMap::new();
+
+    for manifest in ["package.json", "pnpm-workspace.yaml"] {
+        if let Ok(content) = std::fs::read(path.join(manifest)) {
+            fingerprint.insert(
+                manifest.to_string(),
+                format!("{:x}", Sha256::digest(&content)),
+            );
+        }
+    }
+
+    let mut entries = vec![];
+
+    for package in packages {
+        entries.push(PathBuf::from(&package.package_json_path));
+
+        if let Some(packages_dir) = Path::new(&package.package_path).parent() {
+            entries.push(packages_dir.to_path_buf());
+        }
+    }
+
+    for glob_root in get_workspace_glob_roots(path, &get_workspace_entries(path)) {
+        // A package.json added to an existing directory only changes that directory
+        if let Ok(read_dir) = std::fs::read_dir(&glob_root) {
+            entries.extend(
+                read_dir
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| {
+                        let name = entry.file_name().to_string_lossy().to_string();
+
+                        !name.starts_with('.') && name != "node_modules"
+                    })
+                    .map(|entry| entry.path())
+                    .filter(|entry| entry.is_dir()),
+            );
+        }
+
+        entries.push(glob_root);
+    }
+
+    for entry in entries {
+        let stamp = match std::fs::metadata(&entry) {
+            Ok(metadata) => {
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                    .map(|modified| modified.as_nanos())
+                    .unwrap_or(0);
+
+                format!("{}:{}", modified, metadata.len())
+            }
+            Err(_) => String::from("missing"),
+        };
+
+        fingerprint.insert(entry.display().to_string(), stamp);
+    }
+
+    fingerprint
+}
+
+/// Workspace entries of the project, the `packages` of the pnpm-workspace.yaml or else the
+/// `workspaces` of the root package.json. None when neither can be read.
+fn get_workspace_entries(project_root: &Path) -> Vec<String> {
+    if let Ok(content) = std::fs::read_to_string(project_root.join("pnpm-workspace.yaml")) {
+        return parse_pnpm_workspace_packages(&content);
+    }
+
+    std::fs::read_to_string(project_root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<PkgJson>(&content).ok())
+        .map(|PkgJson { workspaces }| workspaces.into_globs())
+        .unwrap_or_default()
+}
+
+/// Reads the `packages` list of a pnpm-workspace.yaml, written as a block sequence
+/// (`- "packages/*"`) like pnpm documents it.
+fn parse_pnpm_workspace_packages(content: &str) -> Vec<String> {
+    let mut in_packages = false;
+    let mut packages = vec![];
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match line.strip_prefix('-') {
+            Some(entry) if in_packages => packages.push(
+                entry
+                    .trim()
+                    .trim_matches(|quote| quote == '"' || quote == '\'')
+                    .to_string(),
+            ),
+            _ => in_packages = line == "packages:",
+        }
+    }
+
+    packages
+}
+
+/// Directories the workspace entries discover packages in: the path before the first glob
+/// component of each included entry (`packages` for `packages/*`, the project root for `**`),
+/// or the package directory of an entry without glob.
+fn get_workspace_glob_roots(project_root: &Path, workspaces: &Vec<String>) -> Vec<PathBuf> {
+    let mut glob_roots = workspaces
+        .iter()
+        .map(|workspace| workspace.trim().replace("\\", "/"))
+        .filter(|workspace| !workspace.starts_with('!'))
+        .map(|workspace| {
+            workspace
+                .split('/')
+                .take_while(|component| !component.contains(&['*', '?', '[', '{'][..]))
+                .filter(|component| !component.is_empty() && *component != ".")
+                .fold(project_root.to_path_buf(), |glob_root, component| {
+                    glob_root.join(component)
+                })
+        })
+        .collect::<Vec<PathBuf>>();
+
+    glob_roots.sort();
+    glob_roots.dedup();
+    glob_roots
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of package discoveries (workspace walks) run by the current test thread.
+    static PACKAGE_DISCOVERIES: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// Discovers the packages of the monorepo, walking the workspaces and reading every package.json
+fn discover_packages(
+    project_root: &String,
+    limits: &CommandLimits,
+) -> Result<Vec<PackageInfo>, PackageManagerError> {
+    #[cfg(test)]
+    PACKAGE_DISCOVERIES.with(|discoveries| discoveries.set(discoveries.get() + 1));
+
+    let project_root = project_root.to_string();
     let package_manager = get_monorepo_package_manager(Some(project_root.to_string()));
 
     let mut packages = match package_manager {
@@ -246,7 +622,9 @@ pub fn get_packages(cwd: Option<String>) -> Vec<PackageInfo> {
             let pnpm_workspace = path.join("pnpm-workspace.yaml");
 
             if !pnpm_workspace.as_path().exists() {
-                panic!("pnpm-workspace.yaml file not found");
+                return Err(PackageManagerError::Command(String::from(
+                    "pnpm-workspace.yaml file not found",
+                )));
             }
 
             let mut command = Command::new("pnpm");
@@ -258,12 +636,17 @@ pub fn get_packages(cwd: Option<String>) -> Vec<PackageInfo> {
                 .arg("-1")
                 .arg("--json");
 
-            command.stdout(Stdio::piped());
-            command.stderr(Stdio::piped());
+            // A runaway listing is killed instead of exhausting the memory
+            let output = execute_with_limits(&mut command, limits)?;
+
+            if !output.status.success() {
+                return Err(PackageManagerError::Command(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ));
+            }
 
-            let output = command.execute_output().unwrap();
-            let pnpm_info =
-                serde_json::from_slice::<Vec<PnpmInfo>>(&output.stdout.as_slice()).unwrap();
+            let pnpm_info = serde_json::from_slice::<Vec<PnpmInfo>>(&output.stdout.as_slice())
+                .map_err(|error| PackageManagerError::Command(error.to_string()))?;
 
             pnpm_info
                 .iter()
@@ -310,10 +693,8 @@ pub fn get_packages(cwd: Option<String>) -> Vec<PackageInfo> {
                     let relative_path = match is_root {
                         true => String::from("."),
                         false => {
-                            let mut rel =
-                                info.path.strip_prefix(&project_root).unwrap().to_string();
-                            rel.remove(0);
-                            rel
+                            to_workspace_relative(Path::new(&info.path), Path::new(&project_root))
+                                .unwrap()
                         }
                     };
 
@@ -331,7 +712,7 @@ pub fn get_packages(cwd: Option<String>) -> Vec<PackageInfo> {
                         root: is_root,
                         version: version.to_string(),
                         url: String::from(repo_url),
-                        repository_info: Some(repository_info),
+                        repository_info,
                         changed_files: vec![],
                         dependencies: vec![],
                     }
@@ -339,141 +720,110 @@ pub fn get_packages(cwd: Option<String>) -> Vec<PackageInfo> {
                 .filter(|pkg| !pkg.root)
                 .collect::<Vec<PackageInfo>>()
         }
-        Some(PackageManager::Yarn) | Some(PackageManager::Npm) => {
+        Some(PackageManager::Yarn) | Some(PackageManager::Npm) | Some(PackageManager::Bun) => {
             let path = Path::new(&project_root);
             let package_json = path.join("package.json");
             let mut packages = vec![];
 
             let package_json = std::fs::read_to_string(&package_json).unwrap();
 
-            let PkgJson { mut workspaces, .. } =
+            let PkgJson { workspaces, .. } =
                 serde_json::from_str::<PkgJson>(&package_json).unwrap();
+            let workspaces = workspaces.into_globs();
 
-            let globs = workspaces
-                .iter_mut()
-                .map(|workspace| {
-                    return match workspace.ends_with("/*") {
-                        true => {
-                            workspace.push_str("*/package.json");
-                            Glob::new(workspace).unwrap()
-                        }
-                        false => {
-                            workspace.push_str("/package.json");
-                            Glob::new(workspace).unwrap()
-                        }
-                    };
-                })
-                .collect::<Vec<Glob>>();
-
-            let patterns = wax::any(globs).unwrap();
-
-            let glob = Glob::new("**/package.json").unwrap();
-
-            for entry in glob
-                .walk(path)
-                .not([
-                    "**/node_modules/**",
-                    "**/src/**",
-                    "**/dist/**",
-                    "**/tests/**",
-                ])
-                .unwrap()
-            {
-                let entry = entry.unwrap();
-                let rel_path = entry
-                    .path()
+            for package_json_path in get_workspace_package_json_paths(path, &workspaces) {
+                let rel_path = package_json_path
                     .strip_prefix(&path)
                     .unwrap()
                     .display()
                     .to_string();
-                //rel_path.remove(0);
-
-                if patterns.is_match(CandidatePath::from(
-                    entry.path().strip_prefix(&path).unwrap(),
-                )) {
-                    let package_json_file = std::fs::File::open(&entry.path()).unwrap();
-                    let package_json_reader = std::io::BufReader::new(package_json_file);
-                    let pkg_json: serde_json::Value =
-                        serde_json::from_reader(package_json_reader).unwrap();
 
-                    let private = match pkg_json.get("private") {
-                        Some(private) => {
-                            if private.is_boolean() {
-                                private.as_bool().unwrap()
-                            } else {
-                                false
-                            }
+                let package_json_file = std::fs::File::open(&package_json_path).unwrap();
+                let package_json_reader = std::io::BufReader::new(package_json_file);
+                let pkg_json: serde_json::Value =
+                    serde_json::from_reader(package_json_reader).unwrap();
+
+                let private = match pkg_json.get("private") {
+                    Some(private) => {
+                        if private.is_boolean() {
+                            private.as_bool().unwrap()
+                        } else {
+                            false
                         }
-                        None => false,
-                    };
-
-                    let ref version = match pkg_json.get("version") {
-                        Some(version) => {
-                            if version.is_string() {
-                                version.as_str().unwrap().to_string()
-                            } else {
-                                String::from("0.0.0")
-                            }
+                    }
+                    None => false,
+                };
+
+                let ref version = match pkg_json.get("version") {
+                    Some(version) => {
+                        if version.is_string() {
+                            version.as_str().unwrap().to_string()
+                        } else {
+                            String::from("0.0.0")
                         }
-                        None => String::from("0.0.0"),
-                    };
+                    }
+                    None => String::from("0.0.0"),
+                };
 
-                    let ref repo_url = match pkg_json.get("repository") {
-                        Some(repository) => {
-                            if repository.is_object() {
-                                let repo = repository.as_object().unwrap();
+                let ref repo_url = match pkg_json.get("repository") {
+                    Some(repository) => {
+                        if repository.is_object() {
+                            let repo = repository.as_object().unwrap();
 
-                                match repo.get("url") {
-                                    Some(url) => url.as_str().unwrap().to_string(),
-                                    None => String::from("https://github.com/my-orga/my-repo"),
-                                }
-                            } else if repository.is_string() {
-                                repository.as_str().unwrap().to_string()
-                            } else {
-                                String::from("https://github.com/my-orga/my-repo")
+                            match repo.get("url") {
+                                Some(url) => url.as_str().unwrap().to_string(),
+                                None => String::from("https://github.com/my-orga/my-repo"),
                             }
+                        } else if repository.is_string() {
+                            repository.as_str().unwrap().to_string()
+                        } else {
+                            String::from("https://github.com/my-orga/my-repo")
                         }
-                        None => String::from("https://github.com/my-orga/my-repo"),
-                    };
-
-                    let name = match pkg_json.get("name") {
-                        Some(name) => {
-                            if name.is_string() {
-                                name.as_str().unwrap().to_string()
-                            } else {
-                                String::from("unknown")
-                            }
+                    }
+                    None => String::from("https://github.com/my-orga/my-repo"),
+                };
+
+                let name = match pkg_json.get("name") {
+                    Some(name) => {
+                        if name.is_string() {
+                            name.as_str().unwrap().to_string()
+                        } else {
+                            String::from("unknown")
                         }
-                        None => String::from("unknown"),
-                    };
-
-                    let repository_info = get_package_repository_info(repo_url);
-
-                    let pkg_info = PackageInfo {
-                        name: name.to_string(),
-                        private,
-                        package_json_path: entry.path().to_str().unwrap().to_string(),
-                        package_path: entry.path().parent().unwrap().to_str().unwrap().to_string(),
-                        package_relative_path: rel_path
-                            .strip_suffix("/package.json")
-                            .unwrap()
-                            .to_string(),
-                        pkg_json,
-                        root: false,
-                        version: version.to_string(),
-                        url: repo_url.to_string(),
-                        repository_info: Some(repository_info),
-                        changed_files: vec![],
-                        dependencies: vec![],
-                    };
-
-                    packages.push(pkg_info);
-                }
+                    }
+                    None => String::from("unknown"),
+                };
+
+                let repository_info = get_package_repository_info(repo_url);
+
+                let pkg_info = PackageInfo {
+                    name: name.to_string(),
+                    private,
+                    package_json_path: package_json_path.to_str().unwrap().to_string(),
+                    package_path: package_json_path
+                        .parent()
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_string(),
+                    package_relative_path: rel_path
+                        .strip_suffix("/package.json")
+                        .unwrap()
+                        .to_string(),
+                    pkg_json,
+                    root: false,
+                    version: version.to_string(),
+                    url: repo_url.to_string(),
+                    repository_info,
+                    changed_files: vec![],
+                    dependencies: vec![],
+                };
+
+                packages.push(pkg_info);
             }
 
             packages
         }
-        Some(PackageManager::Bun) => vec![],
         None => vec![],
     };
 
@@ -512,28 +862,162 @@ pub fn get_packages(cwd: Option<String>) -> Vec<PackageInfo> {
         }
     }
 
-    packages
+    Ok(packages)
+}
+
+/// Normalizes workspace entries into package.json globs, returning the included and the
+/// negated (`!`) ones. `./` prefixes and trailing slashes are dropped, `dir/*` matches direct
+/// children, `dir/**` matches any depth and anything else is an explicit package directory.
+fn normalize_workspace_globs(workspaces: &Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut included = vec![];
+    let mut excluded = vec![];
+
+    for workspace in workspaces {
+        let workspace = workspace.trim();
+        let (negated, workspace) = match workspace.strip_prefix('!') {
+            Some(workspace) => (true, workspace.trim()),
+            None => (false, workspace),
+        };
+
+        let mut workspace = workspace.replace("\\", "/");
+
+        while let Some(stripped) = workspace.strip_prefix("./") {
+            workspace = stripped.to_string();
+        }
+
+        let workspace = workspace.trim_end_matches('/');
+
+        let globs = match workspace {
+            "" | "." => vec![],
+            "**" => vec![String::from("*/**/package.json")],
+            _ => match workspace.strip_suffix("/**") {
+                Some(base) => vec![format!("{}/*/**/package.json", base)],
+                None => vec![format!("{}/package.json", workspace)],
+            },
+        };
+
+        match negated {
+            true => excluded.extend(globs),
+            false => included.extend(globs),
+        }
+    }
+
+    (included, excluded)
+}
+
+/// Walks the project root returning the package.json files matched by the workspace entries.
+fn get_workspace_package_json_paths(root: &Path, workspaces: &Vec<String>) -> Vec<PathBuf> {
+    let (included, excluded) = normalize_workspace_globs(workspaces);
+
+    if included.is_empty() {
+        return vec![];
+    }
+
+    let included = wax::any(
+        included
+            .iter()
+            .map(|glob| Glob::new(glob).unwrap())
+            .collect::<Vec<Glob>>(),
+    )
+    .unwrap();
+    let excluded = match excluded.is_empty() {
+        true => None,
+        false => Some(
+            wax::any(
+                excluded
+                    .iter()
+                    .map(|glob| Glob::new(glob).unwrap())
+                    .collect::<Vec<Glob>>(),
+            )
+            .unwrap(),
+        ),
+    };
+
+    let mut package_json_paths = Glob::new("**/package.json")
+        .unwrap()
+        .walk(root)
+        .not([
+            "**/node_modules/**",
+            "**/src/**",
+            "**/dist/**",
+            "**/tests/**",
+        ])
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let relative_path = CandidatePath::from(entry.path().strip_prefix(root).unwrap());
+
+            included.is_match(relative_path.clone())
+                && !excluded
+                    .as_ref()
+                    .map(|excluded| excluded.is_match(relative_path))
+                    .unwrap_or(false)
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .collect::<Vec<PathBuf>>();
+
+    package_json_paths.sort();
+    package_json_paths
 }
 
-/// Get a list of packages that have changed since a given sha
-pub fn get_changed_packages(sha: Option<String>, cwd: Option<String>) -> Vec<PackageInfo> {
+/// Get a list of packages that have changed since a given sha, branch or tag.
+/// With the `apply_gitignore` option, changed files matched by the .gitignore rules (e.g. build
+/// artifacts committed by mistake) don't mark their package as changed, and with
+/// `exclude_publish_directory` neither do the files in the package publish directory.
+/// With `ignore_release_artifacts`, packages whose only changes are release artifacts
+/// (see `is_release_artifact`) aren't changed.
+pub fn get_changed_packages(
+    sha: Option<String>,
+    cwd: Option<String>,
+    options: &ChangedPackagesOptions,
+) -> Result<Vec<PackageInfo>, PackageManagerError> {
     let root = match cwd {
         Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
         None => get_project_root_path(None).unwrap(),
     };
 
-    let packages = get_packages(Some(root.to_string()));
+    let packages = get_packages(Some(root.to_string()))?;
     let since = sha.unwrap_or(String::from("main"));
 
     let changed_files =
         get_all_files_changed_since_branch(&packages, &since, Some(root.to_string()));
+    let changed_files = match options.apply_gitignore.unwrap_or(false) {
+        true => filter_gitignored_files(&root, changed_files),
+        false => changed_files,
+    };
 
-    packages
+    let changed_packages = packages
         .iter()
         .flat_map(|pkg| {
-            let mut pkgs = changed_files
+            let publish_directory = match options.exclude_publish_directory.unwrap_or(false) {
+                true => pkg
+                    .get_publish_directory()
+                    .map(|directory| Path::new(&pkg.package_path).join(directory)),
+                false => None,
+            };
+
+            let package_files = changed_files
+                .iter()
+                .filter(|file| {
+                    nearest_package_root(Path::new(file), &packages)
+                        .map_or(false, |owner| owner.package_path == pkg.package_path)
+                })
+                .filter(|file| match publish_directory {
+                    Some(ref directory) => !is_within(directory, Path::new(file)),
+                    None => true,
+                })
+                .collect::<Vec<&String>>();
+
+            if options.ignore_release_artifacts.unwrap_or(false)
+                && package_files
+                    .iter()
+                    .all(|file| is_release_artifact(file, &since, &root, &None))
+            {
+                return vec![];
+            }
+
+            let mut pkgs = package_files
                 .iter()
-                .filter(|file| file.starts_with(&pkg.package_path))
                 .map(|file| {
                     let mut pkg_info: PackageInfo = pkg.to_owned();
                     pkg_info.push_changed_file(file.to_string());
@@ -546,53 +1030,391 @@ pub fn get_changed_packages(sha: Option<String>, cwd: Option<String>) -> Vec<Pac
 
             pkgs
         })
-        .collect::<Vec<PackageInfo>>()
+        .collect::<Vec<PackageInfo>>();
+
+    Ok(changed_packages)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Get the names of the packages owning the files changed by a single commit, so a commit
+/// spanning several packages can be attributed to all of them.
+pub fn get_packages_touched_by_commit(
+    sha: String,
+    cwd: Option<String>,
+) -> Result<Vec<String>, PackageManagerError> {
+    let root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
 
-    use crate::manager::PackageManager;
-    use crate::utils::create_test_monorepo;
-    use std::fs::{remove_dir_all, File};
-    use std::io::Write;
-    use std::path::PathBuf;
-    use std::process::Command;
+    let changed_files = git_files_changed_in_commit(sha, Some(root.to_string()));
 
-    fn create_package_change(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        let js_path = monorepo_dir.join("packages/package-a/index.js");
+    let ref packages = get_packages(Some(root.to_string()))?;
 
-        let branch = Command::new("git")
-            .current_dir(&monorepo_dir)
-            .arg("checkout")
-            .arg("-b")
-            .arg("feat/message")
-            .stdout(Stdio::piped())
-            .spawn()
-            .expect("Git branch problem");
+    let mut touched = changed_files
+        .iter()
+        .filter_map(|file| nearest_package_root(Path::new(file), packages))
+        .map(|pkg| pkg.name.to_string())
+        .collect::<Vec<String>>();
 
-        branch.wait_with_output()?;
+    touched.sort();
+    touched.dedup();
+    Ok(touched)
+}
 
-        let mut js_file = File::create(&js_path)?;
-        js_file
-            .write_all(r#"export const message = "hello";"#.as_bytes())
-            .unwrap();
+/// Checks if a changed file is an artifact of a release: a changelog (`changelog_file`, default
+/// `CHANGELOG_FILE`), or a package.json where only the version changed since the sha. A release
+/// commit brought to another branch (e.g. cherry picked) only touches these files, the package
+/// didn't really change.
+pub fn is_release_artifact(
+    file: &String,
+    sha: &String,
+    root: &String,
+    changelog_file: &Option<String>,
+) -> bool {
+    let path = Path::new(file);
+    let changelog_file = changelog_file.as_deref().unwrap_or(CHANGELOG_FILE);
+
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) if name == changelog_file => true,
+        Some("package.json") => {
+            let relative_path =
+                to_workspace_relative(path, Path::new(root)).unwrap_or(file.to_string());
+
+            let previous = git_show_file(sha.to_string(), relative_path, Some(root.to_string()))
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+            let current = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+
+            match (previous, current) {
+                (Some(Value::Object(mut previous)), Some(Value::Object(mut current))) => {
+                    previous.remove("version");
+                    current.remove("version");
+
+                    previous == current
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
 
-        let add = Command::new("git")
-            .current_dir(&monorepo_dir)
-            .arg("add")
-            .arg(".")
-            .stdout(Stdio::piped())
-            .spawn()
-            .expect("Git add problem");
+/// Drops the files matched by the .gitignore stack of the repository: every .gitignore from the
+/// file directory up to the root, plus `.git/info/exclude`. As in git, deeper rules win and
+/// negated (`!`) rules keep a file.
+pub fn filter_gitignored_files(root: &String, files: Vec<String>) -> Vec<String> {
+    let root_path = Path::new(root);
+    let mut matchers: HashMap<PathBuf, Gitignore> = HashMap::new();
 
-        add.wait_with_output()?;
+    files
+        .into_iter()
+        .filter(|file| {
+            let file_path = Path::new(file);
 
-        let commit = Command::new("git")
-            .current_dir(&monorepo_dir)
-            .arg("commit")
-            .arg("-m")
+            if !file_path.starts_with(root_path) {
+                return true;
+            }
+
+            for dir in file_path.ancestors().skip(1) {
+                if !dir.starts_with(root_path) {
+                    break;
+                }
+
+                let matcher = matchers.entry(dir.to_path_buf()).or_insert_with(|| {
+                    let mut builder = GitignoreBuilder::new(dir);
+                    builder.add(dir.join(".gitignore"));
+
+                    if dir == root_path {
+                        builder.add(dir.join(".git").join("info").join("exclude"));
+                    }
+
+                    builder.build().unwrap_or(Gitignore::empty())
+                });
+
+                match matcher.matched_path_or_any_parents(file_path, false) {
+                    Match::Ignore(_) => return false,
+                    Match::Whitelist(_) => return true,
+                    Match::None => continue,
+                }
+            }
+
+            true
+        })
+        .collect::<Vec<String>>()
+}
+
+/// Computes a sha256 content hash of the publishable files of a package.
+/// When the package.json declares `files`, only those entries (and package.json) are hashed,
+/// otherwise the whole package directory excluding node_modules and dist is hashed.
+/// Entries that aren't valid glob patterns match nothing and are skipped.
+/// Files are hashed sorted by relative path so the result is stable across runs.
+pub fn compute_package_hash(package_info: &PackageInfo) -> String {
+    let package_path = Path::new(&package_info.package_path);
+
+    let files_entries = match package_info.pkg_json.get("files") {
+        Some(files) => files.as_array().map(|files| {
+            files
+                .iter()
+                .filter_map(|file| file.as_str())
+                .map(|file| file.trim_start_matches("./").trim_end_matches('/'))
+                .filter(|file| !file.is_empty())
+                .flat_map(|file| vec![file.to_string(), format!("{}/**", file)])
+                .chain(vec![String::from("package.json")])
+                .collect::<Vec<String>>()
+        }),
+        None => None,
+    };
+
+    let shipped = files_entries.as_ref().map(|entries| {
+        let globs = entries
+            .iter()
+            .filter_map(|entry| Glob::new(entry).ok())
+            .collect::<Vec<Glob>>();
+
+        wax::any(globs).unwrap()
+    });
+
+    let mut excluded = vec!["**/node_modules/**"];
+
+    if shipped.is_none() {
+        excluded.push("**/dist/**");
+    }
+
+    let mut files = Glob::new("**/*")
+        .unwrap()
+        .walk(package_path)
+        .not(excluded)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            entry
+                .path()
+                .strip_prefix(package_path)
+                .unwrap()
+                .to_path_buf()
+        })
+        .filter(|path| match shipped {
+            Some(ref shipped) => shipped.is_match(CandidatePath::from(path.as_path())),
+            None => true,
+        })
+        .collect::<Vec<PathBuf>>();
+
+    files.sort();
+
+    let mut hasher = Sha256::new();
+
+    for file in files.iter() {
+        let relative_path = file.to_str().unwrap().replace("\\", "/");
+        let content = std::fs::read(package_path.join(file)).unwrap_or_default();
+
+        hasher.update(relative_path.as_bytes());
+        hasher.update([0]);
+        hasher.update(&content);
+        hasher.update([0]);
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// Get the value of a dotted path (e.g. `publishConfig.registry`) in a json value.
+fn get_field(json: &Value, field_path: &str) -> Option<Value> {
+    field_path
+        .split('.')
+        .try_fold(json, |current, key| current.get(key))
+        .cloned()
+}
+
+/// Sets the value of a dotted path in a json value, creating the intermediate objects.
+/// A null value removes the key.
+fn set_field(json: &mut Value, field_path: &str, value: &Value) {
+    let keys = field_path.split('.').collect::<Vec<&str>>();
+    let (last_key, parent_keys) = keys.split_last().unwrap();
+    let mut current = json;
+
+    for key in parent_keys {
+        if value.is_null() {
+            match current.get_mut(*key) {
+                Some(next) => current = next,
+                None => return,
+            }
+        } else {
+            if !current.is_object() {
+                *current = Value::Object(serde_json::Map::new());
+            }
+
+            current = current
+                .as_object_mut()
+                .unwrap()
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        }
+    }
+
+    if value.is_null() {
+        if let Some(object) = current.as_object_mut() {
+            object.remove(*last_key);
+        }
+    } else {
+        if !current.is_object() {
+            *current = Value::Object(serde_json::Map::new());
+        }
+
+        current
+            .as_object_mut()
+            .unwrap()
+            .insert(last_key.to_string(), value.clone());
+    }
+}
+
+/// Sets a field (dotted path, e.g. `publishConfig.registry`) in the package.json of every
+/// package, or only the ones in `filter`. A null value removes the field.
+/// The root manifest is only updated when its name is explicitly part of the filter.
+pub fn update_packages_field(
+    field_path: &str,
+    value: Value,
+    filter: Option<Vec<String>>,
+    cwd: Option<String>,
+) -> Result<Vec<PackageUpdateResult>, PackageManagerError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let is_selected = |name: &String| match filter {
+        Some(ref filter) => filter.contains(name),
+        None => true,
+    };
+
+    let mut results = get_packages(Some(root.to_string()))?
+        .iter_mut()
+        .filter(|package| is_selected(&package.name))
+        .map(|package| {
+            let previous = get_field(&package.pkg_json, field_path);
+
+            set_field(&mut package.pkg_json, field_path, &value);
+            package.write_package_json();
+
+            PackageUpdateResult {
+                package: package.name.to_string(),
+                previous,
+                current: get_field(&package.pkg_json, field_path),
+            }
+        })
+        .collect::<Vec<PackageUpdateResult>>();
+
+    let root_package_json_path = Path::new(root).join("package.json");
+
+    if let Some(ref filter) = filter {
+        if let Ok(root_package_json) = std::fs::read_to_string(&root_package_json_path) {
+            let mut pkg_json = serde_json::from_str::<Value>(&root_package_json).unwrap();
+            let name = pkg_json["name"].as_str().unwrap_or_default().to_string();
+
+            if filter.contains(&name) && !results.iter().any(|result| result.package == name) {
+                let previous = get_field(&pkg_json, field_path);
+
+                set_field(&mut pkg_json, field_path, &value);
+
+                let package_json_file = std::fs::File::create(&root_package_json_path).unwrap();
+                let package_json_writer = std::io::BufWriter::new(package_json_file);
+
+                serde_json::to_writer_pretty(package_json_writer, &pkg_json).unwrap();
+
+                results.push(PackageUpdateResult {
+                    package: name,
+                    previous,
+                    current: get_field(&pkg_json, field_path),
+                });
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Resolves a `workspace:` protocol dependency spec against the workspace package it refers to.
+/// `workspace:*` becomes the package version, `workspace:~` and `workspace:^` prefix it with
+/// the range operator and an explicit range (`workspace:^1.0.0`) drops the protocol.
+/// Specs without the protocol are returned as is.
+pub fn resolve_workspace_version(spec: &str, package_info: &PackageInfo) -> String {
+    let range = match spec.trim().strip_prefix("workspace:") {
+        Some(range) => range.trim(),
+        None => return spec.to_string(),
+    };
+
+    match range {
+        "" | "*" => package_info.version.to_string(),
+        "~" | "^" => format!("{}{}", range, package_info.version),
+        _ => range.to_string(),
+    }
+}
+
+/// Get where the package gets published, from the `private` and `publishConfig.registry`
+/// fields of its package.json. A private package is never published, whatever its registry.
+pub fn publish_target(package: &PackageInfo) -> PublishTarget {
+    if package.private {
+        return PublishTarget::Private;
+    }
+
+    let registry = package
+        .pkg_json
+        .get("publishConfig")
+        .and_then(|publish_config| publish_config.get("registry"))
+        .and_then(|registry| registry.as_str())
+        .map(|registry| registry.trim())
+        .filter(|registry| !registry.is_empty());
+
+    match registry {
+        Some(registry) => PublishTarget::Registry(registry.to_string()),
+        None => PublishTarget::DefaultRegistry,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::manager::PackageManager;
+    use crate::utils::{create_test_monorepo, run_git};
+    use std::fs::{remove_dir_all, File};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+
+    fn create_package_change(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        let js_path = monorepo_dir.join("packages/package-a/index.js");
+
+        let branch = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("checkout")
+            .arg("-b")
+            .arg("feat/message")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git branch problem");
+
+        branch.wait_with_output()?;
+
+        let mut js_file = File::create(&js_path)?;
+        js_file
+            .write_all(r#"export const message = "hello";"#.as_bytes())
+            .unwrap();
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
             .arg("feat: message to the world")
             .stdout(Stdio::piped())
             .spawn()
@@ -620,7 +1442,7 @@ mod tests {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let packages = get_packages(project_root);
+        let packages = get_packages(project_root)?;
 
         assert_eq!(packages.len(), 4);
         remove_dir_all(&monorepo_dir)?;
@@ -632,7 +1454,7 @@ mod tests {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Yarn)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let packages = get_packages(project_root);
+        let packages = get_packages(project_root)?;
 
         assert_eq!(packages.len(), 4);
         remove_dir_all(&monorepo_dir)?;
@@ -644,13 +1466,304 @@ mod tests {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Pnpm)?;
         let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
 
-        let packages = get_packages(project_root);
+        let packages = get_packages(project_root)?;
 
         assert_eq!(packages.len(), 4);
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
 
+    #[test]
+    fn pnpm_get_packages_with_limits() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Pnpm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        // The listing can't finish within no time, it fails instead of panicking
+        let result = get_packages_with_limits(
+            project_root,
+            &CommandLimits {
+                timeout: Some(std::time::Duration::ZERO),
+                max_output_bytes: None,
+            },
+        );
+
+        assert_eq!(result.is_err(), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn pnpm_get_packages_without_workspace() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Pnpm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        std::fs::remove_file(monorepo_dir.join("pnpm-workspace.yaml"))?;
+
+        // A broken workspace is an error of the package manager, not a panic
+        let result = get_packages(project_root);
+
+        assert_eq!(
+            matches!(result, Err(PackageManagerError::Command(ref message)) if message.contains("pnpm-workspace.yaml")),
+            true
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn bun_get_packages() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Bun)?;
+        let ref npm_monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+
+        std::fs::remove_file(monorepo_dir.join("bun.lockb"))?;
+
+        let mut lock_file = File::create(monorepo_dir.join("bun.lock"))?;
+        lock_file.write_all(r#"{ "lockfileVersion": 1, "workspaces": {} }"#.as_bytes())?;
+
+        let mut root_file = File::create(monorepo_dir.join("package.json"))?;
+        root_file.write_all(
+            r#"{
+                "name": "@scope/root",
+                "version": "0.0.0",
+                "workspaces": {
+                    "packages": ["packages/*"],
+                    "catalog": { "react": "^19.0.0" }
+                }
+            }"#
+            .as_bytes(),
+        )?;
+
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let npm_project_root = get_project_root_path(Some(npm_monorepo_dir.to_path_buf()));
+
+        let dependencies_of = |packages: Vec<PackageInfo>| {
+            let mut dependencies = packages
+                .iter()
+                .map(|package| {
+                    let names = package
+                        .dependencies
+                        .iter()
+                        .map(|dependency| dependency.name.to_string())
+                        .collect::<Vec<String>>();
+
+                    (package.name.to_string(), names)
+                })
+                .collect::<Vec<(String, Vec<String>)>>();
+
+            dependencies.sort();
+            dependencies
+        };
+
+        let package_manager = get_monorepo_package_manager(project_root.to_owned());
+        let packages = get_packages(project_root)?;
+        let npm_packages = get_packages(npm_project_root)?;
+
+        assert_eq!(package_manager, Some(PackageManager::Bun));
+        assert_eq!(packages.len(), 4);
+        assert_eq!(dependencies_of(packages), dependencies_of(npm_packages));
+        remove_dir_all(&monorepo_dir)?;
+        remove_dir_all(&npm_monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_packages_with_cache() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+        let discoveries = || PACKAGE_DISCOVERIES.with(|discoveries| discoveries.get());
+        let version_of = |packages: &Vec<PackageInfo>, name: &str| {
+            packages
+                .iter()
+                .find(|package| package.name == name)
+                .map(|package| package.version.to_string())
+        };
+
+        let discoveries_before = discoveries();
+
+        let warm = get_packages_with_cache(Some(root.to_string()), Some(true))?;
+        let cached = get_packages_with_cache(Some(root.to_string()), Some(true))?;
+
+        assert_eq!(warm.len(), 4);
+        assert_eq!(cached, warm);
+        assert_eq!(discoveries() - discoveries_before, 1);
+
+        let package_json_path = monorepo_dir.join("packages/package-a/package.json");
+        let package_json = std::fs::read_to_string(&package_json_path)?
+            .replace(r#""version": "1.0.0""#, r#""version": "1.10.0""#);
+        std::fs::write(&package_json_path, package_json)?;
+
+        let invalidated = get_packages_with_cache(Some(root.to_string()), Some(true))?;
+
+        assert_eq!(discoveries() - discoveries_before, 2);
+        assert_eq!(
+            version_of(&invalidated, "@scope/package-a"),
+            Some(String::from("1.10.0"))
+        );
+
+        let ref cache_path = get_packages_cache_path(root);
+        std::fs::write(cache_path, "{ corrupt")?;
+
+        let recovered = get_packages_with_cache(Some(root.to_string()), Some(true))?;
+
+        assert_eq!(discoveries() - discoveries_before, 3);
+        assert_eq!(recovered, invalidated);
+
+        std::fs::remove_file(cache_path)?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_packages_with_cache_new_workspace_dir() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+        let discoveries = || PACKAGE_DISCOVERIES.with(|discoveries| discoveries.get());
+        let names = |packages: &Vec<PackageInfo>| {
+            packages
+                .iter()
+                .map(|package| package.name.to_string())
+                .collect::<Vec<String>>()
+        };
+        let add_package = |dir: &str, name: &str| -> Result<(), std::io::Error> {
+            std::fs::create_dir_all(monorepo_dir.join(dir))?;
+            std::fs::write(
+                monorepo_dir.join(dir).join("package.json"),
+                format!(r#"{{ "name": "{}", "version": "0.1.0" }}"#, name),
+            )
+        };
+
+        // apps doesn't exist yet and tools is empty
+        let package_json_path = monorepo_dir.join("package.json");
+        let mut package_json =
+            serde_json::from_str::<Value>(&std::fs::read_to_string(&package_json_path)?)?;
+        package_json["workspaces"] = serde_json::json!(["packages/*", "apps/*", "tools/*"]);
+        std::fs::write(&package_json_path, package_json.to_string())?;
+        std::fs::create_dir_all(monorepo_dir.join("tools"))?;
+
+        let discoveries_before = discoveries();
+
+        let warm = get_packages_with_cache(Some(root.to_string()), Some(true))?;
+        get_packages_with_cache(Some(root.to_string()), Some(true))?;
+
+        assert_eq!(warm.len(), 4);
+        assert_eq!(discoveries() - discoveries_before, 1);
+
+        add_package("apps/web", "@scope/web")?;
+
+        let with_app = get_packages_with_cache(Some(root.to_string()), Some(true))?;
+
+        assert_eq!(discoveries() - discoveries_before, 2);
+        assert_eq!(names(&with_app).contains(&String::from("@scope/web")), true);
+
+        add_package("tools/cli", "@scope/cli")?;
+
+        let with_tool = get_packages_with_cache(Some(root.to_string()), Some(true))?;
+
+        assert_eq!(discoveries() - discoveries_before, 3);
+        assert_eq!(
+            names(&with_tool).contains(&String::from("@scope/cli")),
+            true
+        );
+
+        std::fs::remove_file(get_packages_cache_path(root))?;
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pnpm_workspace_packages() {
+        let content = r#"
+            # the workspace packages
+            packages:
+              - "packages/*"
+              - 'apps/**'
+              - "!**/test/**"
+            catalog:
+              react: ^18.0.0
+        "#;
+
+        assert_eq!(
+            parse_pnpm_workspace_packages(content),
+            vec![
+                String::from("packages/*"),
+                String::from("apps/**"),
+                String::from("!**/test/**")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workspace_package_json_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+
+        for dir in [
+            "packages/nested/deep",
+            "packages/package-a/node_modules/dep",
+            "tools/cli",
+            "apps/app",
+            "apps/app/plugins/extra",
+        ] {
+            std::fs::create_dir_all(monorepo_dir.join(dir))?;
+            File::create(monorepo_dir.join(dir).join("package.json"))?;
+        }
+
+        let all_packages = vec![
+            "packages/package-a",
+            "packages/package-b",
+            "packages/package-c",
+            "packages/package-d",
+        ];
+
+        let cases: Vec<(Vec<&str>, Vec<&str>)> = vec![
+            (vec!["packages/*"], all_packages.to_owned()),
+            (vec!["./packages/*/"], all_packages.to_owned()),
+            (
+                vec!["packages/**"],
+                [vec!["packages/nested/deep"], all_packages.to_owned()].concat(),
+            ),
+            (vec!["tools/cli/"], vec!["tools/cli"]),
+            (vec!["apps/app"], vec!["apps/app"]),
+            (vec!["apps/*/plugins/*"], vec!["apps/app/plugins/extra"]),
+            (
+                vec![
+                    "packages/*",
+                    "!packages/package-b",
+                    "! ./packages/package-c/",
+                ],
+                vec!["packages/package-a", "packages/package-d"],
+            ),
+            (
+                vec!["packages/**", "!packages/nested/**"],
+                all_packages.to_owned(),
+            ),
+        ];
+
+        for (workspaces, expected) in cases {
+            let workspaces = workspaces
+                .iter()
+                .map(|workspace| workspace.to_string())
+                .collect::<Vec<String>>();
+            let expected = expected
+                .iter()
+                .map(|dir| monorepo_dir.join(dir).join("package.json"))
+                .collect::<Vec<PathBuf>>();
+
+            assert_eq!(
+                get_workspace_package_json_paths(monorepo_dir, &workspaces),
+                expected,
+                "workspaces {:?}",
+                workspaces
+            );
+        }
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
     #[test]
     fn monorepo_get_changed_packages() -> Result<(), Box<dyn std::error::Error>> {
         let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
@@ -658,7 +1771,11 @@ mod tests {
 
         create_package_change(monorepo_dir)?;
 
-        let packages = get_changed_packages(Some("main".to_string()), project_root);
+        let packages = get_changed_packages(
+            Some("main".to_string()),
+            project_root,
+            &ChangedPackagesOptions::default(),
+        )?;
         let package = packages.first();
 
         let changed_files = package.unwrap().get_changed_files();
@@ -668,4 +1785,521 @@ mod tests {
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
+
+    #[test]
+    fn test_get_changed_packages_exclude_publish_directory(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        update_packages_field(
+            "publishConfig.directory",
+            Value::String(String::from("./dist/")),
+            Some(vec![String::from("@scope/package-a")]),
+            Some(root.to_string()),
+        )?;
+
+        let package_a = get_package_info(String::from("@scope/package-a"), Some(root.to_string()))?;
+        let package_b = get_package_info(String::from("@scope/package-b"), Some(root.to_string()))?;
+
+        assert_eq!(
+            package_a.unwrap().get_publish_directory(),
+            Some(String::from("dist"))
+        );
+        assert_eq!(package_b.unwrap().get_publish_directory(), None);
+
+        let commit_file = |file: &str, message: &str| -> Result<(), std::io::Error> {
+            let file_path = monorepo_dir.join(file);
+
+            std::fs::create_dir_all(file_path.parent().unwrap())?;
+            std::fs::write(file_path, r#"export const message = "hello";"#)?;
+
+            run_git(&monorepo_dir, &["add", "."])?;
+            run_git(&monorepo_dir, &["commit", "-m", message])?;
+
+            Ok(())
+        };
+
+        run_git(
+            &monorepo_dir,
+            &["commit", "-am", "chore: set the publish directory"],
+        )?;
+
+        let since = run_git(&monorepo_dir, &["rev-parse", "HEAD"])?;
+        let changed_names = |exclude_publish_directory: Option<bool>| {
+            get_changed_packages(
+                Some(since.to_string()),
+                Some(root.to_string()),
+                &ChangedPackagesOptions {
+                    exclude_publish_directory: exclude_publish_directory,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .iter()
+            .map(|package| package.name.to_string())
+            .collect::<Vec<String>>()
+        };
+
+        commit_file("packages/package-a/dist/index.mjs", "chore: build output")?;
+
+        assert_eq!(changed_names(None), vec!["@scope/package-a"]);
+        assert_eq!(changed_names(Some(true)), Vec::<String>::new());
+
+        commit_file("packages/package-a/index.mjs", "feat: source change")?;
+
+        assert_eq!(changed_names(Some(true)), vec!["@scope/package-a"]);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_packages_touched_by_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_package_change(monorepo_dir)?;
+
+        let head = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("rev-parse")
+            .arg("HEAD")
+            .output()?;
+        let head = String::from_utf8(head.stdout)?.trim().to_string();
+
+        assert_eq!(
+            get_packages_touched_by_commit(head, Some(root.to_string()))?,
+            vec!["@scope/package-a"]
+        );
+        assert_eq!(
+            get_packages_touched_by_commit(String::from("unknown"), Some(root.to_string()))?,
+            Vec::<String>::new()
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_changed_packages_nested() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let ref nested_dir = monorepo_dir.join("packages/package-a/nested");
+        std::fs::create_dir_all(nested_dir)?;
+        std::fs::write(
+            nested_dir.join("package.json"),
+            r#"{ "name": "@scope/package-nested", "version": "1.0.0" }"#,
+        )?;
+
+        let root_json_path = monorepo_dir.join("package.json");
+        let mut root_json =
+            serde_json::from_str::<Value>(&std::fs::read_to_string(&root_json_path)?)?;
+        root_json["workspaces"]
+            .as_array_mut()
+            .unwrap()
+            .push(Value::String(String::from("packages/package-a/nested")));
+        std::fs::write(&root_json_path, serde_json::to_string_pretty(&root_json)?)?;
+
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "chore: nested package"])?;
+
+        let since = run_git(&monorepo_dir, &["rev-parse", "HEAD"])?;
+
+        std::fs::write(
+            nested_dir.join("index.js"),
+            r#"export const message = "hello";"#,
+        )?;
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(&monorepo_dir, &["commit", "-m", "feat: nested message"])?;
+
+        let head = run_git(&monorepo_dir, &["rev-parse", "HEAD"])?;
+
+        let changed = get_changed_packages(
+            Some(since.to_string()),
+            Some(root.to_string()),
+            &ChangedPackagesOptions::default(),
+        )?;
+
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].name, String::from("@scope/package-nested"));
+        assert_eq!(
+            get_packages_touched_by_commit(head.to_string(), Some(root.to_string()))?,
+            vec!["@scope/package-nested"]
+        );
+
+        let packages = get_packages(Some(root.to_string()))?;
+        let mut package_a =
+            get_package_info(String::from("@scope/package-a"), Some(root.to_string()))?.unwrap();
+        package_a.extend_changed_files_of(
+            vec![
+                nested_dir.join("index.js").display().to_string(),
+                monorepo_dir
+                    .join("packages/package-a/index.js")
+                    .display()
+                    .to_string(),
+            ],
+            &packages,
+        );
+
+        assert_eq!(package_a.get_changed_files().len(), 1);
+        assert_eq!(
+            package_a.get_changed_files()[0].ends_with("packages/package-a/index.js"),
+            true
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_changed_packages_since_tag() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        create_package_change(monorepo_dir)?;
+
+        std::fs::write(
+            monorepo_dir.join("packages/package-c/index.js"),
+            r#"export const message = "hello";"#,
+        )?;
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("feat: message from package-c")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        let mut changed = get_changed_packages(
+            Some(String::from("@scope/package-a@1.0.0")),
+            Some(root.to_string()),
+            &ChangedPackagesOptions::default(),
+        )?
+        .iter()
+        .map(|package| package.name.to_string())
+        .collect::<Vec<String>>();
+        changed.sort();
+
+        assert_eq!(changed, vec!["@scope/package-a", "@scope/package-c"]);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_changed_packages_apply_gitignore() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        run_git(&monorepo_dir, &["checkout", "-b", "feat/build-output"])?;
+
+        std::fs::write(monorepo_dir.join(".gitignore"), "dist/\n")?;
+        std::fs::create_dir_all(monorepo_dir.join("packages/package-a/dist"))?;
+        std::fs::write(
+            monorepo_dir.join("packages/package-a/dist/bundle.js"),
+            r#"export const bundle = true;"#,
+        )?;
+        std::fs::write(
+            monorepo_dir.join("packages/package-b/index.js"),
+            r#"export const message = "hello";"#,
+        )?;
+
+        run_git(&monorepo_dir, &["add", "."])?;
+        run_git(
+            &monorepo_dir,
+            &["add", "-f", "packages/package-a/dist/bundle.js"],
+        )?;
+        run_git(
+            &monorepo_dir,
+            &["commit", "-m", "chore: commit build output"],
+        )?;
+
+        let changed_names = |apply_gitignore: Option<bool>| {
+            get_changed_packages(
+                Some("main".to_string()),
+                Some(root.to_string()),
+                &ChangedPackagesOptions {
+                    apply_gitignore: apply_gitignore,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .iter()
+            .map(|package| package.name.to_string())
+            .collect::<Vec<String>>()
+        };
+
+        let mut unfiltered = changed_names(None);
+        unfiltered.sort();
+
+        assert_eq!(unfiltered, vec!["@scope/package-a", "@scope/package-b"]);
+        assert_eq!(changed_names(Some(true)), vec!["@scope/package-b"]);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinked_package_changed_files() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+        let linked_dir = monorepo_dir.join("linked-a");
+
+        create_package_change(monorepo_dir)?;
+
+        std::os::unix::fs::symlink(monorepo_dir.join("packages/package-a"), &linked_dir)?;
+
+        let mut package_info =
+            get_package_info(String::from("@scope/package-a"), Some(root.to_string()))?.unwrap();
+        package_info.package_path = linked_dir.display().to_string();
+
+        let changed_files = get_all_files_changed_since_branch(
+            &vec![package_info.to_owned()],
+            &String::from("main"),
+            Some(root.to_string()),
+        );
+
+        assert_eq!(changed_files.len(), 1);
+        assert_eq!(
+            changed_files[0].ends_with("packages/package-a/index.js"),
+            true
+        );
+
+        package_info.extend_changed_files(vec![
+            changed_files[0].to_string(),
+            monorepo_dir
+                .join("packages/package-b/index.js")
+                .display()
+                .to_string(),
+        ]);
+
+        assert_eq!(package_info.get_changed_files(), changed_files);
+
+        let mut real_package_info =
+            get_package_info(String::from("@scope/package-a"), Some(root.to_string()))?.unwrap();
+        real_package_info
+            .extend_changed_files(vec![linked_dir.join("index.js").display().to_string()]);
+
+        assert_eq!(real_package_info.get_changed_files().len(), 1);
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_compute_package_hash() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let package_dir = monorepo_dir.join("packages/package-a");
+
+        std::fs::create_dir_all(package_dir.join("lib"))?;
+        std::fs::create_dir_all(package_dir.join("src"))?;
+        std::fs::create_dir_all(package_dir.join("node_modules/dep"))?;
+
+        let mut lib_file = File::create(package_dir.join("lib/index.js"))?;
+        lib_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+
+        let packages = get_packages(project_root)?;
+        let mut package = packages
+            .iter()
+            .find(|package| package.name == "@scope/package-a")
+            .unwrap()
+            .to_owned();
+
+        package.pkg_json["files"] = serde_json::json!(["lib"]);
+
+        let hash = compute_package_hash(&package);
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(compute_package_hash(&package), hash);
+
+        package.pkg_json["files"] = serde_json::json!(["lib", "src/[unclosed"]);
+
+        assert_eq!(compute_package_hash(&package), hash);
+
+        let mut src_file = File::create(package_dir.join("src/index.ts"))?;
+        src_file.write_all(r#"export const message = "hello";"#.as_bytes())?;
+        File::create(package_dir.join("node_modules/dep/index.js"))?;
+
+        assert_eq!(compute_package_hash(&package), hash);
+
+        let mut lib_file = File::create(package_dir.join("lib/index.js"))?;
+        lib_file.write_all(r#"export const message = "hello world";"#.as_bytes())?;
+
+        assert_ne!(compute_package_hash(&package), hash);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_packages_field() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+        let registry = serde_json::json!("https://registry.example.com");
+
+        let results = update_packages_field(
+            "publishConfig.registry",
+            registry.to_owned(),
+            Some(vec![
+                String::from("@scope/package-a"),
+                String::from("@scope/package-b"),
+            ]),
+            project_root.to_owned(),
+        )?;
+
+        assert_eq!(results.len(), 2);
+
+        for result in results.iter() {
+            assert_eq!(result.previous, None);
+            assert_eq!(result.current, Some(registry.to_owned()));
+
+            let package = get_package_info(result.package.to_string(), project_root.to_owned())?;
+
+            assert_eq!(
+                package.unwrap().pkg_json["publishConfig"]["registry"],
+                registry
+            );
+        }
+
+        let root_package_json = std::fs::read_to_string(monorepo_dir.join("package.json"))?;
+
+        assert_eq!(root_package_json.contains("publishConfig"), false);
+
+        let removed = update_packages_field(
+            "publishConfig.registry",
+            Value::Null,
+            Some(vec![String::from("@scope/package-a")]),
+            project_root.to_owned(),
+        )?;
+
+        assert_eq!(removed[0].previous, Some(registry));
+        assert_eq!(removed[0].current, None);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_publish_target() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let package =
+            get_package_info(String::from("@scope/package-a"), project_root.to_owned())?.unwrap();
+
+        let mut private_package = package.to_owned();
+        private_package.private = true;
+        private_package.pkg_json["publishConfig"] =
+            serde_json::json!({ "registry": "https://registry.example.com" });
+
+        let mut registry_package = package.to_owned();
+        registry_package.pkg_json["publishConfig"] =
+            serde_json::json!({ "registry": "https://registry.example.com" });
+
+        assert_eq!(publish_target(&package), PublishTarget::DefaultRegistry);
+        assert_eq!(publish_target(&private_package), PublishTarget::Private);
+        assert_eq!(
+            publish_target(&registry_package),
+            PublishTarget::Registry(String::from("https://registry.example.com"))
+        );
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_workspace_version() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let package = get_package_info(String::from("@scope/package-a"), project_root)?.unwrap();
+
+        assert_eq!(resolve_workspace_version("workspace:*", &package), "1.0.0");
+        assert_eq!(resolve_workspace_version("workspace:~", &package), "~1.0.0");
+        assert_eq!(resolve_workspace_version("workspace:^", &package), "^1.0.0");
+        assert_eq!(
+            resolve_workspace_version("workspace:^0.9.0", &package),
+            "^0.9.0"
+        );
+        assert_eq!(resolve_workspace_version("^1.0.0", &package), "^1.0.0");
+
+        let dependency = DependencyInfo {
+            name: String::from("@scope/package-a"),
+            version: String::from("workspace:^"),
+        };
+
+        assert_eq!(package.matches(&dependency), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_info_json_format() -> Result<(), Box<dyn std::error::Error>> {
+        let package = PackageInfo {
+            name: String::from("@scope/package-a"),
+            private: false,
+            package_json_path: String::from("/monorepo/packages/package-a/package.json"),
+            package_path: String::from("/monorepo/packages/package-a"),
+            package_relative_path: String::from("packages/package-a"),
+            pkg_json: serde_json::json!({ "name": "@scope/package-a", "version": "1.0.0" }),
+            root: false,
+            version: String::from("1.0.0"),
+            url: String::from("https://github.com/websublime/monorepo/packages/package-a"),
+            repository_info: Some(PackageRepositoryInfo {
+                domain: String::from("https://github.com"),
+                orga: String::from("websublime"),
+                project: String::from("monorepo"),
+            }),
+            changed_files: vec![],
+            dependencies: vec![DependencyInfo {
+                name: String::from("@scope/package-b"),
+                version: String::from("1.0.0"),
+            }],
+        };
+
+        let json = serde_json::json!({
+            "name": "@scope/package-a",
+            "private": false,
+            "package_json_path": "/monorepo/packages/package-a/package.json",
+            "package_path": "/monorepo/packages/package-a",
+            "package_relative_path": "packages/package-a",
+            "pkg_json": { "name": "@scope/package-a", "version": "1.0.0" },
+            "root": false,
+            "version": "1.0.0",
+            "url": "https://github.com/websublime/monorepo/packages/package-a",
+            "repository_info": {
+                "domain": "https://github.com",
+                "orga": "websublime",
+                "project": "monorepo"
+            },
+            "changed_files": [],
+            "dependencies": [{ "name": "@scope/package-b", "version": "1.0.0" }]
+        });
+
+        assert_eq!(serde_json::to_value(&package)?, json);
+        assert_eq!(serde_json::from_value::<PackageInfo>(json)?, package);
+        Ok(())
+    }
 }
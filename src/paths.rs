@@ -2,11 +2,14 @@
 
 //! #Paths module
 //!
-//! The `paths` module is used to get the project root path.
+//! The `paths` module is used to get the project root path and holds the path helpers shared
+//! by the changed files and packages mapping.
+use super::packages::PackageInfo;
 use super::utils::strip_trailing_newline;
 use execute::Execute;
 use std::{
     env,
+    fs::canonicalize,
     path::{Path, PathBuf},
     process::{Command, Stdio},
 };
@@ -69,6 +72,7 @@ fn walk_reverse_dir(path: &Path) -> Option<String> {
         ("yarn.lock", "yarn"),
         ("pnpm-lock.yaml", "pnpm"),
         ("bun.lockb", "bun"),
+        ("bun.lock", "bun"),
     ];
 
     for (file, _) in map_files.iter() {
@@ -86,6 +90,127 @@ fn walk_reverse_dir(path: &Path) -> Option<String> {
     None
 }
 
+/// Normalizes the separators of a path to `/`, collapsing repeated separators and dropping a
+/// trailing one (a root like `/` or `C:/` is kept).
+pub fn normalize_separators(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len());
+
+    for character in path.chars() {
+        let character = match character {
+            '\\' => '/',
+            _ => character,
+        };
+
+        if character == '/' && normalized.ends_with('/') {
+            continue;
+        }
+
+        normalized.push(character);
+    }
+
+    if normalized.len() > 1 && normalized.ends_with('/') && !normalized.ends_with(":/") {
+        normalized.pop();
+    }
+
+    normalized
+}
+
+/// Splits a path in segments with normalized separators and without `.` segments. Absolute
+/// paths start with an empty segment (`/`) or a drive segment (`C:`).
+fn path_segments(path: &Path) -> Vec<String> {
+    let path = normalize_separators(&path.to_string_lossy());
+
+    if path.is_empty() {
+        return vec![];
+    }
+
+    path.split('/')
+        .enumerate()
+        .filter(|(index, segment)| match index {
+            0 => *segment != ".",
+            _ => !segment.is_empty() && *segment != ".",
+        })
+        .map(|(_, segment)| segment.to_string())
+        .collect::<Vec<String>>()
+}
+
+/// Compares two path segments, ignoring the case on case insensitive file systems.
+fn same_segment(a: &String, b: &String) -> bool {
+    match cfg!(any(windows, target_os = "macos")) {
+        true => a.to_lowercase() == b.to_lowercase(),
+        false => a == b,
+    }
+}
+
+/// Segments of the path after the parent segments, `None` when the path isn't within it.
+fn strip_segments(parent: &Path, path: &Path) -> Option<Vec<String>> {
+    let parent = path_segments(parent);
+    let path = path_segments(path);
+
+    let is_prefix = parent.len() <= path.len()
+        && parent
+            .iter()
+            .zip(path.iter())
+            .all(|(parent, segment)| same_segment(parent, segment));
+
+    match is_prefix {
+        true => Some(path[parent.len()..].to_vec()),
+        false => None,
+    }
+}
+
+/// Resolves symlinks of a path. Paths that don't exist (e.g. deleted files) are
+/// resolved through their parent directory, falling back to the path itself.
+fn resolve_path(path: &Path) -> PathBuf {
+    match canonicalize(path) {
+        Ok(resolved) => resolved,
+        Err(_) => match (path.parent(), path.file_name()) {
+            (Some(parent), Some(file_name)) if !parent.as_os_str().is_empty() => {
+                resolve_path(parent).join(file_name)
+            }
+            _ => path.to_path_buf(),
+        },
+    }
+}
+
+/// Checks if a path is the parent directory or inside it. Paths are compared segment by
+/// segment, so `packages/ui-icons` isn't within `packages/ui`, case insensitive on windows
+/// and macos and, when they don't match, with symlinks resolved (e.g. symlinked packages).
+pub fn is_within(parent: &Path, child: &Path) -> bool {
+    strip_segments(parent, child).is_some()
+        || strip_segments(&resolve_path(parent), &resolve_path(child)).is_some()
+}
+
+/// Get the path relative to the workspace root with `/` separators, `None` when the path is
+/// outside the root. Relative paths are taken as already relative to the root.
+pub fn to_workspace_relative(path: &Path, root: &Path) -> Option<String> {
+    let segments = path_segments(path);
+    let is_absolute = match segments.first() {
+        Some(first) => first.is_empty() || first.ends_with(':'),
+        None => false,
+    };
+
+    if !is_absolute {
+        return Some(segments.join("/"));
+    }
+
+    strip_segments(root, path)
+        .or_else(|| strip_segments(&resolve_path(root), &resolve_path(path)))
+        .map(|segments| segments.join("/"))
+}
+
+/// Get the package a file belongs to: the package with the longest path containing the file,
+/// so a file of a nested package maps to the nested package.
+pub fn nearest_package_root<'a>(
+    file: &Path,
+    packages: &'a [PackageInfo],
+) -> Option<&'a PackageInfo> {
+    packages
+        .iter()
+        .filter(|package| is_within(Path::new(&package.package_path), file))
+        .max_by_key(|package| path_segments(Path::new(&package.package_path)).len())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,4 +309,125 @@ mod tests {
         remove_dir_all(&monorepo_dir)?;
         Ok(())
     }
+
+    fn package_at(name: &str, package_path: &str) -> PackageInfo {
+        PackageInfo {
+            name: name.to_string(),
+            private: false,
+            package_json_path: format!("{}/package.json", package_path),
+            package_path: package_path.to_string(),
+            package_relative_path: package_path.trim_start_matches("/repo/").to_string(),
+            pkg_json: serde_json::Value::Null,
+            root: false,
+            version: String::from("1.0.0"),
+            url: String::from("https://github.com/my-orga/my-repo"),
+            repository_info: None,
+            changed_files: vec![],
+            dependencies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_normalize_separators() {
+        assert_eq!(
+            normalize_separators("C:\\repo\\packages\\ui\\"),
+            String::from("C:/repo/packages/ui")
+        );
+        assert_eq!(
+            normalize_separators("packages//ui/"),
+            String::from("packages/ui")
+        );
+        assert_eq!(normalize_separators("/"), String::from("/"));
+        assert_eq!(normalize_separators("C:\\"), String::from("C:/"));
+    }
+
+    #[test]
+    fn test_is_within() {
+        assert_eq!(
+            is_within(
+                Path::new("/repo/packages/ui"),
+                Path::new("/repo/packages/ui/index.js")
+            ),
+            true
+        );
+        assert_eq!(
+            is_within(
+                Path::new("/repo/packages/ui"),
+                Path::new("/repo/packages/ui-icons/index.js")
+            ),
+            false
+        );
+        assert_eq!(
+            is_within(
+                Path::new("/repo/packages/ui/"),
+                Path::new("/repo/packages/ui")
+            ),
+            true
+        );
+        assert_eq!(
+            is_within(
+                Path::new("C:\\repo\\packages\\ui"),
+                Path::new("C:\\repo\\packages\\ui\\src\\index.js")
+            ),
+            true
+        );
+        assert_eq!(
+            is_within(
+                Path::new("C:\\repo\\packages\\ui"),
+                Path::new("C:\\repo\\packages\\ui-icons\\index.js")
+            ),
+            false
+        );
+    }
+
+    #[test]
+    fn test_to_workspace_relative() {
+        assert_eq!(
+            to_workspace_relative(Path::new("/repo/packages/ui/index.js"), Path::new("/repo/")),
+            Some(String::from("packages/ui/index.js"))
+        );
+        assert_eq!(
+            to_workspace_relative(
+                Path::new("C:\\repo\\packages\\ui\\index.js"),
+                Path::new("C:\\repo")
+            ),
+            Some(String::from("packages/ui/index.js"))
+        );
+        assert_eq!(
+            to_workspace_relative(Path::new("./packages\\ui/"), Path::new("/repo")),
+            Some(String::from("packages/ui"))
+        );
+        assert_eq!(
+            to_workspace_relative(Path::new("/repo-icons/index.js"), Path::new("/repo")),
+            None
+        );
+        assert_eq!(
+            to_workspace_relative(Path::new("/repo"), Path::new("/repo")),
+            Some(String::new())
+        );
+    }
+
+    #[test]
+    fn test_nearest_package_root() {
+        let packages = vec![
+            package_at("@scope/ui", "/repo/packages/ui"),
+            package_at("@scope/ui-icons", "/repo/packages/ui-icons"),
+            package_at("@scope/ui-nested", "/repo/packages/ui/nested"),
+        ];
+
+        let package_of = |file: &str| {
+            nearest_package_root(Path::new(file), &packages).map(|package| package.name.as_str())
+        };
+
+        assert_eq!(package_of("/repo/packages/ui/index.js"), Some("@scope/ui"));
+        assert_eq!(
+            package_of("/repo/packages/ui-icons/index.js"),
+            Some("@scope/ui-icons")
+        );
+        assert_eq!(
+            package_of("/repo/packages/ui/nested/index.js"),
+            Some("@scope/ui-nested")
+        );
+        assert_eq!(package_of("/repo/README.md"), None);
+    }
 }
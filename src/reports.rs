@@ -0,0 +1,818 @@
+#![allow(clippy::all)]
+
+//! # Reports
+//!
+//! This module is responsible for building read only reports about the releases in the monorepo.
+use chrono::{DateTime, NaiveDate};
+use execute::Execute;
+use regex::Regex;
+use semver::Version as SemVersion;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::bumps::{
+    check_version_tag_alignment, validate_version_progression, Bump, VersionAlignment,
+};
+use super::changes::{changes_file_exist, changes_status, BranchChangesStatus};
+use super::config::read_release_config;
+use super::errors::WorkspaceError;
+use super::git::{
+    get_commit_count_since, get_commits_since,
+    get_last_known_publish_tag_info_for_package_with_policy, Commit, CommitsSinceOptions,
+    PrereleasePolicy,
+};
+use super::manager::PackageManagerError;
+use super::packages::get_packages;
+use super::paths::get_project_root_path;
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct BumpHistoryEntry {
+    pub package: String,
+    pub from: String,
+    pub to: String,
+    pub level: Bump,
+    pub tag: String,
+    pub date: String,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents a release of a package compared with the previous one.
+/// Prerelease only deltas are classified as `Bump::Snapshot`.
+pub struct BumpHistoryEntry {
+    pub package: String,
+    pub from: String,
+    pub to: String,
+    pub level: Bump,
+    pub tag: String,
+    pub date: String,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct BumpHistory {
+    pub entries: Vec<BumpHistoryEntry>,
+    pub skipped_tags: u32,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents the bump history of the monorepo.
+/// `skipped_tags` counts the package tags whose version could not be parsed.
+pub struct BumpHistory {
+    pub entries: Vec<BumpHistoryEntry>,
+    pub skipped_tags: u32,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ReleaseReadinessOptions {
+    pub changes_path: Option<String>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub tag_format: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Options of the release readiness report. The last release of a package is its latest
+/// publish tag of the `tag_format` (the release config one by default) the
+/// `prerelease_policy` allows.
+pub struct ReleaseReadinessOptions {
+    pub changes_path: Option<String>,
+    pub prerelease_policy: Option<PrereleasePolicy>,
+    pub tag_format: Option<String>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CommitTypeCount {
+    pub commit_type: String,
+    pub count: u32,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// Number of commits of a conventional commit type, `other` for non conventional commits.
+pub struct CommitTypeCount {
+    pub commit_type: String,
+    pub count: u32,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PackageReadiness {
+    pub package: String,
+    pub last_release: Option<String>,
+    pub commit_types: Vec<CommitTypeCount>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A package with pending changes, its latest publish tag version (`None` when never released)
+/// and its commits since the last release counted by type.
+pub struct PackageReadiness {
+    pub package: String,
+    pub last_release: Option<String>,
+    pub commit_types: Vec<CommitTypeCount>,
+}
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ReleaseReadiness {
+    pub changes_file: bool,
+    pub branches: Vec<BranchChangesStatus>,
+    pub packages: Vec<PackageReadiness>,
+    pub warnings: Vec<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// The release readiness of the monorepo: the pending changes of every branch, the packages
+/// involved and the warnings that could block the release. Branches and packages are sorted
+/// by name.
+pub struct ReleaseReadiness {
+    pub changes_file: bool,
+    pub branches: Vec<BranchChangesStatus>,
+    pub packages: Vec<PackageReadiness>,
+    pub warnings: Vec<String>,
+}
+
+/// A local tag with its creation date.
+struct DatedTag {
+    tag: String,
+    date: String,
+}
+
+/// Get all local tags with their creation date (iso strict format).
+fn get_dated_tags(cwd: &String) -> Vec<DatedTag> {
+    let mut command = Command::new("git");
+    command
+        .arg("for-each-ref")
+        .arg("--format=%(refname:strip=2)%09%(creatordate:iso-strict)")
+        .arg("refs/tags");
+
+    command.current_dir(cwd);
+
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let output = command.execute_output().unwrap();
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let (tag, date) = line.split_once('\t')?;
+
+            Some(DatedTag {
+                tag: tag.to_string(),
+                date: date.to_string(),
+            })
+        })
+        .collect::<Vec<DatedTag>>()
+}
+
+/// Parses the since date, accepting a rfc3339 date time or a plain `YYYY-MM-DD` date.
+fn parse_since_date(since_date: &String) -> Option<i64> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(since_date) {
+        return Some(date.timestamp());
+    }
+
+    NaiveDate::parse_from_str(since_date, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc().timestamp())
+}
+
+/// Classifies the delta between two consecutive versions.
+fn classify_bump(from: &SemVersion, to: &SemVersion) -> Bump {
+    if from.major != to.major {
+        Bump::Major
+    } else if from.minor != to.minor {
+        Bump::Minor
+    } else if from.patch != to.patch {
+        Bump::Patch
+    } else {
+        Bump::Snapshot
+    }
+}
+
+/// Get the bump history of every package in the monorepo, derived from the publish tags.
+/// Consecutive versions of a package are paired and the delta classified. When `since_date`
+/// is provided, only releases tagged on or after that date are returned.
+pub fn get_bump_history(
+    cwd: Option<String>,
+    since_date: Option<String>,
+) -> Result<BumpHistory, PackageManagerError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let since_timestamp = since_date.as_ref().and_then(parse_since_date);
+    let tags = get_dated_tags(root);
+    let packages = get_packages(Some(root.to_string()))?;
+
+    let mut entries = vec![];
+    let mut skipped_tags = 0;
+
+    for package in packages.iter() {
+        let prefix = format!("{}@", package.name);
+
+        let mut versions = tags
+            .iter()
+            .filter(|dated_tag| dated_tag.tag.starts_with(&prefix))
+            .filter_map(|dated_tag| {
+                let version = dated_tag.tag.strip_prefix(&prefix).unwrap();
+
+                match SemVersion::parse(version) {
+                    Ok(version) => Some((version, dated_tag)),
+                    Err(_) => {
+                        skipped_tags += 1;
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<(SemVersion, &DatedTag)>>();
+
+        versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for pair in versions.windows(2) {
+            let (from, _) = &pair[0];
+            let (to, to_tag) = &pair[1];
+
+            if let Some(since) = since_timestamp {
+                let tag_timestamp = DateTime::parse_from_rfc3339(&to_tag.date)
+                    .map(|date| date.timestamp())
+                    .unwrap_or(0);
+
+                if tag_timestamp < since {
+                    continue;
+                }
+            }
+
+            entries.push(BumpHistoryEntry {
+                package: package.name.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+                level: classify_bump(from, to),
+                tag: to_tag.tag.to_string(),
+                date: to_tag.date.to_string(),
+            });
+        }
+    }
+
+    Ok(BumpHistory {
+        entries,
+        skipped_tags,
+    })
+}
+
+/// Get the number of unreleased commits of every package, i.e. the commits touching the
+/// package path since its last publish tag (of the release config tag format). Packages never
+/// released count all their commits. Fails when the packages or the publish tags can't be listed.
+pub fn get_unreleased_commit_counts(
+    cwd: Option<String>,
+) -> Result<HashMap<String, usize>, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref tag_format = read_release_config(Some(root.to_string()))
+        .unwrap_or_default()
+        .tag_format;
+
+    get_packages(Some(root.to_string()))?
+        .iter()
+        .map(|package| {
+            let since = get_last_known_publish_tag_info_for_package_with_policy(
+                package,
+                Some(root.to_string()),
+                None,
+                tag_format,
+            )?
+            .map(|tag_info| tag_info.hash);
+
+            let count = get_commit_count_since(
+                Some(root.to_string()),
+                since,
+                Some(package.package_relative_path.to_string()),
+            );
+
+            Ok((package.name.to_string(), count))
+        })
+        .collect::<Result<HashMap<String, usize>, WorkspaceError>>()
+}
+
+/// Counts the commits by conventional commit type, sorted by type.
+fn count_commit_types(commits: &Vec<Commit>) -> Vec<CommitTypeCount> {
+    let type_regex = Regex::new(r"^(?P<type>[a-zA-Z]+)(?:\([^)]*\))?!?:").unwrap();
+
+    let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+
+    for commit in commits {
+        let commit_type = type_regex
+            .captures(&commit.subject)
+            .map(|captures| captures["type"].to_lowercase())
+            .unwrap_or(String::from("other"));
+
+        *counts.entry(commit_type).or_insert(0) += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|(commit_type, count)| CommitTypeCount { commit_type, count })
+        .collect::<Vec<CommitTypeCount>>()
+}
+
+/// Get the release readiness of the monorepo, composing the changes status, the version tag
+/// alignment and the commits of every package with a pending change since its last release.
+/// A missing changes file or never released packages aren't errors, they are reported as is.
+/// Fails when the packages or the publish tags can't be listed.
+pub fn get_release_readiness(
+    cwd: Option<String>,
+    options: &Option<ReleaseReadinessOptions>,
+) -> Result<ReleaseReadiness, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let changes_path = options
+        .as_ref()
+        .and_then(|options| options.changes_path.to_owned());
+    let prerelease_policy = options
+        .as_ref()
+        .and_then(|options| options.prerelease_policy);
+    let ref tag_format = options
+        .as_ref()
+        .and_then(|options| options.tag_format.to_owned())
+        .or_else(|| {
+            read_release_config(Some(root.to_string()))
+                .unwrap_or_default()
+                .tag_format
+        });
+
+    let changes_file = changes_file_exist(Some(root.to_string()), changes_path.to_owned());
+
+    let mut branches = match changes_file {
+        true => changes_status(Some(root.to_string()), changes_path)?.branches,
+        false => vec![],
+    };
+    branches.sort_by(|a, b| a.branch.cmp(&b.branch));
+
+    let alignments = check_version_tag_alignment(Some(root.to_string()))?;
+    let packages = get_packages(Some(root.to_string()))?;
+
+    let mut warnings = vec![];
+
+    for branch in &branches {
+        for change in &branch.packages {
+            let next_version = change
+                .next_version
+                .as_ref()
+                .and_then(|version| SemVersion::parse(version).ok());
+
+            match (change.current_version.as_ref(), next_version) {
+                (Some(current_version), Some(next_version)) => {
+                    if let Err(error) = validate_version_progression(
+                        &change.package,
+                        current_version,
+                        &next_version,
+                        change.release_as,
+                    ) {
+                        warnings.push(format!("{} (branch {})", error, branch.branch));
+                    }
+                }
+                (Some(current_version), None) => warnings.push(format!(
+                    "Package {} has an invalid version {} (branch {})",
+                    change.package, current_version, branch.branch
+                )),
+                (None, _) => warnings.push(format!(
+                    "Package {} is not in the monorepo (branch {})",
+                    change.package, branch.branch
+                )),
+            }
+        }
+    }
+
+    let mut pending_packages = branches
+        .iter()
+        .flat_map(|branch| branch.packages.iter())
+        .filter(|change| change.current_version.is_some())
+        .map(|change| change.package.to_string())
+        .collect::<Vec<String>>();
+    pending_packages.sort();
+    pending_packages.dedup();
+
+    let packages = pending_packages
+        .iter()
+        .filter_map(|name| packages.iter().find(|package| &package.name == name))
+        .map(|package| {
+            let alignment = alignments
+                .iter()
+                .find(|alignment| alignment.package == package.name);
+
+            match alignment.map(|alignment| alignment.alignment) {
+                Some(VersionAlignment::ManifestBehind) => warnings.push(format!(
+                    "Package {} version {} is behind its latest tag version {}",
+                    package.name,
+                    package.version,
+                    alignment
+                        .unwrap()
+                        .tag_version
+                        .to_owned()
+                        .unwrap_or_default()
+                )),
+                Some(VersionAlignment::ManifestAhead) => warnings.push(format!(
+                    "Package {} version {} is ahead of its latest tag version {}",
+                    package.name,
+                    package.version,
+                    alignment
+                        .unwrap()
+                        .tag_version
+                        .to_owned()
+                        .unwrap_or_default()
+                )),
+                _ => {}
+            }
+
+            let since = get_last_known_publish_tag_info_for_package_with_policy(
+                package,
+                Some(root.to_string()),
+                prerelease_policy,
+                tag_format,
+            )?
+            .map(|tag_info| tag_info.hash);
+            let commits = get_commits_since(
+                Some(root.to_string()),
+                since,
+                Some(package.package_relative_path.to_string()),
+                &CommitsSinceOptions::default(),
+            );
+
+            Ok(PackageReadiness {
+                package: package.name.to_string(),
+                last_release: alignment.and_then(|alignment| alignment.tag_version.to_owned()),
+                commit_types: count_commit_types(&commits),
+            })
+        })
+        .collect::<Result<Vec<PackageReadiness>, WorkspaceError>>()?;
+
+    Ok(ReleaseReadiness {
+        changes_file,
+        branches,
+        packages,
+        warnings,
+    })
+}
+
+/// Render the release readiness of the monorepo (see `get_release_readiness`) as a markdown
+/// document. Sections always come in the same order: pending releases, commits since the last
+/// release, never released packages and warnings.
+pub fn render_release_readiness(
+    cwd: Option<String>,
+    options: &Option<ReleaseReadinessOptions>,
+) -> Result<String, WorkspaceError> {
+    let readiness = get_release_readiness(cwd, options)?;
+
+    let mut sections = vec![String::from("# Release readiness")];
+
+    let mut pending = vec![String::from("## Pending releases")];
+
+    match (readiness.changes_file, readiness.branches.is_empty()) {
+        (false, _) => pending.push(String::from(
+            "_No changes file found, there are no pending changes._",
+        )),
+        (true, true) => pending.push(String::from("_No pending changes._")),
+        (true, false) => {
+            let mut table = vec![
+                String::from("| Branch | Package | Release as | From | To |"),
+                String::from("| --- | --- | --- | --- | --- |"),
+            ];
+
+            for branch in &readiness.branches {
+                for change in &branch.packages {
+                    table.push(format!(
+                        "| {} | {} | {} | {} | {} |",
+                        branch.branch,
+                        change.package,
+                        format!("{:?}", change.release_as).to_lowercase(),
+                        change.current_version.as_deref().unwrap_or("-"),
+                        change.next_version.as_deref().unwrap_or("-"),
+                    ));
+                }
+            }
+
+            pending.push(table.join("\n"));
+        }
+    }
+    sections.push(pending.join("\n\n"));
+
+    let mut commits = vec![String::from("## Commits since last release")];
+
+    match readiness.packages.is_empty() {
+        true => commits.push(String::from("_No packages to release._")),
+        false => {
+            let mut table = vec![
+                String::from("| Package | Last release | Commits |"),
+                String::from("| --- | --- | --- |"),
+            ];
+
+            for package in &readiness.packages {
+                let commit_types = package
+                    .commit_types
+                    .iter()
+                    .map(|count| format!("{}: {}", count.commit_type, count.count))
+                    .collect::<Vec<String>>();
+
+                table.push(format!(
+                    "| {} | {} | {} |",
+                    package.package,
+                    package.last_release.as_deref().unwrap_or("-"),
+                    match commit_types.is_empty() {
+                        true => String::from("-"),
+                        false => commit_types.join(", "),
+                    }
+                ));
+            }
+
+            commits.push(table.join("\n"));
+        }
+    }
+    sections.push(commits.join("\n\n"));
+
+    let never_released = readiness
+        .packages
+        .iter()
+        .filter(|package| package.last_release.is_none())
+        .map(|package| format!("- {}", package.package))
+        .collect::<Vec<String>>();
+
+    let mut first_releases = vec![String::from("## Never released")];
+
+    match never_released.is_empty() {
+        true => first_releases.push(String::from(
+            "_Every package to release has a publish tag._",
+        )),
+        false => first_releases.push(never_released.join("\n")),
+    }
+    sections.push(first_releases.join("\n\n"));
+
+    let mut warnings = vec![String::from("## Warnings")];
+
+    match readiness.warnings.is_empty() {
+        true => warnings.push(String::from("_No warnings._")),
+        false => warnings.push(
+            readiness
+                .warnings
+                .iter()
+                .map(|warning| format!("- {}", warning))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        ),
+    }
+    sections.push(warnings.join("\n\n"));
+
+    Ok(format!("{}\n", sections.join("\n\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::changes::{add_change, init_changes, Change};
+    use crate::manager::PackageManager;
+    use crate::utils::create_test_monorepo;
+    use std::fs::{remove_dir_all, File};
+    use std::io::Write;
+
+    fn create_package_release(monorepo_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        let js_path = monorepo_dir.join("packages/package-a/index.js");
+
+        let mut js_file = File::create(&js_path)?;
+        js_file
+            .write_all(r#"export const message = "hello";"#.as_bytes())
+            .unwrap();
+
+        let add = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("add")
+            .arg(".")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git add problem");
+
+        add.wait_with_output()?;
+
+        let commit = Command::new("git")
+            .current_dir(&monorepo_dir)
+            .arg("commit")
+            .arg("-m")
+            .arg("feat: message to the world")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Git commit problem");
+
+        commit.wait_with_output()?;
+
+        for tag in ["@scope/package-a@1.1.0", "@scope/package-a@not-a-version"] {
+            let tag_a = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("tag")
+                .arg("-a")
+                .arg(tag)
+                .arg("-m")
+                .arg(format!("chore: release {}", tag))
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git tag problem");
+
+            tag_a.wait_with_output()?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_bump_history() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_package_release(monorepo_dir)?;
+
+        let history = get_bump_history(project_root, None)?;
+        let entry = history.entries.first().unwrap();
+
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.skipped_tags, 1);
+        assert_eq!(entry.package, String::from("@scope/package-a"));
+        assert_eq!(entry.from, String::from("1.0.0"));
+        assert_eq!(entry.to, String::from("1.1.0"));
+        assert_eq!(entry.level, Bump::Minor);
+        assert_eq!(entry.tag, String::from("@scope/package-a@1.1.0"));
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_bump_history_since_date() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        create_package_release(monorepo_dir)?;
+
+        let history = get_bump_history(project_root, Some(String::from("2999-01-01")))?;
+
+        assert_eq!(history.entries.len(), 0);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_unreleased_commit_counts() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        for file in ["index.js", "utils.js"] {
+            File::create(monorepo_dir.join("packages/package-a").join(file))?;
+
+            let add = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("add")
+                .arg(".")
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git add problem");
+
+            add.wait_with_output()?;
+
+            let commit = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .arg("commit")
+                .arg("-m")
+                .arg(format!("feat: add {}", file))
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git commit problem");
+
+            commit.wait_with_output()?;
+        }
+
+        let counts = get_unreleased_commit_counts(project_root)?;
+
+        assert_eq!(counts.get("@scope/package-a"), Some(&2));
+        assert_eq!(counts.get("@scope/package-b"), Some(&0));
+        // package-d was never released, so the creation commit is unreleased
+        assert_eq!(counts.get("@scope/package-d"), Some(&1));
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_release_readiness() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        File::create(monorepo_dir.join("packages/package-a/index.js"))?;
+
+        for args in [
+            vec!["add", "."],
+            vec!["commit", "-m", "feat: message to the world"],
+        ] {
+            let command = Command::new("git")
+                .current_dir(&monorepo_dir)
+                .args(args)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Git command problem");
+
+            command.wait_with_output()?;
+        }
+
+        init_changes(Some(root.to_string()), &None);
+        add_change(
+            &Change {
+                package: String::from("@scope/package-a"),
+                release_as: Bump::Minor,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            },
+            Some(root.to_string()),
+            None,
+        );
+        add_change(
+            &Change {
+                package: String::from("@scope/package-d"),
+                release_as: Bump::Patch,
+                deploy: vec![String::from("production")],
+                exact_version: None,
+            },
+            Some(root.to_string()),
+            None,
+        );
+
+        let readiness = get_release_readiness(Some(root.to_string()), &None)?;
+        let report = render_release_readiness(Some(root.to_string()), &None)?;
+
+        assert_eq!(readiness.changes_file, true);
+        assert_eq!(readiness.packages.len(), 2);
+        assert_eq!(
+            readiness.packages[0].last_release,
+            Some(String::from("1.0.0"))
+        );
+        assert_eq!(readiness.packages[1].last_release, None);
+        assert_eq!(
+            report.starts_with("# Release readiness\n\n## Pending releases"),
+            true
+        );
+        assert_eq!(
+            report.contains("| @scope/package-a | minor | 1.0.0 | 1.1.0 |"),
+            true
+        );
+        assert_eq!(
+            report.contains("| @scope/package-a | 1.0.0 | feat: 1 |"),
+            true
+        );
+        assert_eq!(
+            report.contains("## Never released\n\n- @scope/package-d\n"),
+            true
+        );
+        assert_eq!(report.ends_with("## Warnings\n\n_No warnings._\n"), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_release_readiness_without_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let readiness = get_release_readiness(project_root.to_owned(), &None)?;
+        let report = render_release_readiness(project_root, &None)?;
+
+        assert_eq!(readiness.changes_file, false);
+        assert_eq!(readiness.branches.len(), 0);
+        assert_eq!(report.contains("_No changes file found"), true);
+        assert_eq!(report.contains("_No packages to release._"), true);
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+}
@@ -28,26 +28,34 @@ use std::process::{Command, Stdio};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Package scope metadata extracted from a package name.
+/// `prerelease` and `build` are the segments of the version after `-` and `+`
+/// (e.g. `rc.1` and `build.5` in `@scope/pkg@1.0.0-rc.1+build.5`).
 pub struct PackageScopeMetadata {
     pub full: String,
     pub name: String,
     pub version: String,
+    pub prerelease: Option<String>,
+    pub build: Option<String>,
     pub path: Option<String>,
 }
 
 /// Extracts the package scope name and version from a package name.
 pub(crate) fn package_scope_name_version(pkg_name: &str) -> Option<PackageScopeMetadata> {
-    let regex = Regex::new("^((?:@[^/@]+/)?[^/@]+)(?:@([^/]+))?(/.*)?$").unwrap();
+    let regex =
+        Regex::new("^((?:@[^/@]+/)?[^/@]+)(?:@([^/+-]+(?:-([^/+]+))?(?:\\+([^/]+))?))?(/.*)?$")
+            .unwrap();
 
-    let matches = regex.captures(pkg_name).unwrap();
+    let matches = regex.captures(pkg_name)?;
 
     if matches.len() > 0 {
         return Some(PackageScopeMetadata {
             full: matches.get(0).map_or("", |m| m.as_str()).to_string(),
             name: matches.get(1).map_or("", |m| m.as_str()).to_string(),
             version: matches.get(2).map_or("", |m| m.as_str()).to_string(),
+            prerelease: matches.get(3).map(|m| m.as_str().to_string()),
+            build: matches.get(4).map(|m| m.as_str().to_string()),
             path: matches
-                .get(3)
+                .get(5)
                 .map_or(None, |m| Some(m.as_str().to_string())),
         });
     }
@@ -420,9 +428,120 @@ pub(crate) fn create_test_monorepo(
     Ok(PathBuf::from(root))
 }
 
+/// Clones a test monorepo (through a file:// url) into a new temp directory.
+#[cfg(test)]
+pub(crate) fn clone_test_monorepo(monorepo_dir: &PathBuf) -> Result<PathBuf, std::io::Error> {
+    let rand_string: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(30)
+        .map(char::from)
+        .collect();
+
+    let clone_temp_dir = std::env::temp_dir().join(format!("monorepo-clone-{}", rand_string));
+    let url = format!("file://{}", monorepo_dir.display());
+
+    let clone_dir = super::git::git_clone(&url, &clone_temp_dir, &Default::default())
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))?;
+
+    Ok(PathBuf::from(clone_dir))
+}
+
+/// Runs git with the given args in a test monorepo, returning its trimmed stdout. Fails with
+/// the stderr when git exits with a non-zero status.
+#[cfg(test)]
+pub(crate) fn run_git<P: AsRef<Path>>(dir: P, args: &[&str]) -> Result<String, std::io::Error> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    //use super::*;
+    use super::{create_test_monorepo, package_scope_name_version, run_git};
+    use crate::manager::PackageManager;
+    use std::fs::remove_dir_all;
+
+    #[test]
+    fn test_package_scope_name_version() {
+        let metadata = package_scope_name_version("@scope/pkg@1.0.0").unwrap();
+
+        assert_eq!(metadata.name, "@scope/pkg");
+        assert_eq!(metadata.version, "1.0.0");
+        assert_eq!(metadata.prerelease, None);
+        assert_eq!(metadata.build, None);
+        assert_eq!(metadata.path, None);
+
+        let metadata = package_scope_name_version("pkg@2.0.0/dist/index.js").unwrap();
+
+        assert_eq!(metadata.name, "pkg");
+        assert_eq!(metadata.version, "2.0.0");
+        assert_eq!(metadata.path, Some(String::from("/dist/index.js")));
+    }
+
+    #[test]
+    fn test_package_scope_name_version_prerelease() {
+        let metadata = package_scope_name_version("@scope/pkg@1.0.0-rc.1").unwrap();
+
+        assert_eq!(metadata.name, "@scope/pkg");
+        assert_eq!(metadata.version, "1.0.0-rc.1");
+        assert_eq!(metadata.prerelease, Some(String::from("rc.1")));
+        assert_eq!(metadata.build, None);
+
+        let metadata = package_scope_name_version("@scope/pkg@1.0.0-alpha-2.3").unwrap();
+
+        assert_eq!(metadata.version, "1.0.0-alpha-2.3");
+        assert_eq!(metadata.prerelease, Some(String::from("alpha-2.3")));
+    }
+
+    #[test]
+    fn test_package_scope_name_version_build_metadata() {
+        let metadata = package_scope_name_version("@scope/pkg@1.0.0-rc.1+build.5").unwrap();
+
+        assert_eq!(metadata.name, "@scope/pkg");
+        assert_eq!(metadata.version, "1.0.0-rc.1+build.5");
+        assert_eq!(metadata.prerelease, Some(String::from("rc.1")));
+        assert_eq!(metadata.build, Some(String::from("build.5")));
+        assert_eq!(metadata.path, None);
+
+        let metadata = package_scope_name_version("@scope/pkg@1.0.0+20240101").unwrap();
+
+        assert_eq!(metadata.version, "1.0.0+20240101");
+        assert_eq!(metadata.prerelease, None);
+        assert_eq!(metadata.build, Some(String::from("20240101")));
+    }
+
+    #[test]
+    fn test_run_git_fails_on_error_status() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+
+        assert_eq!(
+            run_git(monorepo_dir, &["rev-parse", "--abbrev-ref", "HEAD"])?,
+            "main"
+        );
+
+        let error = run_git(monorepo_dir, &["checkout", "unknown-branch"]).unwrap_err();
+
+        assert_eq!(error.to_string().contains("unknown-branch"), true);
+
+        remove_dir_all(monorepo_dir)?;
+        Ok(())
+    }
 
     #[test]
     fn test_parse_package_json() {
@@ -0,0 +1,216 @@
+#![allow(clippy::all)]
+
+//! # Versions
+//!
+//! This module maintains an optional versions manifest, a single json file in the root of the
+//! project mapping every package name to its current version (by default `versions.json`).
+//! Releases keep it in sync, so tooling can read the versions without parsing every package.json.
+//!
+//! # Example
+//! ```json
+//! {
+//!   "@scope/package-a": "1.1.0",
+//!   "@scope/package-b": "1.0.0"
+//! }
+//!```
+use serde::{Deserialize, Serialize};
+use std::io::{BufWriter, Write};
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, rename, File},
+    path::{Path, PathBuf},
+};
+
+use crate::errors::WorkspaceError;
+
+use super::packages::get_packages;
+use super::paths::get_project_root_path;
+
+/// Default path of the versions manifest, relative to the project root.
+pub const DEFAULT_VERSIONS_MANIFEST: &str = "versions.json";
+
+#[cfg(feature = "napi")]
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct VersionDrift {
+    pub package: String,
+    pub manifest_version: Option<String>,
+    pub package_version: Option<String>,
+}
+
+#[cfg(not(feature = "napi"))]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A struct that represents a package whose manifest version differs from its package.json.
+/// A missing `manifest_version` means the package isn't in the manifest and a missing
+/// `package_version` means the manifest lists a package that doesn't exist.
+pub struct VersionDrift {
+    pub package: String,
+    pub manifest_version: Option<String>,
+    pub package_version: Option<String>,
+}
+
+/// Resolves the manifest path, relative to the project root.
+fn get_versions_manifest_path(root: &String, manifest_path: &Option<String>) -> PathBuf {
+    Path::new(root).join(
+        manifest_path
+            .to_owned()
+            .unwrap_or(String::from(DEFAULT_VERSIONS_MANIFEST)),
+    )
+}
+
+/// Reads the versions manifest, package name to version.
+pub fn read_versions_manifest(
+    cwd: Option<String>,
+    manifest_path: Option<String>,
+) -> Result<BTreeMap<String, String>, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref path = get_versions_manifest_path(root, &manifest_path);
+
+    if !path.is_file() {
+        return Err(WorkspaceError::FileNotFound(path.display().to_string()));
+    }
+
+    let content = read_to_string(path)?;
+
+    serde_json::from_str::<BTreeMap<String, String>>(&content)
+        .map_err(|error| WorkspaceError::Io(error.into()))
+}
+
+/// Sets the given versions in the manifest and returns its path. When the manifest doesn't
+/// exist yet it's created with the current versions of all packages first.
+pub fn update_versions_manifest(
+    versions: &BTreeMap<String, String>,
+    cwd: Option<String>,
+    manifest_path: Option<String>,
+) -> Result<PathBuf, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let ref path = get_versions_manifest_path(root, &manifest_path);
+
+    let mut manifest = match path.is_file() {
+        true => read_versions_manifest(Some(root.to_string()), manifest_path)?,
+        false => get_packages(Some(root.to_string()))?
+            .iter()
+            .map(|package| (package.name.to_string(), package.version.to_string()))
+            .collect::<BTreeMap<String, String>>(),
+    };
+
+    manifest.extend(versions.to_owned());
+
+    // Written to a temporary file first so a failed write never leaves a partial manifest
+    let file_name = path.file_name().unwrap().to_str().unwrap();
+    let ref temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let temp_file = File::create(temp_path)?;
+    let mut temp_writer = BufWriter::new(temp_file);
+
+    serde_json::to_writer_pretty(&mut temp_writer, &manifest)
+        .map_err(|error| WorkspaceError::Io(error.into()))?;
+    temp_writer.flush()?;
+
+    rename(temp_path, path)?;
+
+    Ok(path.to_path_buf())
+}
+
+/// Compares the versions manifest with the package.json versions, returning the drifts.
+pub fn verify_versions_manifest(
+    cwd: Option<String>,
+    manifest_path: Option<String>,
+) -> Result<Vec<VersionDrift>, WorkspaceError> {
+    let ref root = match cwd {
+        Some(ref dir) => get_project_root_path(Some(PathBuf::from(dir))).unwrap(),
+        None => get_project_root_path(None).unwrap(),
+    };
+
+    let manifest = read_versions_manifest(Some(root.to_string()), manifest_path)?;
+    let packages = get_packages(Some(root.to_string()))?
+        .iter()
+        .map(|package| (package.name.to_string(), package.version.to_string()))
+        .collect::<BTreeMap<String, String>>();
+
+    let mut names = manifest
+        .keys()
+        .chain(packages.keys())
+        .collect::<Vec<&String>>();
+    names.sort();
+    names.dedup();
+
+    Ok(names
+        .iter()
+        .filter_map(|name| {
+            let manifest_version = manifest.get(*name);
+            let package_version = packages.get(*name);
+
+            match manifest_version == package_version {
+                true => None,
+                false => Some(VersionDrift {
+                    package: name.to_string(),
+                    manifest_version: manifest_version.map(|version| version.to_string()),
+                    package_version: package_version.map(|version| version.to_string()),
+                }),
+            }
+        })
+        .collect::<Vec<VersionDrift>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::manager::PackageManager;
+    use crate::utils::create_test_monorepo;
+    use std::fs::{remove_dir_all, write};
+
+    #[test]
+    fn test_verify_versions_manifest() -> Result<(), Box<dyn std::error::Error>> {
+        let ref monorepo_dir = create_test_monorepo(&PackageManager::Npm)?;
+        let project_root = get_project_root_path(Some(monorepo_dir.to_path_buf()));
+
+        let ref root = project_root.unwrap().to_string();
+
+        let missing = read_versions_manifest(Some(root.to_string()), None);
+
+        assert_eq!(
+            matches!(missing, Err(WorkspaceError::FileNotFound(_))),
+            true
+        );
+
+        update_versions_manifest(&BTreeMap::new(), Some(root.to_string()), None)?;
+
+        let manifest = read_versions_manifest(Some(root.to_string()), None)?;
+
+        assert_eq!(manifest.len(), 4);
+        assert_eq!(
+            verify_versions_manifest(Some(root.to_string()), None)?,
+            vec![]
+        );
+
+        let manifest_path = monorepo_dir.join(DEFAULT_VERSIONS_MANIFEST);
+        let hand_edited = read_to_string(&manifest_path)?.replace(
+            r#""@scope/package-c": "1.0.0""#,
+            r#""@scope/package-c": "1.2.0""#,
+        );
+
+        write(&manifest_path, hand_edited)?;
+
+        assert_eq!(
+            verify_versions_manifest(Some(root.to_string()), None)?,
+            vec![VersionDrift {
+                package: String::from("@scope/package-c"),
+                manifest_version: Some(String::from("1.2.0")),
+                package_version: Some(String::from("1.0.0")),
+            }]
+        );
+
+        remove_dir_all(&monorepo_dir)?;
+        Ok(())
+    }
+}
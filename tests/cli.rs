@@ -0,0 +1,347 @@
+#![cfg(feature = "cli")]
+#![allow(clippy::all)]
+
+use assert_cmd::Command;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use serde_json::Value;
+use std::fs::{canonicalize, create_dir_all, remove_dir_all, write};
+use std::path::PathBuf;
+use std::process::Command as ProcessCommand;
+
+/// Creates a npm monorepo with `@scope/package-a` (depending on `@scope/package-b`) and
+/// `@scope/package-b`, both at 1.0.0 and tagged, on the main branch.
+fn create_monorepo() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let rand_string: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(30)
+        .map(char::from)
+        .collect();
+
+    let monorepo_dir = std::env::temp_dir().join(format!("monorepo-cli-{}", rand_string));
+
+    create_dir_all(monorepo_dir.join("packages/package-a"))?;
+    create_dir_all(monorepo_dir.join("packages/package-b"))?;
+
+    write(
+        monorepo_dir.join("package.json"),
+        r#"{ "name": "@scope/root", "version": "0.0.0", "workspaces": ["packages/*"] }"#,
+    )?;
+    write(monorepo_dir.join("package-lock.json"), "")?;
+    write(
+        monorepo_dir.join("packages/package-a/package.json"),
+        r#"{ "name": "@scope/package-a", "version": "1.0.0", "dependencies": { "@scope/package-b": "1.0.0" } }"#,
+    )?;
+    write(
+        monorepo_dir.join("packages/package-b/package.json"),
+        r#"{ "name": "@scope/package-b", "version": "1.0.0" }"#,
+    )?;
+
+    let monorepo_dir = canonicalize(&monorepo_dir)?;
+
+    git(&monorepo_dir, &["init", "--initial-branch", "main"]);
+    git(
+        &monorepo_dir,
+        &["config", "user.email", "machine@websublime.dev"],
+    );
+    git(&monorepo_dir, &["config", "user.name", "Sublime Machine"]);
+    git(&monorepo_dir, &["add", "."]);
+    git(&monorepo_dir, &["commit", "-m", "feat: project creation"]);
+
+    for package in ["@scope/package-a", "@scope/package-b"] {
+        let tag = format!("{}@1.0.0", package);
+        git(&monorepo_dir, &["tag", "-a", &tag, "-m", &tag]);
+    }
+
+    Ok(monorepo_dir)
+}
+
+fn git(monorepo_dir: &PathBuf, args: &[&str]) {
+    ProcessCommand::new("git")
+        .current_dir(monorepo_dir)
+        .args(args)
+        .output()
+        .expect("Git problem");
+}
+
+fn cli(monorepo_dir: &PathBuf) -> Command {
+    let mut command = Command::cargo_bin("workspace-tools").unwrap();
+    command.arg("--cwd").arg(monorepo_dir);
+    command
+}
+
+fn json_output(command: &mut Command) -> Value {
+    let output = command
+        .arg("--json")
+        .assert()
+        .success()
+        .get_output()
+        .to_owned();
+
+    serde_json::from_slice::<Value>(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_cli_packages_list() -> Result<(), Box<dyn std::error::Error>> {
+    let ref monorepo_dir = create_monorepo()?;
+
+    let packages = json_output(cli(monorepo_dir).args(["packages", "list"]));
+    let mut names = packages
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|package| package["name"].as_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+    names.sort();
+
+    assert_eq!(names, vec!["@scope/package-a", "@scope/package-b"]);
+
+    let table = cli(monorepo_dir)
+        .args(["packages", "list"])
+        .assert()
+        .success()
+        .get_output()
+        .to_owned();
+    let table = String::from_utf8(table.stdout)?;
+
+    assert_eq!(table.starts_with("NAME"), true);
+    assert_eq!(table.contains("packages/package-b"), true);
+
+    remove_dir_all(&monorepo_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cli_changed_and_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let ref monorepo_dir = create_monorepo()?;
+
+    git(monorepo_dir, &["checkout", "-b", "feat/message"]);
+    write(
+        monorepo_dir.join("packages/package-b/index.js"),
+        r#"export const message = "hello";"#,
+    )?;
+    git(monorepo_dir, &["add", "."]);
+    git(
+        monorepo_dir,
+        &["commit", "-m", "feat: message to the world"],
+    );
+
+    let changed = json_output(cli(monorepo_dir).args(["changed", "--since", "main"]));
+
+    assert_eq!(changed.as_array().unwrap().len(), 1);
+    assert_eq!(changed[0]["name"], "@scope/package-b");
+
+    cli(monorepo_dir)
+        .args([
+            "changes",
+            "add",
+            "@scope/package-b",
+            "--release-as",
+            "minor",
+        ])
+        .assert()
+        .success();
+
+    let changes = json_output(cli(monorepo_dir).args(["changes", "show"]));
+
+    assert_eq!(changes[0]["package"], "@scope/package-b");
+    assert_eq!(changes[0]["release_as"], "Minor");
+
+    let plan = json_output(cli(monorepo_dir).args(["bump", "plan"]));
+    let planned = &plan["branches"][0]["packages"][0];
+
+    assert_eq!(planned["current_version"], "1.0.0");
+    assert_eq!(planned["next_version"], "1.1.0");
+
+    cli(monorepo_dir)
+        .args(["changes", "remove", "feat/message"])
+        .assert()
+        .success();
+
+    let changes = json_output(cli(monorepo_dir).args(["changes", "show"]));
+
+    assert_eq!(changes.as_array().unwrap().len(), 0);
+
+    remove_dir_all(&monorepo_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cli_changelog() -> Result<(), Box<dyn std::error::Error>> {
+    let ref monorepo_dir = create_monorepo()?;
+
+    write(
+        monorepo_dir.join("packages/package-b/index.js"),
+        r#"export const message = "hello";"#,
+    )?;
+    git(monorepo_dir, &["add", "."]);
+    git(
+        monorepo_dir,
+        &["commit", "-m", "feat: message to the world"],
+    );
+
+    let changelog = cli(monorepo_dir)
+        .args(["changelog", "@scope/package-b", "--version", "1.1.0"])
+        .assert()
+        .success()
+        .get_output()
+        .to_owned();
+    let changelog = String::from_utf8(changelog.stdout)?;
+
+    assert_eq!(changelog.contains("## [1.1.0]"), true);
+    assert_eq!(changelog.contains("Message to the world"), true);
+    assert_eq!(
+        monorepo_dir
+            .join("packages/package-b/CHANGELOG.md")
+            .exists(),
+        false
+    );
+
+    remove_dir_all(&monorepo_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cli_bump_apply() -> Result<(), Box<dyn std::error::Error>> {
+    let ref monorepo_dir = create_monorepo()?;
+
+    git(monorepo_dir, &["checkout", "-b", "feat/message"]);
+    write(
+        monorepo_dir.join("packages/package-b/index.js"),
+        r#"export const message = "hello";"#,
+    )?;
+    git(monorepo_dir, &["add", "."]);
+    git(
+        monorepo_dir,
+        &["commit", "-m", "feat: message to the world"],
+    );
+
+    cli(monorepo_dir)
+        .args([
+            "changes",
+            "add",
+            "@scope/package-b",
+            "--release-as",
+            "minor",
+        ])
+        .assert()
+        .success();
+
+    git(monorepo_dir, &["add", "."]);
+    git(monorepo_dir, &["commit", "-m", "chore: add change"]);
+    git(monorepo_dir, &["checkout", "main"]);
+    git(monorepo_dir, &["merge", "feat/message"]);
+
+    let bumps = json_output(cli(monorepo_dir).args([
+        "bump",
+        "apply",
+        "--branch",
+        "feat/message",
+        "--since",
+        "main",
+    ]));
+
+    assert_eq!(bumps.as_array().unwrap().len(), 1);
+    assert_eq!(bumps[0]["package_info"]["name"], "@scope/package-b");
+    assert_eq!(bumps[0]["to"], "1.1.0");
+
+    let tags = ProcessCommand::new("git")
+        .current_dir(monorepo_dir)
+        .args(["tag", "--list", "@scope/package-b@1.1.0"])
+        .output()?;
+
+    assert_eq!(
+        String::from_utf8(tags.stdout)?.trim(),
+        "@scope/package-b@1.1.0"
+    );
+
+    remove_dir_all(&monorepo_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cli_bump_apply_invalid_exact_version() -> Result<(), Box<dyn std::error::Error>> {
+    let ref monorepo_dir = create_monorepo()?;
+
+    git(monorepo_dir, &["checkout", "-b", "feat/message"]);
+    write(
+        monorepo_dir.join("packages/package-b/index.js"),
+        r#"export const message = "hello";"#,
+    )?;
+    git(monorepo_dir, &["add", "."]);
+    git(
+        monorepo_dir,
+        &["commit", "-m", "feat: message to the world"],
+    );
+
+    cli(monorepo_dir)
+        .args(["changes", "add", "@scope/package-b"])
+        .assert()
+        .success();
+
+    let ref changes_path = monorepo_dir.join(".changes.json");
+    let changes = std::fs::read_to_string(changes_path)?;
+
+    assert_eq!(changes.contains(r#""exact_version": null"#), true);
+
+    write(
+        changes_path,
+        changes.replace(r#""exact_version": null"#, r#""exact_version": "latest""#),
+    )?;
+
+    // Snapshots never apply the exact version, the change is released from main
+    git(monorepo_dir, &["add", "."]);
+    git(monorepo_dir, &["commit", "-m", "chore: add change"]);
+    git(monorepo_dir, &["checkout", "main"]);
+    git(monorepo_dir, &["merge", "feat/message"]);
+
+    let output = cli(monorepo_dir)
+        .args([
+            "bump",
+            "apply",
+            "--branch",
+            "feat/message",
+            "--since",
+            "main",
+        ])
+        .assert()
+        .failure()
+        .code(1)
+        .get_output()
+        .to_owned();
+
+    assert_eq!(
+        String::from_utf8(output.stderr)?.trim(),
+        "error: Invalid exact version: latest"
+    );
+
+    remove_dir_all(&monorepo_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cli_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let ref monorepo_dir = create_monorepo()?;
+
+    let output = cli(monorepo_dir)
+        .args(["changes", "add", "@scope/unknown"])
+        .assert()
+        .failure()
+        .code(1)
+        .get_output()
+        .to_owned();
+
+    assert_eq!(
+        String::from_utf8(output.stderr)?.trim(),
+        "error: Package not found: @scope/unknown"
+    );
+
+    cli(monorepo_dir)
+        .args(["changes", "remove", "feat/unknown"])
+        .assert()
+        .failure()
+        .code(1);
+
+    remove_dir_all(&monorepo_dir)?;
+    Ok(())
+}